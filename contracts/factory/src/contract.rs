@@ -1,13 +1,24 @@
 use crate::{
     error::ContractError,
-    stake_contract::StakedResponse,
+    stake_contract::{StakedResponse, WithdrawableRewardsResponse},
     storage::{
-        get_blend_wasm_hash, get_config, get_lp_by_tuple_v2, get_lp_vec, get_stable_wasm_hash,
-        save_blend_wasm_hash, save_config, save_lp_vec, save_lp_vec_with_tuple_as_key,
-        save_lp_vec_with_tuple_v2_as_key, save_stable_wasm_hash, Asset, Config, LiquidityPoolInfo,
-        LpPortfolio, PairTupleKey, StakePortfolio, UserPortfolio, ADMIN, FACTORY_KEY,
-        PENDING_ADMIN,
+        add_pool_for_token, get_blend_wasm_hash, get_config, get_contract_version,
+        get_lp_by_tuple_v2, get_lp_by_tuple_v3, get_lp_vec, get_permissionless_config,
+        get_pool_count, get_pool_deprecation, get_pool_position, get_pool_registry_info,
+        get_pool_template, get_pools, get_pools_for_token, get_stable_wasm_hash, get_stake_pool,
+        get_token_info, get_token_pool_count, get_token_pool_position, get_token_position,
+        get_tokens, init_pool_registry, remove_lp_routing, save_blend_wasm_hash, save_config,
+        save_contract_version, save_lp_vec_with_tuple_as_key, save_lp_vec_with_tuple_v2_as_key,
+        save_lp_vec_with_tuple_v3_as_key, save_permissionless_config, save_pool,
+        save_pool_deprecation, save_pool_registry_info, save_pool_template, save_stable_wasm_hash,
+        save_stake_pool, save_token_info, Asset, Config, ContractVersion, InitialLiquidity,
+        LiquidityPoolInfo, LpPortfolio, PairTupleKey, PermissionlessPoolConfig, PoolDeprecation,
+        PoolInfoResponse, PoolParamsOverrides, PoolRegistryInfo, PoolTemplate, PoolsDetailsPage,
+        PoolsPage, StakePortfolio, TokenInfo, UpgradeResult, UserPortfolio, ValuedAsset,
+        ValuedLpPosition, ValuedStakePosition, ValuedUserPortfolio, ADMIN, FACTORY_KEY,
+        MAX_QUERY_LIMIT, PENDING_ADMIN,
     },
+    token_contract,
     utils::deploy_and_initialize_multihop_contract,
     ConvertVec,
 };
@@ -21,7 +32,7 @@ use phoenix::{
 };
 use soroban_sdk::{
     contract, contractimpl, contractmeta, log, panic_with_error, vec, xdr::ToXdr, Address, Bytes,
-    BytesN, Env, IntoVal, String, Symbol, Val, Vec,
+    BytesN, Env, IntoVal, InvokeError, String, Symbol, Val, Vec,
 };
 
 // Metadata that is added on to the WASM custom section
@@ -32,6 +43,8 @@ pub struct Factory;
 
 #[allow(dead_code)]
 pub trait FactoryTrait {
+    /// Creates a pool. With `initial_liquidity`, the sender provides it right
+    /// after the deploy, in the same call.
     fn create_liquidity_pool(
         env: Env,
         sender: Address,
@@ -42,6 +55,21 @@ pub trait FactoryTrait {
         amp: Option<u64>,
         default_slippage_bps: i64,
         max_allowed_fee_bps: i64,
+        initial_liquidity: Option<InitialLiquidity>,
+    ) -> Address;
+
+    /// Creates a pool of `pool_type` with the parameters of its template,
+    /// overridden by the fields set in `overrides`.
+    fn create_pool_from_template(
+        env: Env,
+        sender: Address,
+        token_a: Address,
+        token_b: Address,
+        pool_type: PoolType,
+        overrides: PoolParamsOverrides,
+        share_token_name: String,
+        share_token_symbol: String,
+        initial_liquidity: Option<InitialLiquidity>,
     ) -> Address;
 
     fn update_config(
@@ -55,12 +83,83 @@ pub trait FactoryTrait {
         lp_token_decimals: Option<u32>,
     ) -> Result<Config, ContractError>;
 
+    /// Sets the default parameters of new pools of `pool_type`.
+    fn set_pool_template(env: Env, pool_type: PoolType, template: PoolTemplate);
+
+    fn query_pool_template(env: Env, pool_type: PoolType) -> Option<PoolTemplate>;
+
+    /// Updates the config of a pool administered by the factory. The swap fee
+    /// is the fee tier the pool is routed and deployed by, so it can't be
+    /// changed. Stable pools have no referral fee and reject
+    /// `max_referral_bps`.
+    fn update_pool_config(
+        env: Env,
+        pool: Address,
+        total_fee_bps: Option<i64>,
+        fee_recipient: Option<Address>,
+        max_allowed_slippage_bps: Option<i64>,
+        max_allowed_spread_bps: Option<i64>,
+        max_referral_bps: Option<i64>,
+    );
+
+    /// Sets whether and under which conditions accounts outside of the
+    /// whitelist can create Xyk pools.
+    fn update_permissionless_config(
+        env: Env,
+        permissionless_config: PermissionlessPoolConfig,
+    ) -> Result<(), ContractError>;
+
+    /// Lists a token, or updates its entry when already listed. The
+    /// decimals must match the ones of the token contract.
+    fn set_token_info(env: Env, token_info: TokenInfo);
+
+    /// Adds the pools created before the token index to it, `limit` pools of
+    /// the registry from the `start` position. Returns the number of pools
+    /// indexed.
+    fn backfill_pools_for_tokens(env: Env, start: u32, limit: u32) -> u32;
+
+    fn query_token(env: Env, token: Address) -> Option<TokenInfo>;
+
+    /// Up to `limit` listed tokens after `start_after`, in listing order.
+    fn query_tokens(env: Env, start_after: Option<Address>, limit: u32) -> Vec<TokenInfo>;
+
+    /// Up to `limit` pools with `token` as one of their assets after
+    /// `start_after`, in creation order, deprecated ones included.
+    fn query_pools_for_token(
+        env: Env,
+        token: Address,
+        start_after: Option<Address>,
+        limit: u32,
+    ) -> Vec<Address>;
+
     fn query_pools(env: Env) -> Vec<Address>;
 
     fn query_pool_details(env: Env, pool_address: Address) -> LiquidityPoolInfo;
 
     fn query_all_pools_details(env: Env) -> Vec<LiquidityPoolInfo>;
 
+    /// Paginated sibling of `query_pools`: scans up to `limit` pools created
+    /// after `start_after` and returns the ones of `pool_type` and/or with
+    /// `token` as one of their assets. Pools of a token are scanned through
+    /// the pools-by-token index.
+    fn query_pools_paginated(
+        env: Env,
+        start_after: Option<Address>,
+        limit: u32,
+        pool_type: Option<PoolType>,
+        token: Option<Address>,
+    ) -> PoolsPage;
+
+    /// Paginated sibling of `query_all_pools_details`, with the same
+    /// pagination and filters as `query_pools_paginated`.
+    fn query_pools_details_paginated(
+        env: Env,
+        start_after: Option<Address>,
+        limit: u32,
+        pool_type: Option<PoolType>,
+        token: Option<Address>,
+    ) -> PoolsDetailsPage;
+
     fn query_for_pool_by_token_pair(env: Env, token_a: Address, token_b: Address) -> Address;
 
     /// Type-aware sibling of `query_for_pool_by_token_pair`. Resolves a pool
@@ -74,12 +173,47 @@ pub trait FactoryTrait {
         pool_type: PoolType,
     ) -> Address;
 
+    /// Fee-tier-aware sibling of `query_pool_by_pair_type`. Resolves the pool
+    /// of `pool_type` for `(token_a, token_b)` created with the `fee_tier`
+    /// swap fee. Pools that pre-date the V3 key resolve when their swap fee
+    /// matches the requested tier.
+    fn query_pool_by_pair_type_and_tier(
+        env: Env,
+        token_a: Address,
+        token_b: Address,
+        pool_type: PoolType,
+        fee_tier: i64,
+    ) -> Address;
+
     fn get_admin(env: Env) -> Address;
 
     fn get_config(env: Env) -> Config;
 
+    fn query_permissionless_config(env: Env) -> Option<PermissionlessPoolConfig>;
+
     fn query_user_portfolio(env: Env, sender: Address, staking: bool) -> UserPortfolio;
 
+    /// Paginated sibling of `query_user_portfolio`, covering up to `limit`
+    /// pools created after `start_after`.
+    fn query_user_portfolio_paginated(
+        env: Env,
+        sender: Address,
+        staking: bool,
+        start_after: Option<Address>,
+        limit: u32,
+    ) -> UserPortfolio;
+
+    /// Values the LP positions, staked LP positions and pending stake rewards
+    /// of `sender` in `quote_token`, at the spot price of the pool of each
+    /// token against it. Paginated like `query_user_portfolio_paginated`.
+    fn query_user_portfolio_valued(
+        env: Env,
+        sender: Address,
+        quote_token: Address,
+        start_after: Option<Address>,
+        limit: u32,
+    ) -> ValuedUserPortfolio;
+
     fn migrate_admin_key(env: Env) -> Result<(), ContractError>;
 
     fn propose_admin(
@@ -91,6 +225,53 @@ pub trait FactoryTrait {
     fn revoke_admin_change(env: Env) -> Result<(), ContractError>;
 
     fn accept_admin(env: Env) -> Result<Address, ContractError>;
+
+    /// Upgrades the pools of `pool_type` among the `limit` pools of the
+    /// registry from position `start`. A pool failing to upgrade doesn't stop
+    /// the batch, its result is reported instead.
+    fn upgrade_pools(
+        env: Env,
+        pool_type: PoolType,
+        new_wasm_hash: BytesN<32>,
+        start: u32,
+        limit: u32,
+    ) -> Vec<UpgradeResult>;
+
+    /// Same as `upgrade_pools` for the stake contracts of those pools.
+    fn upgrade_stakes(
+        env: Env,
+        pool_type: PoolType,
+        new_wasm_hash: BytesN<32>,
+        start: u32,
+        limit: u32,
+    ) -> Vec<UpgradeResult>;
+
+    fn query_contract_version(env: Env, contract: Address) -> Option<ContractVersion>;
+
+    /// Removes a pool from the routing lookups, so multihop no longer trades
+    /// through it, while keeping it in the registry for withdrawals. The
+    /// replacement, if any, must hold the same tokens and becomes the default
+    /// pool of its type when none is left.
+    fn deprecate_pool(env: Env, pool: Address, replacement: Option<Address>, pause_swaps: bool);
+
+    fn query_pool_deprecation(env: Env, pool: Address) -> Option<PoolDeprecation>;
+
+    /// Pool whose LP shares `stake` holds. Known for the pools created or
+    /// upgraded with `upgrade_stakes` since the index was introduced.
+    fn query_pool_by_stake_contract(env: Env, stake: Address) -> Option<Address>;
+
+    /// Withdraws `share_amount` LP shares of the sender from a deprecated pool
+    /// and provides the withdrawn tokens to its replacement. Returns the
+    /// withdrawn amounts.
+    fn migrate_liquidity(
+        env: Env,
+        sender: Address,
+        pool: Address,
+        share_amount: i128,
+        min_a: i128,
+        min_b: i128,
+        custom_slippage_bps: Option<i64>,
+    ) -> (i128, i128);
 }
 
 #[contractimpl]
@@ -105,121 +286,93 @@ impl FactoryTrait for Factory {
         amp: Option<u64>,
         default_slippage_bps: i64,
         max_allowed_fee_bps: i64,
+        initial_liquidity: Option<InitialLiquidity>,
     ) -> Address {
         sender.require_auth();
         env.storage()
             .instance()
             .extend_ttl(INSTANCE_RENEWAL_THRESHOLD, INSTANCE_TARGET_TTL);
 
-        validate_pool_info(&pool_type, &amp);
+        create_pool(
+            &env,
+            &sender,
+            lp_init_info,
+            share_token_name,
+            share_token_symbol,
+            pool_type,
+            amp,
+            default_slippage_bps,
+            max_allowed_fee_bps,
+            initial_liquidity,
+        )
+    }
+
+    fn create_pool_from_template(
+        env: Env,
+        sender: Address,
+        token_a: Address,
+        token_b: Address,
+        pool_type: PoolType,
+        overrides: PoolParamsOverrides,
+        share_token_name: String,
+        share_token_symbol: String,
+        initial_liquidity: Option<InitialLiquidity>,
+    ) -> Address {
+        sender.require_auth();
+        env.storage()
+            .instance()
+            .extend_ttl(INSTANCE_RENEWAL_THRESHOLD, INSTANCE_TARGET_TTL);
 
-        if !get_config(&env).whitelisted_accounts.contains(sender) {
+        let template = get_pool_template(&env, pool_type).unwrap_or_else(|| {
             log!(
                 &env,
-                "Factory: Create Liquidity Pool: You are not authorized to create liquidity pool!"
+                "Factory: Create Pool From Template: No template for this pool type"
             );
-            panic_with_error!(&env, ContractError::NotAuthorized);
-        };
-
-        validate_token_info(
-            &env,
-            &lp_init_info.token_init_info,
-            &lp_init_info.stake_init_info,
-        );
-
-        let config = get_config(&env);
-        let stake_wasm_hash = config.stake_wasm_hash;
-        let token_wasm_hash = config.token_wasm_hash;
+            panic_with_error!(&env, ContractError::PoolTemplateNotSet);
+        });
 
-        validate_bps!(
-            lp_init_info.swap_fee_bps,
-            lp_init_info.max_allowed_slippage_bps,
-            lp_init_info.max_allowed_spread_bps,
-            lp_init_info.max_referral_bps,
+        let default_slippage_bps = overrides
+            .default_slippage_bps
+            .unwrap_or(template.default_slippage_bps);
+        let lp_init_info = LiquidityPoolInitInfo {
+            admin: overrides
+                .admin
+                .unwrap_or_else(|| env.current_contract_address()),
+            fee_recipient: overrides.fee_recipient.unwrap_or(template.fee_recipient),
+            max_allowed_slippage_bps: overrides
+                .max_allowed_slippage_bps
+                .unwrap_or(template.max_allowed_slippage_bps),
             default_slippage_bps,
-            max_allowed_fee_bps
-        );
+            max_allowed_spread_bps: overrides
+                .max_allowed_spread_bps
+                .unwrap_or(template.max_allowed_spread_bps),
+            swap_fee_bps: overrides.swap_fee_bps.unwrap_or(template.swap_fee_bps),
+            max_referral_bps: overrides
+                .max_referral_bps
+                .unwrap_or(template.max_referral_bps),
+            token_init_info: TokenInitInfo { token_a, token_b },
+            stake_init_info: StakeInitInfo {
+                min_bond: overrides.min_bond.unwrap_or(template.min_bond),
+                min_reward: overrides.min_reward.unwrap_or(template.min_reward),
+                manager: overrides.stake_manager.unwrap_or(template.stake_manager),
+                max_complexity: overrides.max_complexity.unwrap_or(template.max_complexity),
+            },
+        };
 
-        let factory_addr = env.current_contract_address();
-        let mut init_fn_args: Vec<Val> = (
-            stake_wasm_hash,
-            token_wasm_hash,
-            lp_init_info.clone(),
-            factory_addr,
+        create_pool(
+            &env,
+            &sender,
+            lp_init_info,
             share_token_name,
             share_token_symbol,
+            pool_type,
+            overrides.amp.or(template.amp),
+            default_slippage_bps,
+            overrides
+                .max_allowed_fee_bps
+                .unwrap_or(template.max_allowed_fee_bps),
+            initial_liquidity,
         )
-            .into_val(&env);
-
-        if matches!(pool_type, PoolType::Xyk | PoolType::Blend) {
-            init_fn_args.push_back(default_slippage_bps.into_val(&env));
-        }
-
-        if let PoolType::Stable = pool_type {
-            init_fn_args.push_back(amp.unwrap().into_val(&env));
-        }
-
-        init_fn_args.push_back(max_allowed_fee_bps.into_val(&env));
-
-        // Salt formula: legacy `sha256(token_a || token_b)` for Xyk/Stable
-        // (preserves on-chain addresses of existing pools), but Blend pools
-        // prefix with the pool-type discriminant so they get a fresh, distinct
-        // deterministic address — letting Xyk and Blend coexist for the same
-        // unordered pair.
-        let mut salt = Bytes::new(&env);
-        if let PoolType::Blend = pool_type {
-            salt.append(&Bytes::from_array(&env, &[PoolType::Blend as u8]));
-        }
-        salt.append(&lp_init_info.token_init_info.token_a.clone().to_xdr(&env));
-        salt.append(&lp_init_info.token_init_info.token_b.clone().to_xdr(&env));
-        let salt = env.crypto().sha256(&salt);
-
-        let lp_contract_address = match pool_type {
-            PoolType::Xyk => env
-                .deployer()
-                .with_current_contract(salt)
-                .deploy_v2(config.lp_wasm_hash, init_fn_args.clone()),
-            PoolType::Stable => env
-                .deployer()
-                .with_current_contract(salt)
-                .deploy_v2(get_stable_wasm_hash(&env), init_fn_args),
-            PoolType::Blend => env
-                .deployer()
-                .with_current_contract(salt)
-                .deploy_v2(get_blend_wasm_hash(&env), init_fn_args),
-        };
-
-        let mut lp_vec = get_lp_vec(&env);
-
-        lp_vec.push_back(lp_contract_address.clone());
-
-        save_lp_vec(&env, lp_vec);
-        let token_a = &lp_init_info.token_init_info.token_a;
-        let token_b = &lp_init_info.token_init_info.token_b;
-
-        // Storage policy:
-        // * Xyk: keep writing the legacy `PairTupleKey { a, b }` slot so any
-        //   client that still calls `query_for_pool_by_token_pair(a, b)` (no
-        //   `pool_type` arg) keeps resolving the Xyk pool — preserving the
-        //   semantics of all pools that pre-date this V2 key.
-        // * Stable: writes legacy too (no Stable pools coexist with anything
-        //   else on the current factory; same backwards-compat reasoning).
-        // * Blend: writes ONLY the V2 slot so it never clobbers an existing
-        //   legacy entry pointing at a different-type pool for the same pair.
-        // All three also write the explicit V2 slot so the type-aware query
-        // returns the correct pool unambiguously.
-        match pool_type {
-            PoolType::Xyk | PoolType::Stable => {
-                save_lp_vec_with_tuple_as_key(&env, (token_a, token_b), &lp_contract_address);
-            }
-            PoolType::Blend => {}
-        }
-        save_lp_vec_with_tuple_v2_as_key(&env, pool_type, (token_a, token_b), &lp_contract_address);
-
-        env.events()
-            .publish(("create", "liquidity_pool"), &lp_contract_address);
-
-        lp_contract_address
     }
 
     fn update_config(
@@ -286,6 +439,248 @@ impl FactoryTrait for Factory {
         Ok(config)
     }
 
+    fn set_pool_template(env: Env, pool_type: PoolType, template: PoolTemplate) {
+        get_config(&env).admin.require_auth();
+        env.storage()
+            .instance()
+            .extend_ttl(INSTANCE_RENEWAL_THRESHOLD, INSTANCE_TARGET_TTL);
+
+        validate_pool_info(&pool_type, &template.amp);
+        validate_bps!(
+            template.swap_fee_bps,
+            template.max_allowed_slippage_bps,
+            template.default_slippage_bps,
+            template.max_allowed_spread_bps,
+            template.max_referral_bps,
+            template.max_allowed_fee_bps
+        );
+        if template.min_bond <= 0 {
+            log!(
+                &env,
+                "Factory: Set Pool Template: Minimum amount of lp share tokens to bond can not be smaller or equal to 0"
+            );
+            panic_with_error!(&env, ContractError::MinStakeInvalid);
+        }
+        if template.min_reward <= 0 {
+            log!(
+                &env,
+                "Factory: Set Pool Template: min_reward must be bigger then 0!"
+            );
+            panic_with_error!(&env, ContractError::MinRewardInvalid);
+        }
+
+        save_pool_template(&env, pool_type, &template);
+
+        env.events()
+            .publish(("Factory", "Updated pool template"), pool_type);
+    }
+
+    fn query_pool_template(env: Env, pool_type: PoolType) -> Option<PoolTemplate> {
+        env.storage()
+            .instance()
+            .extend_ttl(INSTANCE_RENEWAL_THRESHOLD, INSTANCE_TARGET_TTL);
+
+        get_pool_template(&env, pool_type)
+    }
+
+    fn update_pool_config(
+        env: Env,
+        pool: Address,
+        total_fee_bps: Option<i64>,
+        fee_recipient: Option<Address>,
+        max_allowed_slippage_bps: Option<i64>,
+        max_allowed_spread_bps: Option<i64>,
+        max_referral_bps: Option<i64>,
+    ) {
+        get_config(&env).admin.require_auth();
+        env.storage()
+            .instance()
+            .extend_ttl(INSTANCE_RENEWAL_THRESHOLD, INSTANCE_TARGET_TTL);
+
+        if get_pool_position(&env, &pool).is_none() {
+            log!(&env, "Factory: Update Pool Config: Pool not found");
+            panic_with_error!(&env, ContractError::LiquidityPoolNotFound);
+        }
+
+        let info = pool_registry_info(&env, &pool);
+        if total_fee_bps.is_some_and(|total_fee_bps| total_fee_bps != info.fee_tier) {
+            log!(
+                &env,
+                "Factory: Update Pool Config: The swap fee is the fee tier of the pool"
+            );
+            panic_with_error!(&env, ContractError::FeeTierChangeNotAllowed);
+        }
+        if info.pool_type == PoolType::Stable && max_referral_bps.is_some() {
+            log!(
+                &env,
+                "Factory: Update Pool Config: Stable pools have no referral fee"
+            );
+            panic_with_error!(&env, ContractError::ReferralFeeNotSupported);
+        }
+
+        // the factory calls as the pool admin, the pool keeps its admin
+        let args: Vec<Val> = match info.pool_type {
+            PoolType::Stable => vec![
+                &env,
+                env.current_contract_address().into_val(&env),
+                None::<Address>.into_val(&env),
+                total_fee_bps.into_val(&env),
+                fee_recipient.into_val(&env),
+                max_allowed_slippage_bps.into_val(&env),
+                max_allowed_spread_bps.into_val(&env),
+            ],
+            PoolType::Xyk | PoolType::Blend => vec![
+                &env,
+                None::<Address>.into_val(&env),
+                total_fee_bps.into_val(&env),
+                fee_recipient.into_val(&env),
+                max_allowed_slippage_bps.into_val(&env),
+                max_allowed_spread_bps.into_val(&env),
+                max_referral_bps.into_val(&env),
+            ],
+        };
+        env.invoke_contract::<()>(&pool, &Symbol::new(&env, "update_config"), args);
+
+        env.events()
+            .publish(("Factory", "Updated pool config"), pool);
+    }
+
+    fn update_permissionless_config(
+        env: Env,
+        permissionless_config: PermissionlessPoolConfig,
+    ) -> Result<(), ContractError> {
+        env.storage()
+            .instance()
+            .extend_ttl(INSTANCE_RENEWAL_THRESHOLD, INSTANCE_TARGET_TTL);
+        get_config(&env).admin.require_auth();
+
+        if permissionless_config.fee_amount < 0
+            || permissionless_config.allowed_fee_tiers.is_empty()
+        {
+            log!(
+                &env,
+                "Factory: Update Permissionless Config: Fee must not be negative and at least one fee tier must be allowed"
+            );
+            panic_with_error!(&env, ContractError::InvalidPermissionlessConfig);
+        }
+        for fee_tier in permissionless_config.allowed_fee_tiers.iter() {
+            validate_bps!(fee_tier);
+        }
+        validate_bps!(
+            permissionless_config.max_slippage_bps,
+            permissionless_config.max_spread_bps,
+            permissionless_config.max_referral_bps
+        );
+
+        save_permissionless_config(&env, &permissionless_config);
+
+        env.events().publish(
+            ("Factory", "Updated permissionless config"),
+            permissionless_config.enabled,
+        );
+
+        Ok(())
+    }
+
+    fn set_token_info(env: Env, token_info: TokenInfo) {
+        get_config(&env).admin.require_auth();
+        env.storage()
+            .instance()
+            .extend_ttl(INSTANCE_RENEWAL_THRESHOLD, INSTANCE_TARGET_TTL);
+
+        let decimals = token_contract::Client::new(&env, &token_info.address).decimals();
+        if token_info.decimals != decimals {
+            log!(
+                &env,
+                "Factory: Set Token Info: Decimals don't match the token contract"
+            );
+            panic_with_error!(&env, ContractError::InvalidTokenInfo);
+        }
+        if token_info
+            .logo_hash
+            .as_ref()
+            .is_some_and(|logo_hash| logo_hash.len() != 32)
+        {
+            log!(&env, "Factory: Set Token Info: Logo hash must be 32 bytes");
+            panic_with_error!(&env, ContractError::InvalidTokenInfo);
+        }
+
+        save_token_info(&env, &token_info);
+
+        env.events().publish(
+            ("Factory", "Updated token info"),
+            (token_info.address, token_info.verified),
+        );
+    }
+
+    fn backfill_pools_for_tokens(env: Env, start: u32, limit: u32) -> u32 {
+        get_config(&env).admin.require_auth();
+        env.storage()
+            .instance()
+            .extend_ttl(INSTANCE_RENEWAL_THRESHOLD, INSTANCE_TARGET_TTL);
+
+        let pools = get_pools(&env, start, limit.min(MAX_QUERY_LIMIT));
+        for pool in pools.iter() {
+            let info = pool_registry_info(&env, &pool);
+            add_pool_for_token(&env, &info.token_a, &pool);
+            add_pool_for_token(&env, &info.token_b, &pool);
+        }
+
+        pools.len()
+    }
+
+    fn query_token(env: Env, token: Address) -> Option<TokenInfo> {
+        env.storage()
+            .instance()
+            .extend_ttl(INSTANCE_RENEWAL_THRESHOLD, INSTANCE_TARGET_TTL);
+
+        get_token_info(&env, &token)
+    }
+
+    fn query_tokens(env: Env, start_after: Option<Address>, limit: u32) -> Vec<TokenInfo> {
+        env.storage()
+            .instance()
+            .extend_ttl(INSTANCE_RENEWAL_THRESHOLD, INSTANCE_TARGET_TTL);
+
+        let start = match start_after {
+            Some(start_after) => {
+                get_token_position(&env, &start_after).unwrap_or_else(|| {
+                    log!(&env, "Factory: Query Tokens: Unknown start_after token");
+                    panic_with_error!(&env, ContractError::TokenNotFound);
+                }) + 1
+            }
+            None => 0,
+        };
+
+        get_tokens(&env, start, limit.min(MAX_QUERY_LIMIT))
+    }
+
+    fn query_pools_for_token(
+        env: Env,
+        token: Address,
+        start_after: Option<Address>,
+        limit: u32,
+    ) -> Vec<Address> {
+        env.storage()
+            .instance()
+            .extend_ttl(INSTANCE_RENEWAL_THRESHOLD, INSTANCE_TARGET_TTL);
+
+        let start = match start_after {
+            Some(start_after) => {
+                get_token_pool_position(&env, &token, &start_after).unwrap_or_else(|| {
+                    log!(
+                        &env,
+                        "Factory: Query Pools For Token: Unknown start_after pool"
+                    );
+                    panic_with_error!(&env, ContractError::LiquidityPoolNotFound);
+                }) + 1
+            }
+            None => 0,
+        };
+
+        get_pools_for_token(&env, &token, start, limit.min(MAX_QUERY_LIMIT))
+    }
+
     fn query_pools(env: Env) -> Vec<Address> {
         env.storage()
             .instance()
@@ -297,12 +692,7 @@ impl FactoryTrait for Factory {
         env.storage()
             .instance()
             .extend_ttl(INSTANCE_RENEWAL_THRESHOLD, INSTANCE_TARGET_TTL);
-        let pool_response: LiquidityPoolInfo = env.invoke_contract(
-            &pool_address,
-            &Symbol::new(&env, "query_pool_info_for_factory"),
-            Vec::new(&env),
-        );
-        pool_response
+        query_pool_info(&env, &pool_address)
     }
 
     fn query_all_pools_details(env: Env) -> Vec<LiquidityPoolInfo> {
@@ -312,18 +702,49 @@ impl FactoryTrait for Factory {
         let all_lp_vec_addresses = get_lp_vec(&env);
         let mut result = Vec::new(&env);
         for address in all_lp_vec_addresses {
-            let pool_response: LiquidityPoolInfo = env.invoke_contract(
-                &address,
-                &Symbol::new(&env, "query_pool_info_for_factory"),
-                Vec::new(&env),
-            );
-
-            result.push_back(pool_response);
+            result.push_back(query_pool_info(&env, &address));
         }
 
         result
     }
 
+    fn query_pools_paginated(
+        env: Env,
+        start_after: Option<Address>,
+        limit: u32,
+        pool_type: Option<PoolType>,
+        token: Option<Address>,
+    ) -> PoolsPage {
+        env.storage()
+            .instance()
+            .extend_ttl(INSTANCE_RENEWAL_THRESHOLD, INSTANCE_TARGET_TTL);
+
+        find_pools(&env, start_after, limit, pool_type, token)
+    }
+
+    fn query_pools_details_paginated(
+        env: Env,
+        start_after: Option<Address>,
+        limit: u32,
+        pool_type: Option<PoolType>,
+        token: Option<Address>,
+    ) -> PoolsDetailsPage {
+        env.storage()
+            .instance()
+            .extend_ttl(INSTANCE_RENEWAL_THRESHOLD, INSTANCE_TARGET_TTL);
+
+        let page = find_pools(&env, start_after, limit, pool_type, token);
+        let mut pools = Vec::new(&env);
+        for address in page.pools {
+            pools.push_back(query_pool_info(&env, &address));
+        }
+
+        PoolsDetailsPage {
+            pools,
+            next_start_after: page.next_start_after,
+        }
+    }
+
     fn query_for_pool_by_token_pair(env: Env, token_a: Address, token_b: Address) -> Address {
         env.storage()
             .instance()
@@ -397,52 +818,10 @@ impl FactoryTrait for Factory {
             .instance()
             .extend_ttl(INSTANCE_RENEWAL_THRESHOLD, INSTANCE_TARGET_TTL);
 
-        // Try V2 in both orderings first — this is the authoritative source
-        // for any pool created after this entrypoint shipped.
-        if let Some(addr) = get_lp_by_tuple_v2(&env, pool_type, &token_a, &token_b) {
-            return addr;
-        }
-        if let Some(addr) = get_lp_by_tuple_v2(&env, pool_type, &token_b, &token_a) {
+        if let Some(addr) = find_pool_by_pair_type(&env, &token_a, &token_b, pool_type) {
             return addr;
         }
 
-        // Legacy fallback. Pre-V2 pools wrote `PairTupleKey { a, b }` only,
-        // and on the current factory those are all Xyk. Anything Stable/Blend
-        // routed only through V2 (Blend never writes the legacy slot), so the
-        // fallback applies to Xyk lookups exclusively.
-        if let PoolType::Xyk = pool_type {
-            let pool_result: Option<Address> = env.storage().persistent().get(&PairTupleKey {
-                token_a: token_a.clone(),
-                token_b: token_b.clone(),
-            });
-            if let Some(addr) = pool_result {
-                env.storage().persistent().extend_ttl(
-                    &PairTupleKey {
-                        token_a: token_a.clone(),
-                        token_b: token_b.clone(),
-                    },
-                    PERSISTENT_RENEWAL_THRESHOLD,
-                    PERSISTENT_TARGET_TTL,
-                );
-                return addr;
-            }
-            let reverted: Option<Address> = env.storage().persistent().get(&PairTupleKey {
-                token_a: token_b.clone(),
-                token_b: token_a.clone(),
-            });
-            if let Some(addr) = reverted {
-                env.storage().persistent().extend_ttl(
-                    &PairTupleKey {
-                        token_a: token_b,
-                        token_b: token_a,
-                    },
-                    PERSISTENT_RENEWAL_THRESHOLD,
-                    PERSISTENT_TARGET_TTL,
-                );
-                return addr;
-            }
-        }
-
         log!(
             &env,
             "Factory: query_for_pool_by_token_pair_and_type failed: No liquidity pool found"
@@ -450,8 +829,29 @@ impl FactoryTrait for Factory {
         panic_with_error!(&env, ContractError::LiquidityPoolNotFound);
     }
 
-    fn get_admin(env: Env) -> Address {
-        env.storage()
+    fn query_pool_by_pair_type_and_tier(
+        env: Env,
+        token_a: Address,
+        token_b: Address,
+        pool_type: PoolType,
+        fee_tier: i64,
+    ) -> Address {
+        env.storage()
+            .instance()
+            .extend_ttl(INSTANCE_RENEWAL_THRESHOLD, INSTANCE_TARGET_TTL);
+
+        find_pool_by_pair_type_and_tier(&env, &token_a, &token_b, pool_type, fee_tier)
+            .unwrap_or_else(|| {
+                log!(
+                    &env,
+                    "Factory: query_pool_by_pair_type_and_tier failed: No liquidity pool found"
+                );
+                panic_with_error!(&env, ContractError::LiquidityPoolNotFound);
+            })
+    }
+
+    fn get_admin(env: Env) -> Address {
+        env.storage()
             .instance()
             .extend_ttl(INSTANCE_RENEWAL_THRESHOLD, INSTANCE_TARGET_TTL);
         get_config(&env).admin
@@ -464,95 +864,49 @@ impl FactoryTrait for Factory {
         get_config(&env)
     }
 
-    fn query_user_portfolio(env: Env, sender: Address, staking: bool) -> UserPortfolio {
+    fn query_permissionless_config(env: Env) -> Option<PermissionlessPoolConfig> {
         env.storage()
             .instance()
             .extend_ttl(INSTANCE_RENEWAL_THRESHOLD, INSTANCE_TARGET_TTL);
-        let initialized_pools = get_lp_vec(&env);
-        let mut lp_portfolio: Vec<LpPortfolio> = Vec::new(&env);
-        let mut stake_portfolio: Vec<StakePortfolio> = Vec::new(&env);
-
-        for address in initialized_pools {
-            let response: LiquidityPoolInfo = env.invoke_contract(
-                &address,
-                &Symbol::new(&env, "query_pool_info_for_factory"),
-                Vec::new(&env),
-            );
-
-            // get the lp share token balance for the user
-            // if the user has any liquidity tokens in the pool add to the lp_portfolio
-            let lp_share_balance: i128 = env.invoke_contract(
-                &response.pool_response.asset_lp_share.address,
-                &Symbol::new(&env, "balance"),
-                vec![&env, sender.into_val(&env)],
-            );
-
-            let lp_share_staked: StakedResponse = env.invoke_contract(
-                &response.pool_response.stake_address,
-                &Symbol::new(&env, "query_staked"),
-                vec![&env, sender.into_val(&env)],
-            );
-
-            let sum_of_lp_share_staked: i128 = lp_share_staked
-                .stakes
-                .iter()
-                .map(|stake| stake.stake)
-                .try_fold(0i128, |acc, stake| acc.checked_add(stake))
-                .unwrap_or_else(|| {
-                    log!(
-                        &env,
-                        "Factory: Query User Portfolio: Cannot addition more stakes"
-                    );
-                    panic_with_error!(env, ContractError::OverflowingOps);
-                });
+        get_permissionless_config(&env)
+    }
 
-            let total_lp_share_for_user = lp_share_balance
-                .checked_add(sum_of_lp_share_staked)
-                .unwrap_or_else(|| {
-                    log!(
-                        &env,
-                        "Factory: Query User Portfolio: Cannot add lp_share_balance with sum_of_lp_share_staked"
-                    );
-                    panic_with_error!(env, ContractError::OverflowingOps);
-                });
+    fn query_user_portfolio(env: Env, sender: Address, staking: bool) -> UserPortfolio {
+        env.storage()
+            .instance()
+            .extend_ttl(INSTANCE_RENEWAL_THRESHOLD, INSTANCE_TARGET_TTL);
 
-            // query the balance of the liquidity tokens
-            let (asset_a, asset_b) = env.invoke_contract::<(Asset, Asset)>(
-                &address,
-                &Symbol::new(&env, "query_share"),
-                vec![&env, total_lp_share_for_user.into_val(&env)],
-            );
+        user_portfolio(&env, &sender, staking, get_lp_vec(&env))
+    }
 
-            // we add only liquidity pools that the user has staked to to his portfolio
-            if total_lp_share_for_user > 0 {
-                // add to the lp_portfolio
-                lp_portfolio.push_back(LpPortfolio {
-                    assets: (asset_a, asset_b),
-                });
-            }
+    fn query_user_portfolio_paginated(
+        env: Env,
+        sender: Address,
+        staking: bool,
+        start_after: Option<Address>,
+        limit: u32,
+    ) -> UserPortfolio {
+        env.storage()
+            .instance()
+            .extend_ttl(INSTANCE_RENEWAL_THRESHOLD, INSTANCE_TARGET_TTL);
 
-            // make a call towards the stake contract to check the staked amount
-            if staking {
-                let stake_response: StakedResponse = env.invoke_contract(
-                    &response.pool_response.stake_address,
-                    &Symbol::new(&env, "query_staked"),
-                    vec![&env, sender.into_val(&env)],
-                );
+        let pools = find_pools(&env, start_after, limit, None, None).pools;
+        user_portfolio(&env, &sender, staking, pools)
+    }
 
-                // only stakes that the user has made
-                if !stake_response.stakes.is_empty() {
-                    stake_portfolio.push_back(StakePortfolio {
-                        staking_contract: response.pool_response.stake_address,
-                        stakes: stake_response.stakes.convert_vec(),
-                    })
-                }
-            }
-        }
+    fn query_user_portfolio_valued(
+        env: Env,
+        sender: Address,
+        quote_token: Address,
+        start_after: Option<Address>,
+        limit: u32,
+    ) -> ValuedUserPortfolio {
+        env.storage()
+            .instance()
+            .extend_ttl(INSTANCE_RENEWAL_THRESHOLD, INSTANCE_TARGET_TTL);
 
-        UserPortfolio {
-            lp_portfolio,
-            stake_portfolio,
-        }
+        let pools = find_pools(&env, start_after, limit, None, None).pools;
+        valued_user_portfolio(&env, &sender, &quote_token, pools)
     }
 
     fn migrate_admin_key(env: Env) -> Result<(), ContractError> {
@@ -641,6 +995,256 @@ impl FactoryTrait for Factory {
 
         Ok(pending_admin)
     }
+
+    fn upgrade_pools(
+        env: Env,
+        pool_type: PoolType,
+        new_wasm_hash: BytesN<32>,
+        start: u32,
+        limit: u32,
+    ) -> Vec<UpgradeResult> {
+        let admin = get_config(&env).admin;
+        admin.require_auth();
+        env.storage()
+            .instance()
+            .extend_ttl(INSTANCE_RENEWAL_THRESHOLD, INSTANCE_TARGET_TTL);
+
+        let mut results = Vec::new(&env);
+        for pool in get_pools(&env, start, limit.min(MAX_QUERY_LIMIT)) {
+            if pool_registry_info(&env, &pool).pool_type != pool_type {
+                continue;
+            }
+
+            results.push_back(upgrade_contract(
+                &env,
+                &pool,
+                "upgrade",
+                &new_wasm_hash,
+                "pool",
+            ));
+        }
+
+        results
+    }
+
+    fn upgrade_stakes(
+        env: Env,
+        pool_type: PoolType,
+        new_wasm_hash: BytesN<32>,
+        start: u32,
+        limit: u32,
+    ) -> Vec<UpgradeResult> {
+        let admin = get_config(&env).admin;
+        admin.require_auth();
+        env.storage()
+            .instance()
+            .extend_ttl(INSTANCE_RENEWAL_THRESHOLD, INSTANCE_TARGET_TTL);
+
+        let mut results = Vec::new(&env);
+        for pool in get_pools(&env, start, limit.min(MAX_QUERY_LIMIT)) {
+            if pool_registry_info(&env, &pool).pool_type != pool_type {
+                continue;
+            }
+
+            // Blend pools have no stake contract and report themselves instead
+            let stake = query_pool_info(&env, &pool).pool_response.stake_address;
+            if stake == pool {
+                continue;
+            }
+            // indexes the stake contracts of pools created before the index
+            save_stake_pool(&env, &stake, &pool);
+
+            results.push_back(upgrade_contract(
+                &env,
+                &stake,
+                "update",
+                &new_wasm_hash,
+                "stake",
+            ));
+        }
+
+        results
+    }
+
+    fn query_contract_version(env: Env, contract: Address) -> Option<ContractVersion> {
+        env.storage()
+            .instance()
+            .extend_ttl(INSTANCE_RENEWAL_THRESHOLD, INSTANCE_TARGET_TTL);
+
+        get_contract_version(&env, &contract)
+    }
+
+    fn deprecate_pool(env: Env, pool: Address, replacement: Option<Address>, pause_swaps: bool) {
+        let admin = get_config(&env).admin;
+        admin.require_auth();
+        env.storage()
+            .instance()
+            .extend_ttl(INSTANCE_RENEWAL_THRESHOLD, INSTANCE_TARGET_TTL);
+
+        if get_pool_position(&env, &pool).is_none() {
+            log!(&env, "Factory: Deprecate Pool: Pool not found");
+            panic_with_error!(&env, ContractError::LiquidityPoolNotFound);
+        }
+        if get_pool_deprecation(&env, &pool).is_some() {
+            log!(&env, "Factory: Deprecate Pool: Pool is already deprecated");
+            panic_with_error!(&env, ContractError::PoolAlreadyDeprecated);
+        }
+
+        let info = pool_registry_info(&env, &pool);
+        if let Some(replacement) = &replacement {
+            let is_valid = *replacement != pool
+                && get_pool_position(&env, replacement).is_some()
+                && get_pool_deprecation(&env, replacement).is_none()
+                && {
+                    let replacement_info = pool_registry_info(&env, replacement);
+                    replacement_info.token_a == info.token_a
+                        && replacement_info.token_b == info.token_b
+                };
+            if !is_valid {
+                log!(
+                    &env,
+                    "Factory: Deprecate Pool: Replacement must be another active pool of the same pair"
+                );
+                panic_with_error!(&env, ContractError::InvalidReplacementPool);
+            }
+        }
+
+        remove_lp_routing(
+            &env,
+            info.pool_type,
+            info.fee_tier,
+            (&info.token_a, &info.token_b),
+            &pool,
+        );
+
+        if let Some(replacement) = &replacement {
+            let replacement_type = pool_registry_info(&env, replacement).pool_type;
+            if find_pool_by_pair_type(&env, &info.token_a, &info.token_b, replacement_type)
+                .is_none()
+            {
+                save_default_routing(
+                    &env,
+                    replacement_type,
+                    &info.token_a,
+                    &info.token_b,
+                    replacement,
+                );
+            }
+        }
+
+        if pause_swaps {
+            env.invoke_contract::<()>(
+                &pool,
+                &Symbol::new(&env, "set_swaps_paused"),
+                vec![&env, true.into_val(&env)],
+            );
+        }
+
+        save_pool_deprecation(
+            &env,
+            &pool,
+            &PoolDeprecation {
+                replacement: replacement.clone(),
+                deprecated_at: env.ledger().timestamp(),
+                swaps_paused: pause_swaps,
+            },
+        );
+
+        env.events()
+            .publish(("deprecate", "pool"), (pool, replacement));
+    }
+
+    fn query_pool_deprecation(env: Env, pool: Address) -> Option<PoolDeprecation> {
+        env.storage()
+            .instance()
+            .extend_ttl(INSTANCE_RENEWAL_THRESHOLD, INSTANCE_TARGET_TTL);
+
+        get_pool_deprecation(&env, &pool)
+    }
+
+    fn query_pool_by_stake_contract(env: Env, stake: Address) -> Option<Address> {
+        env.storage()
+            .instance()
+            .extend_ttl(INSTANCE_RENEWAL_THRESHOLD, INSTANCE_TARGET_TTL);
+
+        get_stake_pool(&env, &stake)
+    }
+
+    fn migrate_liquidity(
+        env: Env,
+        sender: Address,
+        pool: Address,
+        share_amount: i128,
+        min_a: i128,
+        min_b: i128,
+        custom_slippage_bps: Option<i64>,
+    ) -> (i128, i128) {
+        sender.require_auth();
+        env.storage()
+            .instance()
+            .extend_ttl(INSTANCE_RENEWAL_THRESHOLD, INSTANCE_TARGET_TTL);
+
+        let replacement = get_pool_deprecation(&env, &pool)
+            .and_then(|deprecation| deprecation.replacement)
+            .unwrap_or_else(|| {
+                log!(
+                    &env,
+                    "Factory: Migrate Liquidity: Pool is not deprecated in favour of a replacement"
+                );
+                panic_with_error!(&env, ContractError::NoReplacementPool);
+            });
+
+        let (amount_a, amount_b): (i128, i128) = env.invoke_contract(
+            &pool,
+            &Symbol::new(&env, "withdraw_liquidity"),
+            vec![
+                &env,
+                sender.into_val(&env),
+                share_amount.into_val(&env),
+                min_a.into_val(&env),
+                min_b.into_val(&env),
+                None::<u64>.into_val(&env),
+                None::<Val>.into_val(&env),
+            ],
+        );
+
+        // the stable pool takes plain amounts and a minimum of shares instead
+        let provide_args: Vec<Val> = match pool_registry_info(&env, &replacement).pool_type {
+            PoolType::Stable => vec![
+                &env,
+                sender.into_val(&env),
+                amount_a.into_val(&env),
+                amount_b.into_val(&env),
+                custom_slippage_bps.into_val(&env),
+                None::<u64>.into_val(&env),
+                None::<u128>.into_val(&env),
+                false.into_val(&env),
+            ],
+            PoolType::Xyk | PoolType::Blend => vec![
+                &env,
+                sender.into_val(&env),
+                Some(amount_a).into_val(&env),
+                None::<i128>.into_val(&env),
+                Some(amount_b).into_val(&env),
+                None::<i128>.into_val(&env),
+                custom_slippage_bps.into_val(&env),
+                None::<u64>.into_val(&env),
+                false.into_val(&env),
+            ],
+        };
+        env.invoke_contract::<()>(
+            &replacement,
+            &Symbol::new(&env, "provide_liquidity"),
+            provide_args,
+        );
+
+        env.events().publish(
+            ("migrate", "liquidity"),
+            (sender, pool, replacement, share_amount),
+        );
+
+        (amount_a, amount_b)
+    }
 }
 
 #[contractimpl]
@@ -678,7 +1282,7 @@ impl Factory {
         );
         save_stable_wasm_hash(&env, stable_wasm_hash);
 
-        save_lp_vec(&env, Vec::new(&env));
+        init_pool_registry(&env);
 
         env.storage().persistent().set(&FACTORY_KEY, &true);
 
@@ -721,6 +1325,796 @@ impl Factory {
     }
 }
 
+/// Creates a pool for `sender`, who must be whitelisted or pay the
+/// permissionless creation fee.
+#[allow(clippy::too_many_arguments)]
+fn create_pool(
+    env: &Env,
+    sender: &Address,
+    mut lp_init_info: LiquidityPoolInitInfo,
+    share_token_name: String,
+    share_token_symbol: String,
+    pool_type: PoolType,
+    amp: Option<u64>,
+    default_slippage_bps: i64,
+    max_allowed_fee_bps: i64,
+    initial_liquidity: Option<InitialLiquidity>,
+) -> Address {
+    validate_pool_info(&pool_type, &amp);
+    if let Some(initial_liquidity) = &initial_liquidity {
+        validate_initial_liquidity(env, &pool_type, initial_liquidity);
+    }
+
+    // whitelisted accounts create pools freely, anyone else only when
+    // permissionless creation is enabled and by paying the creation fee
+    if !get_config(env)
+        .whitelisted_accounts
+        .contains(sender.clone())
+    {
+        let permissionless_config = get_permissionless_config(env)
+            .filter(|permissionless_config| permissionless_config.enabled)
+            .unwrap_or_else(|| {
+                log!(
+                    env,
+                    "Factory: Create Liquidity Pool: You are not authorized to create liquidity pool!"
+                );
+                panic_with_error!(env, ContractError::NotAuthorized);
+            });
+
+        validate_permissionless_pool(
+            env,
+            &permissionless_config,
+            &pool_type,
+            &lp_init_info,
+            default_slippage_bps,
+            max_allowed_fee_bps,
+        );
+
+        // the creator can't administer the pool or its stake contract, or it
+        // could lift the parameters out of the bounds above right away, and
+        // the pool fees and the staking rewards are left to the treasury
+        lp_init_info.admin = env.current_contract_address();
+        lp_init_info.fee_recipient = permissionless_config.treasury.clone();
+        lp_init_info.stake_init_info.manager = permissionless_config.treasury.clone();
+
+        if permissionless_config.fee_amount > 0 {
+            token_contract::Client::new(env, &permissionless_config.fee_token).transfer(
+                sender,
+                &permissionless_config.treasury,
+                &permissionless_config.fee_amount,
+            );
+            env.events().publish(
+                ("create", "creation_fee"),
+                (sender.clone(), permissionless_config.fee_amount),
+            );
+        }
+    };
+
+    validate_token_info(
+        env,
+        &lp_init_info.token_init_info,
+        &lp_init_info.stake_init_info,
+    );
+
+    let config = get_config(env);
+    let stake_wasm_hash = config.stake_wasm_hash;
+    let token_wasm_hash = config.token_wasm_hash;
+
+    validate_bps!(
+        lp_init_info.swap_fee_bps,
+        lp_init_info.max_allowed_slippage_bps,
+        lp_init_info.max_allowed_spread_bps,
+        lp_init_info.max_referral_bps,
+        default_slippage_bps,
+        max_allowed_fee_bps
+    );
+
+    let factory_addr = env.current_contract_address();
+    let mut init_fn_args: Vec<Val> = (
+        stake_wasm_hash,
+        token_wasm_hash,
+        lp_init_info.clone(),
+        factory_addr,
+        share_token_name,
+        share_token_symbol,
+    )
+        .into_val(env);
+
+    if matches!(pool_type, PoolType::Xyk | PoolType::Blend) {
+        init_fn_args.push_back(default_slippage_bps.into_val(env));
+    }
+
+    if let PoolType::Stable = pool_type {
+        init_fn_args.push_back(amp.unwrap().into_val(env));
+    }
+
+    init_fn_args.push_back(max_allowed_fee_bps.into_val(env));
+
+    let token_a = &lp_init_info.token_init_info.token_a;
+    let token_b = &lp_init_info.token_init_info.token_b;
+    // the swap fee is the fee tier of the pool
+    let fee_tier = lp_init_info.swap_fee_bps;
+
+    if find_pool_by_pair_type_and_tier(env, token_a, token_b, pool_type, fee_tier).is_some() {
+        log!(
+            env,
+            "Factory: Create Liquidity Pool: Pool for this pair, type and fee tier already exists"
+        );
+        panic_with_error!(env, ContractError::PoolAlreadyExists);
+    }
+
+    // Salt formula: `sha256(token_a || token_b || fee_tier)` for Xyk/Stable,
+    // but Blend pools prefix with the pool-type discriminant so they get a
+    // fresh, distinct deterministic address — letting Xyk and Blend coexist
+    // for the same unordered pair. The fee tier lets a pair have one pool
+    // per tier.
+    let mut salt = Bytes::new(env);
+    if let PoolType::Blend = pool_type {
+        salt.append(&Bytes::from_array(env, &[PoolType::Blend as u8]));
+    }
+    salt.append(&token_a.clone().to_xdr(env));
+    salt.append(&token_b.clone().to_xdr(env));
+    salt.append(&fee_tier.to_xdr(env));
+    let salt = env.crypto().sha256(&salt);
+
+    let lp_contract_address = match pool_type {
+        PoolType::Xyk => env
+            .deployer()
+            .with_current_contract(salt)
+            .deploy_v2(config.lp_wasm_hash, init_fn_args.clone()),
+        PoolType::Stable => env
+            .deployer()
+            .with_current_contract(salt)
+            .deploy_v2(get_stable_wasm_hash(env), init_fn_args),
+        PoolType::Blend => env
+            .deployer()
+            .with_current_contract(salt)
+            .deploy_v2(get_blend_wasm_hash(env), init_fn_args),
+    };
+
+    save_pool(
+        env,
+        &lp_contract_address,
+        &PoolRegistryInfo {
+            pool_type,
+            token_a: token_a.clone(),
+            token_b: token_b.clone(),
+            fee_tier,
+        },
+    );
+    add_pool_for_token(env, token_a, &lp_contract_address);
+    add_pool_for_token(env, token_b, &lp_contract_address);
+    // Blend pools have no stake contract
+    if pool_type != PoolType::Blend {
+        let stake: Address = env.invoke_contract(
+            &lp_contract_address,
+            &Symbol::new(env, "query_stake_contract_address"),
+            Vec::new(env),
+        );
+        save_stake_pool(env, &stake, &lp_contract_address);
+    }
+
+    // Storage policy:
+    // * Xyk: keep writing the legacy `PairTupleKey { a, b }` slot so any
+    //   client that still calls `query_for_pool_by_token_pair(a, b)` (no
+    //   `pool_type` arg) keeps resolving the Xyk pool — preserving the
+    //   semantics of all pools that pre-date this V2 key.
+    // * Stable: writes legacy too (no Stable pools coexist with anything
+    //   else on the current factory; same backwards-compat reasoning).
+    // * Blend: writes ONLY the V2 slot so it never clobbers an existing
+    //   legacy entry pointing at a different-type pool for the same pair.
+    // All three also write the explicit V2 slot so the type-aware query
+    // returns the correct pool unambiguously.
+    // The legacy and V2 slots belong to the first pool of a pair and type;
+    // pools in further fee tiers only write the V3 slot, so existing
+    // routing keeps resolving the same pool.
+    if find_pool_by_pair_type(env, token_a, token_b, pool_type).is_none() {
+        save_default_routing(env, pool_type, token_a, token_b, &lp_contract_address);
+    }
+    save_lp_vec_with_tuple_v3_as_key(
+        env,
+        pool_type,
+        fee_tier,
+        (token_a, token_b),
+        &lp_contract_address,
+    );
+
+    env.events()
+        .publish(("create", "liquidity_pool"), &lp_contract_address);
+
+    if let Some(initial_liquidity) = initial_liquidity {
+        provide_initial_liquidity(
+            env,
+            sender,
+            &lp_contract_address,
+            pool_type,
+            &initial_liquidity,
+        );
+    }
+
+    lp_contract_address
+}
+
+/// Seeds a freshly created pool with the liquidity of its creator, who signs
+/// the transfers of the pool. The trading floors of Blend pools are set by
+/// the pool admin, whose authorization is needed when it isn't the factory.
+fn provide_initial_liquidity(
+    env: &Env,
+    sender: &Address,
+    pool: &Address,
+    pool_type: PoolType,
+    initial_liquidity: &InitialLiquidity,
+) {
+    let InitialLiquidity {
+        amount_a,
+        amount_b,
+        min_trading_balance_a,
+        min_trading_balance_b,
+    } = initial_liquidity.clone();
+
+    // the stable pool takes plain amounts and a minimum of shares instead
+    let provide_args: Vec<Val> = match pool_type {
+        PoolType::Stable => vec![
+            env,
+            sender.into_val(env),
+            amount_a.into_val(env),
+            amount_b.into_val(env),
+            None::<i64>.into_val(env),
+            None::<u64>.into_val(env),
+            None::<u128>.into_val(env),
+            false.into_val(env),
+        ],
+        PoolType::Xyk | PoolType::Blend => vec![
+            env,
+            sender.into_val(env),
+            Some(amount_a).into_val(env),
+            None::<i128>.into_val(env),
+            Some(amount_b).into_val(env),
+            None::<i128>.into_val(env),
+            None::<i64>.into_val(env),
+            None::<u64>.into_val(env),
+            false.into_val(env),
+        ],
+    };
+    env.invoke_contract::<()>(pool, &Symbol::new(env, "provide_liquidity"), provide_args);
+
+    if min_trading_balance_a > 0 || min_trading_balance_b > 0 {
+        env.invoke_contract::<()>(
+            pool,
+            &Symbol::new(env, "set_min_trading_balances"),
+            vec![
+                env,
+                min_trading_balance_a.into_val(env),
+                min_trading_balance_b.into_val(env),
+            ],
+        );
+    }
+
+    env.events().publish(
+        ("create", "initial_liquidity"),
+        (pool.clone(), amount_a, amount_b),
+    );
+}
+
+/// Calls the upgrade entrypoint of a pool or stake contract, recording the
+/// version it reports once upgraded. Failures are returned, not raised.
+fn upgrade_contract(
+    env: &Env,
+    contract: &Address,
+    upgrade_fn: &str,
+    new_wasm_hash: &BytesN<32>,
+    contract_kind: &str,
+) -> UpgradeResult {
+    let upgraded = matches!(
+        env.try_invoke_contract::<(), InvokeError>(
+            contract,
+            &Symbol::new(env, upgrade_fn),
+            vec![env, new_wasm_hash.into_val(env)],
+        ),
+        Ok(Ok(()))
+    );
+
+    if upgraded {
+        let version = match env.try_invoke_contract::<String, InvokeError>(
+            contract,
+            &Symbol::new(env, "query_version"),
+            Vec::new(env),
+        ) {
+            Ok(Ok(version)) => version,
+            _ => String::from_str(env, ""),
+        };
+        save_contract_version(
+            env,
+            contract,
+            &ContractVersion {
+                wasm_hash: new_wasm_hash.clone(),
+                version,
+                upgraded_at: env.ledger().timestamp(),
+            },
+        );
+    }
+
+    env.events()
+        .publish(("upgrade", contract_kind), (contract.clone(), upgraded));
+
+    UpgradeResult {
+        contract: contract.clone(),
+        upgraded,
+    }
+}
+
+fn query_pool_info(env: &Env, pool: &Address) -> LiquidityPoolInfo {
+    let pool_info: PoolInfoResponse = env.invoke_contract(
+        pool,
+        &Symbol::new(env, "query_pool_info_for_factory"),
+        Vec::new(env),
+    );
+    let deprecation = get_pool_deprecation(env, pool);
+
+    LiquidityPoolInfo {
+        pool_address: pool_info.pool_address,
+        pool_response: pool_info.pool_response,
+        total_fee_bps: pool_info.total_fee_bps,
+        deprecated: deprecation.is_some(),
+        replacement: deprecation.and_then(|deprecation| deprecation.replacement),
+    }
+}
+
+/// Makes `pool` the one resolved by the pair lookups without a fee tier.
+/// Blend pools never write the legacy slot, see `create_liquidity_pool`.
+fn save_default_routing(
+    env: &Env,
+    pool_type: PoolType,
+    token_a: &Address,
+    token_b: &Address,
+    pool: &Address,
+) {
+    match pool_type {
+        PoolType::Xyk | PoolType::Stable => {
+            save_lp_vec_with_tuple_as_key(env, (token_a, token_b), pool);
+        }
+        PoolType::Blend => {}
+    }
+    save_lp_vec_with_tuple_v2_as_key(env, pool_type, (token_a, token_b), pool);
+}
+
+/// Type, tokens and fee tier of a pool. Pools created before the registry
+/// kept them are looked up once and saved, taking their current swap fee as
+/// the fee tier.
+fn pool_registry_info(env: &Env, pool: &Address) -> PoolRegistryInfo {
+    if let Some(info) = get_pool_registry_info(env, pool) {
+        return info;
+    }
+
+    let pool_info = query_pool_info(env, pool);
+    let token_a = pool_info.pool_response.asset_a.address;
+    let token_b = pool_info.pool_response.asset_b.address;
+    // Stable and Blend pools always have a V2 slot, older pools are Xyk
+    let pool_type = [PoolType::Stable, PoolType::Blend]
+        .into_iter()
+        .find(|pool_type| {
+            get_lp_by_tuple_v2(env, *pool_type, &token_a, &token_b).as_ref() == Some(pool)
+        })
+        .unwrap_or(PoolType::Xyk);
+
+    let info = PoolRegistryInfo {
+        pool_type,
+        token_a,
+        token_b,
+        fee_tier: pool_info.total_fee_bps,
+    };
+    save_pool_registry_info(env, pool, &info);
+
+    info
+}
+
+fn find_pools(
+    env: &Env,
+    start_after: Option<Address>,
+    limit: u32,
+    pool_type: Option<PoolType>,
+    token: Option<Address>,
+) -> PoolsPage {
+    let limit = limit.min(MAX_QUERY_LIMIT);
+    // the pools of a token have an index of their own, any other filter has
+    // to go through the whole registry
+    let position = |pool: &Address| match &token {
+        Some(token) => get_token_pool_position(env, token, pool),
+        None => get_pool_position(env, pool),
+    };
+    let start = match start_after {
+        Some(start_after) => {
+            position(&start_after).unwrap_or_else(|| {
+                log!(env, "Factory: Find Pools: Unknown start_after pool");
+                panic_with_error!(env, ContractError::LiquidityPoolNotFound);
+            }) + 1
+        }
+        None => 0,
+    };
+    let (scanned, count) = match &token {
+        Some(token) => (
+            get_pools_for_token(env, token, start, limit),
+            get_token_pool_count(env, token),
+        ),
+        None => (get_pools(env, start, limit), get_pool_count(env)),
+    };
+
+    let mut pools = Vec::new(env);
+    for pool in scanned.iter() {
+        let type_matches = pool_type.map_or(true, |pool_type| {
+            pool_registry_info(env, &pool).pool_type == pool_type
+        });
+        if type_matches {
+            pools.push_back(pool);
+        }
+    }
+
+    let next_start_after = if start + scanned.len() < count {
+        scanned.last()
+    } else {
+        None
+    };
+
+    PoolsPage {
+        pools,
+        next_start_after,
+    }
+}
+
+fn user_portfolio(
+    env: &Env,
+    sender: &Address,
+    staking: bool,
+    pools: Vec<Address>,
+) -> UserPortfolio {
+    let mut lp_portfolio: Vec<LpPortfolio> = Vec::new(env);
+    let mut stake_portfolio: Vec<StakePortfolio> = Vec::new(env);
+
+    for address in pools {
+        let response = query_pool_info(env, &address);
+
+        // get the lp share token balance for the user
+        // if the user has any liquidity tokens in the pool add to the lp_portfolio
+        let lp_share_balance: i128 = env.invoke_contract(
+            &response.pool_response.asset_lp_share.address,
+            &Symbol::new(env, "balance"),
+            vec![env, sender.into_val(env)],
+        );
+
+        let lp_share_staked: StakedResponse = env.invoke_contract(
+            &response.pool_response.stake_address,
+            &Symbol::new(env, "query_staked"),
+            vec![env, sender.into_val(env)],
+        );
+
+        let sum_of_lp_share_staked: i128 = lp_share_staked
+            .stakes
+            .iter()
+            .map(|stake| stake.stake)
+            .try_fold(0i128, |acc, stake| acc.checked_add(stake))
+            .unwrap_or_else(|| {
+                log!(
+                    env,
+                    "Factory: Query User Portfolio: Cannot addition more stakes"
+                );
+                panic_with_error!(env, ContractError::OverflowingOps);
+            });
+
+        let total_lp_share_for_user = lp_share_balance
+            .checked_add(sum_of_lp_share_staked)
+            .unwrap_or_else(|| {
+                log!(
+                    env,
+                    "Factory: Query User Portfolio: Cannot add lp_share_balance with sum_of_lp_share_staked"
+                );
+                panic_with_error!(env, ContractError::OverflowingOps);
+            });
+
+        // query the balance of the liquidity tokens
+        let (asset_a, asset_b) = env.invoke_contract::<(Asset, Asset)>(
+            &address,
+            &Symbol::new(env, "query_share"),
+            vec![env, total_lp_share_for_user.into_val(env)],
+        );
+
+        // we add only liquidity pools that the user has staked to to his portfolio
+        if total_lp_share_for_user > 0 {
+            // add to the lp_portfolio
+            lp_portfolio.push_back(LpPortfolio {
+                assets: (asset_a, asset_b),
+            });
+        }
+
+        // make a call towards the stake contract to check the staked amount
+        if staking {
+            let stake_response: StakedResponse = env.invoke_contract(
+                &response.pool_response.stake_address,
+                &Symbol::new(env, "query_staked"),
+                vec![env, sender.into_val(env)],
+            );
+
+            // only stakes that the user has made
+            if !stake_response.stakes.is_empty() {
+                stake_portfolio.push_back(StakePortfolio {
+                    staking_contract: response.pool_response.stake_address,
+                    stakes: stake_response.stakes.convert_vec(),
+                })
+            }
+        }
+    }
+
+    UserPortfolio {
+        lp_portfolio,
+        stake_portfolio,
+    }
+}
+
+fn valued_user_portfolio(
+    env: &Env,
+    sender: &Address,
+    quote_token: &Address,
+    pools: Vec<Address>,
+) -> ValuedUserPortfolio {
+    let mut portfolio = ValuedUserPortfolio {
+        quote_token: quote_token.clone(),
+        lp_positions: Vec::new(env),
+        stake_positions: Vec::new(env),
+        total_lp_value: 0,
+        total_staked_value: 0,
+        total_rewards_value: 0,
+        total_value: 0,
+        unpriced_tokens: Vec::new(env),
+    };
+
+    for pool in pools {
+        let pool_response = query_pool_info(env, &pool).pool_response;
+
+        let lp_share_balance: i128 = env.invoke_contract(
+            &pool_response.asset_lp_share.address,
+            &Symbol::new(env, "balance"),
+            vec![env, sender.into_val(env)],
+        );
+        if lp_share_balance > 0 {
+            let assets = value_share(env, &pool, lp_share_balance, &mut portfolio);
+            let value = add_values(env, assets.0.value, assets.1.value);
+            portfolio.total_lp_value = add_values(env, portfolio.total_lp_value, value);
+            portfolio.lp_positions.push_back(ValuedLpPosition {
+                pool: pool.clone(),
+                assets,
+                value,
+            });
+        }
+
+        // Blend pools have no stake contract and report themselves instead
+        let staking_contract = pool_response.stake_address;
+        if staking_contract == pool {
+            continue;
+        }
+
+        let staked_shares = env
+            .invoke_contract::<StakedResponse>(
+                &staking_contract,
+                &Symbol::new(env, "query_staked"),
+                vec![env, sender.into_val(env)],
+            )
+            .total_stake;
+        let rewards: WithdrawableRewardsResponse = env.invoke_contract(
+            &staking_contract,
+            &Symbol::new(env, "query_withdrawable_rewards"),
+            vec![env, sender.into_val(env)],
+        );
+
+        let mut pending_rewards = Vec::new(env);
+        let mut rewards_value = 0;
+        for reward in rewards
+            .rewards
+            .iter()
+            .filter(|reward| reward.reward_amount > 0)
+        {
+            let amount = i128::try_from(reward.reward_amount).unwrap_or_else(|_| {
+                log!(
+                    env,
+                    "Factory: Query User Portfolio Valued: Reward amount too big"
+                );
+                panic_with_error!(env, ContractError::OverflowingOps);
+            });
+            let reward = value_asset(
+                env,
+                Asset {
+                    address: reward.reward_address,
+                    amount,
+                },
+                &mut portfolio,
+            );
+            rewards_value = add_values(env, rewards_value, reward.value);
+            pending_rewards.push_back(reward);
+        }
+
+        if staked_shares == 0 && pending_rewards.is_empty() {
+            continue;
+        }
+
+        let assets = value_share(env, &pool, staked_shares, &mut portfolio);
+        let value = add_values(env, assets.0.value, assets.1.value);
+        portfolio.total_staked_value = add_values(env, portfolio.total_staked_value, value);
+        portfolio.total_rewards_value =
+            add_values(env, portfolio.total_rewards_value, rewards_value);
+        portfolio.stake_positions.push_back(ValuedStakePosition {
+            pool,
+            staking_contract,
+            staked_shares,
+            assets,
+            value,
+            pending_rewards,
+            rewards_value,
+        });
+    }
+
+    portfolio.total_value = add_values(
+        env,
+        add_values(env, portfolio.total_lp_value, portfolio.total_staked_value),
+        portfolio.total_rewards_value,
+    );
+
+    portfolio
+}
+
+/// Pool tokens `share_amount` LP shares of `pool` are worth, valued
+fn value_share(
+    env: &Env,
+    pool: &Address,
+    share_amount: i128,
+    portfolio: &mut ValuedUserPortfolio,
+) -> (ValuedAsset, ValuedAsset) {
+    let (asset_a, asset_b) = env.invoke_contract::<(Asset, Asset)>(
+        pool,
+        &Symbol::new(env, "query_share"),
+        vec![env, share_amount.into_val(env)],
+    );
+
+    (
+        value_asset(env, asset_a, portfolio),
+        value_asset(env, asset_b, portfolio),
+    )
+}
+
+/// Values an asset in the quote token of the portfolio, recording its token
+/// as unpriced when no pool can price it.
+fn value_asset(env: &Env, asset: Asset, portfolio: &mut ValuedUserPortfolio) -> ValuedAsset {
+    let value = spot_value(env, &asset.address, asset.amount, &portfolio.quote_token)
+        .unwrap_or_else(|| {
+            if !portfolio.unpriced_tokens.contains(&asset.address) {
+                portfolio.unpriced_tokens.push_back(asset.address.clone());
+            }
+            0
+        });
+
+    ValuedAsset { asset, value }
+}
+
+/// Value of `amount` of `token` in `quote_token` at the spot price of the
+/// default pool of the pair, trying Xyk, Stable then Blend pools. `None`
+/// when the pair has no pool with liquidity.
+fn spot_value(env: &Env, token: &Address, amount: i128, quote_token: &Address) -> Option<i128> {
+    if token == quote_token {
+        return Some(amount);
+    }
+
+    // the reserve ratio of a stable pool is not its price, the curve keeps
+    // the price near 1 whatever the reserves, so stable pools are not used.
+    // The legacy pair slot looked up for Xyk can hold a stable pool too.
+    [PoolType::Xyk, PoolType::Blend]
+        .into_iter()
+        .filter_map(|pool_type| find_pool_by_pair_type(env, token, quote_token, pool_type))
+        .filter(|pool| pool_registry_info(env, pool).pool_type != PoolType::Stable)
+        .find_map(|pool| {
+            let pool_response = query_pool_info(env, &pool).pool_response;
+            let (token_reserve, quote_reserve) = if pool_response.asset_a.address == *token {
+                (pool_response.asset_a.amount, pool_response.asset_b.amount)
+            } else {
+                (pool_response.asset_b.amount, pool_response.asset_a.amount)
+            };
+            if token_reserve == 0 {
+                return None;
+            }
+
+            let value = amount
+                .checked_mul(quote_reserve)
+                .map(|value| value / token_reserve)
+                .unwrap_or_else(|| {
+                    log!(env, "Factory: Spot Value: Overflow while pricing a token");
+                    panic_with_error!(env, ContractError::OverflowingOps);
+                });
+            Some(value)
+        })
+}
+
+fn add_values(env: &Env, a: i128, b: i128) -> i128 {
+    a.checked_add(b).unwrap_or_else(|| {
+        log!(
+            env,
+            "Factory: Query User Portfolio Valued: Cannot add values"
+        );
+        panic_with_error!(env, ContractError::OverflowingOps);
+    })
+}
+
+fn find_pool_by_pair_type(
+    env: &Env,
+    token_a: &Address,
+    token_b: &Address,
+    pool_type: PoolType,
+) -> Option<Address> {
+    // Try V2 in both orderings first — this is the authoritative source
+    // for any pool created after this entrypoint shipped.
+    if let Some(addr) = get_lp_by_tuple_v2(env, pool_type, token_a, token_b) {
+        return Some(addr);
+    }
+    if let Some(addr) = get_lp_by_tuple_v2(env, pool_type, token_b, token_a) {
+        return Some(addr);
+    }
+
+    // Legacy fallback. Pre-V2 pools wrote `PairTupleKey { a, b }` only,
+    // and on the current factory those are all Xyk. Anything Stable/Blend
+    // routed only through V2 (Blend never writes the legacy slot), so the
+    // fallback applies to Xyk lookups exclusively.
+    if let PoolType::Xyk = pool_type {
+        let pool_result: Option<Address> = env.storage().persistent().get(&PairTupleKey {
+            token_a: token_a.clone(),
+            token_b: token_b.clone(),
+        });
+        if let Some(addr) = pool_result {
+            env.storage().persistent().extend_ttl(
+                &PairTupleKey {
+                    token_a: token_a.clone(),
+                    token_b: token_b.clone(),
+                },
+                PERSISTENT_RENEWAL_THRESHOLD,
+                PERSISTENT_TARGET_TTL,
+            );
+            return Some(addr);
+        }
+        let reverted: Option<Address> = env.storage().persistent().get(&PairTupleKey {
+            token_a: token_b.clone(),
+            token_b: token_a.clone(),
+        });
+        if let Some(addr) = reverted {
+            env.storage().persistent().extend_ttl(
+                &PairTupleKey {
+                    token_a: token_b.clone(),
+                    token_b: token_a.clone(),
+                },
+                PERSISTENT_RENEWAL_THRESHOLD,
+                PERSISTENT_TARGET_TTL,
+            );
+            return Some(addr);
+        }
+    }
+
+    None
+}
+
+/// Looks up the V3 slot in both orderings first. Pools created before fee
+/// tiers only have the V2/legacy slot, so they match when their swap fee is
+/// the requested tier.
+fn find_pool_by_pair_type_and_tier(
+    env: &Env,
+    token_a: &Address,
+    token_b: &Address,
+    pool_type: PoolType,
+    fee_tier: i64,
+) -> Option<Address> {
+    if let Some(addr) = get_lp_by_tuple_v3(env, pool_type, fee_tier, token_a, token_b) {
+        return Some(addr);
+    }
+    if let Some(addr) = get_lp_by_tuple_v3(env, pool_type, fee_tier, token_b, token_a) {
+        return Some(addr);
+    }
+
+    find_pool_by_pair_type(env, token_a, token_b, pool_type)
+        .filter(|pool_address| pool_registry_info(env, pool_address).fee_tier == fee_tier)
+}
+
 fn validate_token_info(
     env: &Env,
     token_init_info: &TokenInitInfo,
@@ -751,6 +2145,50 @@ fn validate_token_info(
     }
 }
 
+/// Bounds for pools created by accounts outside of the whitelist
+fn validate_permissionless_pool(
+    env: &Env,
+    permissionless_config: &PermissionlessPoolConfig,
+    pool_type: &PoolType,
+    lp_init_info: &LiquidityPoolInitInfo,
+    default_slippage_bps: i64,
+    max_allowed_fee_bps: i64,
+) {
+    if !matches!(pool_type, PoolType::Xyk) {
+        log!(
+            env,
+            "Factory: Create Liquidity Pool: Only Xyk pools can be created permissionlessly"
+        );
+        panic_with_error!(&env, ContractError::PoolTypeNotPermissionless);
+    }
+
+    let allowed_fee_tiers = &permissionless_config.allowed_fee_tiers;
+    if !allowed_fee_tiers.contains(lp_init_info.swap_fee_bps) {
+        log!(
+            env,
+            "Factory: Create Liquidity Pool: Swap fee is not one of the allowed fee tiers"
+        );
+        panic_with_error!(&env, ContractError::FeeTierNotAllowed);
+    }
+
+    // the pool must not allow fees above the highest tier
+    let highest_fee_tier = allowed_fee_tiers.iter().max().unwrap_or(0);
+    if lp_init_info.max_allowed_slippage_bps > permissionless_config.max_slippage_bps
+        || lp_init_info.default_slippage_bps > permissionless_config.max_slippage_bps
+        || default_slippage_bps > permissionless_config.max_slippage_bps
+        || lp_init_info.max_allowed_spread_bps > permissionless_config.max_spread_bps
+        || lp_init_info.max_referral_bps > permissionless_config.max_referral_bps
+        || lp_init_info.stake_init_info.max_complexity > permissionless_config.max_complexity
+        || max_allowed_fee_bps > highest_fee_tier
+    {
+        log!(
+            env,
+            "Factory: Create Liquidity Pool: Pool parameters are out of the permissionless bounds"
+        );
+        panic_with_error!(&env, ContractError::PoolParamsOutOfBounds);
+    }
+}
+
 fn validate_pool_info(pool_type: &PoolType, amp: &Option<u64>) {
     match pool_type {
         PoolType::Xyk => (),
@@ -762,6 +2200,30 @@ fn validate_pool_info(pool_type: &PoolType, amp: &Option<u64>) {
     }
 }
 
+fn validate_initial_liquidity(
+    env: &Env,
+    pool_type: &PoolType,
+    initial_liquidity: &InitialLiquidity,
+) {
+    if initial_liquidity.amount_a <= 0 || initial_liquidity.amount_b <= 0 {
+        log!(
+            env,
+            "Factory: Create Liquidity Pool: Initial amounts must be bigger than 0"
+        );
+        panic_with_error!(env, ContractError::InvalidInitialLiquidity);
+    }
+
+    let has_trading_floor = initial_liquidity.min_trading_balance_a != 0
+        || initial_liquidity.min_trading_balance_b != 0;
+    if has_trading_floor && *pool_type != PoolType::Blend {
+        log!(
+            env,
+            "Factory: Create Liquidity Pool: Trading floors can only be set on blend pools"
+        );
+        panic_with_error!(env, ContractError::InvalidInitialLiquidity);
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -17,4 +17,18 @@ pub enum ContractError {
     NoAdminChangeInPlace = 110,
     AdminChangeExpired = 111,
     TokenDecimalsInvalid = 112,
+    PoolTypeNotPermissionless = 113,
+    FeeTierNotAllowed = 114,
+    PoolParamsOutOfBounds = 115,
+    InvalidPermissionlessConfig = 116,
+    PoolAlreadyExists = 117,
+    PoolAlreadyDeprecated = 118,
+    InvalidReplacementPool = 119,
+    NoReplacementPool = 120,
+    PoolTemplateNotSet = 121,
+    InvalidInitialLiquidity = 122,
+    InvalidTokenInfo = 123,
+    TokenNotFound = 124,
+    FeeTierChangeNotAllowed = 125,
+    ReferralFeeNotSupported = 126,
 }
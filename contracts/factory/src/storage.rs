@@ -3,8 +3,8 @@ use phoenix::ttl::{
     PERSISTENT_TARGET_TTL,
 };
 use soroban_sdk::{
-    contracttype, log, panic_with_error, symbol_short, Address, BytesN, ConversionError, Env,
-    Symbol, TryFromVal, Val, Vec,
+    contracttype, log, panic_with_error, symbol_short, Address, Bytes, BytesN, ConversionError,
+    Env, IntoVal, String, Symbol, TryFromVal, Val, Vec,
 };
 
 use phoenix::utils::PoolType;
@@ -17,12 +17,20 @@ pub(crate) const PENDING_ADMIN: Symbol = symbol_short!("p_admin");
 const STABLE_WASM_HASH: Symbol = symbol_short!("stabwasm");
 const BLEND_WASM_HASH: Symbol = symbol_short!("blndwasm");
 
+/// Pools per shard of the pool registry
+pub const POOL_REGISTRY_SHARD_SIZE: u32 = 50;
+/// Tokens per shard of the token registry
+pub const TOKEN_REGISTRY_SHARD_SIZE: u32 = 50;
+/// Upper bound for the `limit` of the paginated queries
+pub const MAX_QUERY_LIMIT: u32 = 50;
+
 #[derive(Clone, Copy)]
 #[repr(u32)]
 pub enum DataKey {
     Config = 1,
-    LpVec = 2,
+    LpVec = 2,       // deprecated, moved into the sharded `PoolRegistryKey` storage
     Initialized = 3, // TODO: deprecated, remove in next upgrade
+    PermissionlessConfig = 4,
 }
 
 #[derive(Clone)]
@@ -44,6 +52,173 @@ pub struct PairTupleKeyV2 {
     pub(crate) token_b: Address,
 }
 
+/// V3 pair-tuple key, which adds the fee tier (the pool's swap fee in bps) to
+/// the pool identity so a pair can have one pool per fee tier.
+#[derive(Clone)]
+#[contracttype]
+pub struct PairTupleKeyV3 {
+    pub(crate) pool_type: PoolType,
+    pub(crate) fee_tier: i64,
+    pub(crate) token_a: Address,
+    pub(crate) token_b: Address,
+}
+
+/// Sharded registry of all pools created by the factory, so no single entry
+/// grows with the number of pools.
+#[derive(Clone)]
+#[contracttype]
+pub enum PoolRegistryKey {
+    /// Number of pools in the registry
+    Count,
+    /// Up to `POOL_REGISTRY_SHARD_SIZE` pool addresses, in creation order
+    Shard(u32),
+    /// Position of a pool in the registry
+    Position(Address),
+    /// Type and tokens of a pool, used to filter the registry
+    Info(Address),
+    /// Set once a pool is deprecated by the admin
+    Deprecation(Address),
+    /// Pool whose LP shares a stake contract holds
+    StakePool(Address),
+}
+
+#[contracttype]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PoolRegistryInfo {
+    pub pool_type: PoolType,
+    pub token_a: Address,
+    pub token_b: Address,
+    /// Swap fee the pool was created with, part of its V3 routing key
+    pub fee_tier: i64,
+}
+
+#[derive(Clone)]
+#[contracttype]
+pub enum UpgradeDataKey {
+    /// Latest version a pool or stake contract was upgraded to by the factory
+    Version(Address),
+}
+
+#[contracttype]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ContractVersion {
+    pub wasm_hash: BytesN<32>,
+    /// Version reported by the upgraded contract
+    pub version: String,
+    pub upgraded_at: u64,
+}
+
+/// Token list curated by the admin, sharded like the pool registry, and the
+/// index of the pools of each token.
+#[derive(Clone)]
+#[contracttype]
+pub enum TokenRegistryKey {
+    /// Number of tokens in the list
+    Count,
+    /// Up to `TOKEN_REGISTRY_SHARD_SIZE` token addresses, in listing order
+    Shard(u32),
+    /// Position of a token in the list
+    Position(Address),
+    /// Metadata of a listed token
+    Info(Address),
+    /// Number of pools with the token as one of their assets
+    PoolCount(Address),
+    /// Up to `TOKEN_REGISTRY_SHARD_SIZE` pools of the token, in creation order
+    PoolShard(Address, u32),
+    /// Position of a pool in the pools of the token
+    PoolPosition(Address, Address),
+}
+
+/// Token list entry
+#[contracttype]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TokenInfo {
+    pub address: Address,
+    pub symbol: String,
+    pub decimals: u32,
+    pub verified: bool,
+    /// SHA-256 hash of the logo image, to check the one served at `logo_uri`
+    pub logo_hash: Option<Bytes>,
+    pub logo_uri: Option<String>,
+}
+
+#[derive(Clone)]
+#[contracttype]
+pub enum PoolTemplateKey {
+    /// Default parameters for new pools of a type
+    Template(PoolType),
+}
+
+/// Default parameters of the pools of a type, applied by
+/// `create_pool_from_template` where no override is given
+#[contracttype]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PoolTemplate {
+    pub fee_recipient: Address,
+    pub swap_fee_bps: i64,
+    pub max_allowed_slippage_bps: i64,
+    pub default_slippage_bps: i64,
+    pub max_allowed_spread_bps: i64,
+    pub max_referral_bps: i64,
+    pub max_allowed_fee_bps: i64,
+    pub stake_manager: Address,
+    pub min_bond: i128,
+    pub min_reward: i128,
+    pub max_complexity: u32,
+    /// Amplification of Stable pools
+    pub amp: Option<u64>,
+}
+
+/// Per-pool overrides of the `PoolTemplate` fields
+#[contracttype]
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct PoolParamsOverrides {
+    /// Admin of the pool, the factory when `None`
+    pub admin: Option<Address>,
+    pub fee_recipient: Option<Address>,
+    pub swap_fee_bps: Option<i64>,
+    pub max_allowed_slippage_bps: Option<i64>,
+    pub default_slippage_bps: Option<i64>,
+    pub max_allowed_spread_bps: Option<i64>,
+    pub max_referral_bps: Option<i64>,
+    pub max_allowed_fee_bps: Option<i64>,
+    pub stake_manager: Option<Address>,
+    pub min_bond: Option<i128>,
+    pub min_reward: Option<i128>,
+    pub max_complexity: Option<u32>,
+    pub amp: Option<u64>,
+}
+
+/// Liquidity provided by the creator of a pool in the call that creates it,
+/// so the initial price can't be set by someone else
+#[contracttype]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct InitialLiquidity {
+    pub amount_a: i128,
+    pub amount_b: i128,
+    /// Trading floors of Blend pools, zero leaves them unset
+    pub min_trading_balance_a: i128,
+    pub min_trading_balance_b: i128,
+}
+
+/// A pool removed from routing. It stays in the registry so liquidity can
+/// still be withdrawn or migrated to the replacement.
+#[contracttype]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PoolDeprecation {
+    pub replacement: Option<Address>,
+    pub deprecated_at: u64,
+    pub swaps_paused: bool,
+}
+
+/// Outcome of upgrading a single pool or stake contract in a batch
+#[contracttype]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct UpgradeResult {
+    pub contract: Address,
+    pub upgraded: bool,
+}
+
 impl TryFromVal<Env, DataKey> for Val {
     type Error = ConversionError;
 
@@ -64,6 +239,31 @@ pub struct Config {
     pub lp_token_decimals: u32,
 }
 
+/// Settings for pools created by accounts outside of the whitelist. Kept apart
+/// from `Config` so factories deployed before it can still read their config.
+#[contracttype]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PermissionlessPoolConfig {
+    /// Whether non-whitelisted accounts can create pools at all
+    pub enabled: bool,
+    /// Token the creation fee is paid in
+    pub fee_token: Address,
+    /// Creation fee paid by every non-whitelisted creator
+    pub fee_amount: i128,
+    /// Receives the creation fees
+    pub treasury: Address,
+    /// Swap fees in bps a permissionless pool can be created with
+    pub allowed_fee_tiers: Vec<i64>,
+    /// Upper bound for the pool's max allowed and default slippage
+    pub max_slippage_bps: i64,
+    /// Upper bound for the pool's max allowed spread
+    pub max_spread_bps: i64,
+    /// Upper bound for the pool's max referral fee
+    pub max_referral_bps: i64,
+    /// Upper bound for the number of stakes per user in the pool's stake contract
+    pub max_complexity: u32,
+}
+
 pub fn save_stable_wasm_hash(env: &Env, hash: BytesN<32>) {
     env.storage().persistent().set(&STABLE_WASM_HASH, &hash);
     env.storage().persistent().extend_ttl(
@@ -134,6 +334,52 @@ pub struct StakePortfolio {
     pub stakes: Vec<Stake>,
 }
 
+/// An amount of a token together with its value in the quote token
+#[contracttype]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ValuedAsset {
+    pub asset: Asset,
+    pub value: i128,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ValuedLpPosition {
+    pub pool: Address,
+    pub assets: (ValuedAsset, ValuedAsset),
+    pub value: i128,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ValuedStakePosition {
+    pub pool: Address,
+    pub staking_contract: Address,
+    pub staked_shares: i128,
+    /// Pool tokens the staked LP shares are worth
+    pub assets: (ValuedAsset, ValuedAsset),
+    /// Value of the staked LP shares
+    pub value: i128,
+    pub pending_rewards: Vec<ValuedAsset>,
+    pub rewards_value: i128,
+}
+
+/// Portfolio of a user valued in `quote_token` at the spot prices of the
+/// Phoenix pools
+#[contracttype]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ValuedUserPortfolio {
+    pub quote_token: Address,
+    pub lp_positions: Vec<ValuedLpPosition>,
+    pub stake_positions: Vec<ValuedStakePosition>,
+    pub total_lp_value: i128,
+    pub total_staked_value: i128,
+    pub total_rewards_value: i128,
+    pub total_value: i128,
+    /// Tokens without a pool against the quote token, valued at 0
+    pub unpriced_tokens: Vec<Address>,
+}
+
 #[contracttype]
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub struct Asset {
@@ -163,6 +409,37 @@ pub struct LiquidityPoolInfo {
     pub pool_address: Address,
     pub pool_response: PoolResponse,
     pub total_fee_bps: i64,
+    /// Whether the pool was deprecated, see `PoolDeprecation`
+    pub deprecated: bool,
+    pub replacement: Option<Address>,
+}
+
+/// A page of `query_pools_paginated`. Filtered pages can come back short or
+/// empty before the end of the registry, the scan goes on from `next_start_after`.
+#[contracttype]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PoolsPage {
+    pub pools: Vec<Address>,
+    /// Last pool scanned, `None` once there are no more pools to scan
+    pub next_start_after: Option<Address>,
+}
+
+/// A page of `query_pools_details_paginated`, see `PoolsPage`
+#[contracttype]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PoolsDetailsPage {
+    pub pools: Vec<LiquidityPoolInfo>,
+    /// Last pool scanned, `None` once there are no more pools to scan
+    pub next_start_after: Option<Address>,
+}
+
+/// Pool info as returned by the pools' `query_pool_info_for_factory`
+#[contracttype]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PoolInfoResponse {
+    pub pool_address: Address,
+    pub pool_response: PoolResponse,
+    pub total_fee_bps: i64,
 }
 
 #[contracttype]
@@ -206,6 +483,33 @@ pub fn get_config(env: &Env) -> Config {
     config
 }
 
+pub fn save_permissionless_config(env: &Env, config: &PermissionlessPoolConfig) {
+    env.storage()
+        .persistent()
+        .set(&DataKey::PermissionlessConfig, config);
+    env.storage().persistent().extend_ttl(
+        &DataKey::PermissionlessConfig,
+        PERSISTENT_RENEWAL_THRESHOLD,
+        PERSISTENT_TARGET_TTL,
+    );
+}
+
+pub fn get_permissionless_config(env: &Env) -> Option<PermissionlessPoolConfig> {
+    let config = env
+        .storage()
+        .persistent()
+        .get(&DataKey::PermissionlessConfig);
+    if config.is_some() {
+        env.storage().persistent().extend_ttl(
+            &DataKey::PermissionlessConfig,
+            PERSISTENT_RENEWAL_THRESHOLD,
+            PERSISTENT_TARGET_TTL,
+        );
+    }
+
+    config
+}
+
 pub fn _save_admin(env: &Env, admin_addr: Address) {
     env.storage().instance().set(&ADMIN, &admin_addr);
 
@@ -227,31 +531,158 @@ pub fn _get_admin(env: &Env) -> Address {
     admin_addr
 }
 
-pub fn get_lp_vec(env: &Env) -> Vec<Address> {
-    let lp_vec = env
+pub fn init_pool_registry(env: &Env) {
+    env.storage()
+        .persistent()
+        .set(&PoolRegistryKey::Count, &0u32);
+    env.storage().persistent().extend_ttl(
+        &PoolRegistryKey::Count,
+        PERSISTENT_RENEWAL_THRESHOLD,
+        PERSISTENT_TARGET_TTL,
+    );
+}
+
+/// Moves the pools of the deprecated `LpVec` into the sharded registry, once,
+/// for factories created before it.
+fn migrate_lp_vec(env: &Env) {
+    let lp_vec: Vec<Address> = env
         .storage()
         .persistent()
         .get(&DataKey::LpVec)
-        .expect("Factory: get_lp_vec: Liquidity Pool vector not found");
+        .unwrap_or(Vec::new(env));
+
+    init_pool_registry(env);
+    for pool in lp_vec.iter() {
+        register_pool(env, &pool);
+    }
+    env.storage().persistent().remove(&DataKey::LpVec);
+}
+
+pub fn get_pool_count(env: &Env) -> u32 {
+    if !env.storage().persistent().has(&PoolRegistryKey::Count) {
+        migrate_lp_vec(env);
+    }
 
+    let count = env
+        .storage()
+        .persistent()
+        .get(&PoolRegistryKey::Count)
+        .unwrap_or_default();
+    env.storage().persistent().extend_ttl(
+        &PoolRegistryKey::Count,
+        PERSISTENT_RENEWAL_THRESHOLD,
+        PERSISTENT_TARGET_TTL,
+    );
+
+    count
+}
+
+fn register_pool(env: &Env, pool: &Address) {
+    let count = get_pool_count(env);
+
+    let shard_key = PoolRegistryKey::Shard(count / POOL_REGISTRY_SHARD_SIZE);
+    let mut shard: Vec<Address> = env
+        .storage()
+        .persistent()
+        .get(&shard_key)
+        .unwrap_or(Vec::new(env));
+    shard.push_back(pool.clone());
+
+    env.storage().persistent().set(&shard_key, &shard);
     env.storage().persistent().extend_ttl(
-        &DataKey::LpVec,
+        &shard_key,
         PERSISTENT_RENEWAL_THRESHOLD,
         PERSISTENT_TARGET_TTL,
     );
 
-    lp_vec
+    let position_key = PoolRegistryKey::Position(pool.clone());
+    env.storage().persistent().set(&position_key, &count);
+    env.storage().persistent().extend_ttl(
+        &position_key,
+        PERSISTENT_RENEWAL_THRESHOLD,
+        PERSISTENT_TARGET_TTL,
+    );
+
+    env.storage()
+        .persistent()
+        .set(&PoolRegistryKey::Count, &(count + 1));
+}
+
+pub fn save_pool(env: &Env, pool: &Address, info: &PoolRegistryInfo) {
+    register_pool(env, pool);
+    save_pool_registry_info(env, pool, info);
+}
+
+/// Up to `limit` pools starting from the `start` position, in creation order.
+pub fn get_pools(env: &Env, start: u32, limit: u32) -> Vec<Address> {
+    let end = start.saturating_add(limit).min(get_pool_count(env));
+
+    let mut pools = Vec::new(env);
+    let mut index = start;
+    while index < end {
+        let shard_key = PoolRegistryKey::Shard(index / POOL_REGISTRY_SHARD_SIZE);
+        let shard: Vec<Address> = env
+            .storage()
+            .persistent()
+            .get(&shard_key)
+            .expect("Factory: get_pools: Pool registry shard not found");
+        env.storage().persistent().extend_ttl(
+            &shard_key,
+            PERSISTENT_RENEWAL_THRESHOLD,
+            PERSISTENT_TARGET_TTL,
+        );
+
+        let from = index % POOL_REGISTRY_SHARD_SIZE;
+        let to = POOL_REGISTRY_SHARD_SIZE.min(from + end - index);
+        pools.append(&shard.slice(from..to));
+        index += to - from;
+    }
+
+    pools
 }
 
-pub fn save_lp_vec(env: &Env, lp_info: Vec<Address>) {
-    env.storage().persistent().set(&DataKey::LpVec, &lp_info);
+pub fn get_lp_vec(env: &Env) -> Vec<Address> {
+    get_pools(env, 0, get_pool_count(env))
+}
+
+pub fn get_pool_position(env: &Env, pool: &Address) -> Option<u32> {
+    let key = PoolRegistryKey::Position(pool.clone());
+    let position = env.storage().persistent().get(&key);
+    if position.is_some() {
+        env.storage().persistent().extend_ttl(
+            &key,
+            PERSISTENT_RENEWAL_THRESHOLD,
+            PERSISTENT_TARGET_TTL,
+        );
+    }
+
+    position
+}
+
+pub fn save_pool_registry_info(env: &Env, pool: &Address, info: &PoolRegistryInfo) {
+    let key = PoolRegistryKey::Info(pool.clone());
+    env.storage().persistent().set(&key, info);
     env.storage().persistent().extend_ttl(
-        &DataKey::LpVec,
+        &key,
         PERSISTENT_RENEWAL_THRESHOLD,
         PERSISTENT_TARGET_TTL,
     );
 }
 
+pub fn get_pool_registry_info(env: &Env, pool: &Address) -> Option<PoolRegistryInfo> {
+    let key = PoolRegistryKey::Info(pool.clone());
+    let info = env.storage().persistent().get(&key);
+    if info.is_some() {
+        env.storage().persistent().extend_ttl(
+            &key,
+            PERSISTENT_RENEWAL_THRESHOLD,
+            PERSISTENT_TARGET_TTL,
+        );
+    }
+
+    info
+}
+
 pub fn save_lp_vec_with_tuple_as_key(
     env: &Env,
     tuple_pool: (&Address, &Address),
@@ -294,6 +725,55 @@ pub fn save_lp_vec_with_tuple_v2_as_key(
     );
 }
 
+/// Removes the legacy, V2 and V3 routing slots of `(token_a, token_b)` that
+/// point to `lp_address`.
+pub fn remove_lp_routing(
+    env: &Env,
+    pool_type: PoolType,
+    fee_tier: i64,
+    tuple_pool: (&Address, &Address),
+    lp_address: &Address,
+) {
+    let (token_a, token_b) = (tuple_pool.0.clone(), tuple_pool.1.clone());
+    remove_key_if_points_to(
+        env,
+        &PairTupleKey {
+            token_a: token_a.clone(),
+            token_b: token_b.clone(),
+        },
+        lp_address,
+    );
+    remove_key_if_points_to(
+        env,
+        &PairTupleKeyV2 {
+            pool_type,
+            token_a: token_a.clone(),
+            token_b: token_b.clone(),
+        },
+        lp_address,
+    );
+    remove_key_if_points_to(
+        env,
+        &PairTupleKeyV3 {
+            pool_type,
+            fee_tier,
+            token_a,
+            token_b,
+        },
+        lp_address,
+    );
+}
+
+fn remove_key_if_points_to<K>(env: &Env, key: &K, lp_address: &Address)
+where
+    K: IntoVal<Env, Val>,
+{
+    let pool: Option<Address> = env.storage().persistent().get(key);
+    if pool.as_ref() == Some(lp_address) {
+        env.storage().persistent().remove(key);
+    }
+}
+
 /// Read a pool address by `(pool_type, token_a, token_b)`. Caller is
 /// responsible for trying both orderings.
 pub fn get_lp_by_tuple_v2(
@@ -317,3 +797,362 @@ pub fn get_lp_by_tuple_v2(
     }
     result
 }
+
+pub fn save_lp_vec_with_tuple_v3_as_key(
+    env: &Env,
+    pool_type: PoolType,
+    fee_tier: i64,
+    tuple_pool: (&Address, &Address),
+    lp_address: &Address,
+) {
+    let key = PairTupleKeyV3 {
+        pool_type,
+        fee_tier,
+        token_a: tuple_pool.0.clone(),
+        token_b: tuple_pool.1.clone(),
+    };
+    env.storage().persistent().set(&key, &lp_address);
+    env.storage().persistent().extend_ttl(
+        &key,
+        PERSISTENT_RENEWAL_THRESHOLD,
+        PERSISTENT_TARGET_TTL,
+    );
+}
+
+/// Read a pool address by `(pool_type, fee_tier, token_a, token_b)`. Caller is
+/// responsible for trying both orderings.
+pub fn get_lp_by_tuple_v3(
+    env: &Env,
+    pool_type: PoolType,
+    fee_tier: i64,
+    token_a: &Address,
+    token_b: &Address,
+) -> Option<Address> {
+    let key = PairTupleKeyV3 {
+        pool_type,
+        fee_tier,
+        token_a: token_a.clone(),
+        token_b: token_b.clone(),
+    };
+    let result: Option<Address> = env.storage().persistent().get(&key);
+    if result.is_some() {
+        env.storage().persistent().extend_ttl(
+            &key,
+            PERSISTENT_RENEWAL_THRESHOLD,
+            PERSISTENT_TARGET_TTL,
+        );
+    }
+    result
+}
+
+pub fn save_contract_version(env: &Env, contract: &Address, version: &ContractVersion) {
+    let key = UpgradeDataKey::Version(contract.clone());
+    env.storage().persistent().set(&key, version);
+    env.storage().persistent().extend_ttl(
+        &key,
+        PERSISTENT_RENEWAL_THRESHOLD,
+        PERSISTENT_TARGET_TTL,
+    );
+}
+
+pub fn get_contract_version(env: &Env, contract: &Address) -> Option<ContractVersion> {
+    let key = UpgradeDataKey::Version(contract.clone());
+    let version = env.storage().persistent().get(&key);
+    if version.is_some() {
+        env.storage().persistent().extend_ttl(
+            &key,
+            PERSISTENT_RENEWAL_THRESHOLD,
+            PERSISTENT_TARGET_TTL,
+        );
+    }
+
+    version
+}
+
+pub fn save_pool_deprecation(env: &Env, pool: &Address, deprecation: &PoolDeprecation) {
+    let key = PoolRegistryKey::Deprecation(pool.clone());
+    env.storage().persistent().set(&key, deprecation);
+    env.storage().persistent().extend_ttl(
+        &key,
+        PERSISTENT_RENEWAL_THRESHOLD,
+        PERSISTENT_TARGET_TTL,
+    );
+}
+
+pub fn get_pool_deprecation(env: &Env, pool: &Address) -> Option<PoolDeprecation> {
+    let key = PoolRegistryKey::Deprecation(pool.clone());
+    let deprecation = env.storage().persistent().get(&key);
+    if deprecation.is_some() {
+        env.storage().persistent().extend_ttl(
+            &key,
+            PERSISTENT_RENEWAL_THRESHOLD,
+            PERSISTENT_TARGET_TTL,
+        );
+    }
+
+    deprecation
+}
+
+pub fn save_stake_pool(env: &Env, stake: &Address, pool: &Address) {
+    let key = PoolRegistryKey::StakePool(stake.clone());
+    env.storage().persistent().set(&key, pool);
+    env.storage().persistent().extend_ttl(
+        &key,
+        PERSISTENT_RENEWAL_THRESHOLD,
+        PERSISTENT_TARGET_TTL,
+    );
+}
+
+pub fn get_stake_pool(env: &Env, stake: &Address) -> Option<Address> {
+    let key = PoolRegistryKey::StakePool(stake.clone());
+    let pool = env.storage().persistent().get(&key);
+    if pool.is_some() {
+        env.storage().persistent().extend_ttl(
+            &key,
+            PERSISTENT_RENEWAL_THRESHOLD,
+            PERSISTENT_TARGET_TTL,
+        );
+    }
+
+    pool
+}
+
+pub fn save_pool_template(env: &Env, pool_type: PoolType, template: &PoolTemplate) {
+    let key = PoolTemplateKey::Template(pool_type);
+    env.storage().persistent().set(&key, template);
+    env.storage().persistent().extend_ttl(
+        &key,
+        PERSISTENT_RENEWAL_THRESHOLD,
+        PERSISTENT_TARGET_TTL,
+    );
+}
+
+pub fn get_pool_template(env: &Env, pool_type: PoolType) -> Option<PoolTemplate> {
+    let key = PoolTemplateKey::Template(pool_type);
+    let template = env.storage().persistent().get(&key);
+    if template.is_some() {
+        env.storage().persistent().extend_ttl(
+            &key,
+            PERSISTENT_RENEWAL_THRESHOLD,
+            PERSISTENT_TARGET_TTL,
+        );
+    }
+
+    template
+}
+
+/// Lists the token of `info`, or updates its metadata when already listed.
+pub fn save_token_info(env: &Env, info: &TokenInfo) {
+    if get_token_position(env, &info.address).is_none() {
+        let count: u32 = env
+            .storage()
+            .persistent()
+            .get(&TokenRegistryKey::Count)
+            .unwrap_or_default();
+
+        let shard_key = TokenRegistryKey::Shard(count / TOKEN_REGISTRY_SHARD_SIZE);
+        let mut shard: Vec<Address> = env
+            .storage()
+            .persistent()
+            .get(&shard_key)
+            .unwrap_or(Vec::new(env));
+        shard.push_back(info.address.clone());
+
+        env.storage().persistent().set(&shard_key, &shard);
+        env.storage().persistent().extend_ttl(
+            &shard_key,
+            PERSISTENT_RENEWAL_THRESHOLD,
+            PERSISTENT_TARGET_TTL,
+        );
+
+        let position_key = TokenRegistryKey::Position(info.address.clone());
+        env.storage().persistent().set(&position_key, &count);
+        env.storage().persistent().extend_ttl(
+            &position_key,
+            PERSISTENT_RENEWAL_THRESHOLD,
+            PERSISTENT_TARGET_TTL,
+        );
+
+        env.storage()
+            .persistent()
+            .set(&TokenRegistryKey::Count, &(count + 1));
+        env.storage().persistent().extend_ttl(
+            &TokenRegistryKey::Count,
+            PERSISTENT_RENEWAL_THRESHOLD,
+            PERSISTENT_TARGET_TTL,
+        );
+    }
+
+    let key = TokenRegistryKey::Info(info.address.clone());
+    env.storage().persistent().set(&key, info);
+    env.storage().persistent().extend_ttl(
+        &key,
+        PERSISTENT_RENEWAL_THRESHOLD,
+        PERSISTENT_TARGET_TTL,
+    );
+}
+
+pub fn get_token_info(env: &Env, token: &Address) -> Option<TokenInfo> {
+    let key = TokenRegistryKey::Info(token.clone());
+    let info = env.storage().persistent().get(&key);
+    if info.is_some() {
+        env.storage().persistent().extend_ttl(
+            &key,
+            PERSISTENT_RENEWAL_THRESHOLD,
+            PERSISTENT_TARGET_TTL,
+        );
+    }
+
+    info
+}
+
+pub fn get_token_position(env: &Env, token: &Address) -> Option<u32> {
+    let key = TokenRegistryKey::Position(token.clone());
+    let position = env.storage().persistent().get(&key);
+    if position.is_some() {
+        env.storage().persistent().extend_ttl(
+            &key,
+            PERSISTENT_RENEWAL_THRESHOLD,
+            PERSISTENT_TARGET_TTL,
+        );
+    }
+
+    position
+}
+
+/// Up to `limit` listed tokens starting from the `start` position, in listing
+/// order.
+pub fn get_tokens(env: &Env, start: u32, limit: u32) -> Vec<TokenInfo> {
+    let count: u32 = env
+        .storage()
+        .persistent()
+        .get(&TokenRegistryKey::Count)
+        .unwrap_or_default();
+    let end = start.saturating_add(limit).min(count);
+
+    let mut tokens = Vec::new(env);
+    let mut index = start;
+    while index < end {
+        let shard_key = TokenRegistryKey::Shard(index / TOKEN_REGISTRY_SHARD_SIZE);
+        let shard: Vec<Address> = env
+            .storage()
+            .persistent()
+            .get(&shard_key)
+            .expect("Factory: get_tokens: Token registry shard not found");
+        env.storage().persistent().extend_ttl(
+            &shard_key,
+            PERSISTENT_RENEWAL_THRESHOLD,
+            PERSISTENT_TARGET_TTL,
+        );
+
+        let from = index % TOKEN_REGISTRY_SHARD_SIZE;
+        let to = TOKEN_REGISTRY_SHARD_SIZE.min(from + end - index);
+        for token in shard.slice(from..to) {
+            tokens.push_back(
+                get_token_info(env, &token).expect("Factory: get_tokens: Token info not found"),
+            );
+        }
+        index += to - from;
+    }
+
+    tokens
+}
+
+/// Adds `pool` to the pools of `token`, once.
+pub fn add_pool_for_token(env: &Env, token: &Address, pool: &Address) {
+    if get_token_pool_position(env, token, pool).is_some() {
+        return;
+    }
+    let count = get_token_pool_count(env, token);
+
+    let shard_key = TokenRegistryKey::PoolShard(token.clone(), count / TOKEN_REGISTRY_SHARD_SIZE);
+    let mut shard: Vec<Address> = env
+        .storage()
+        .persistent()
+        .get(&shard_key)
+        .unwrap_or(Vec::new(env));
+    shard.push_back(pool.clone());
+
+    env.storage().persistent().set(&shard_key, &shard);
+    env.storage().persistent().extend_ttl(
+        &shard_key,
+        PERSISTENT_RENEWAL_THRESHOLD,
+        PERSISTENT_TARGET_TTL,
+    );
+
+    let position_key = TokenRegistryKey::PoolPosition(token.clone(), pool.clone());
+    env.storage().persistent().set(&position_key, &count);
+    env.storage().persistent().extend_ttl(
+        &position_key,
+        PERSISTENT_RENEWAL_THRESHOLD,
+        PERSISTENT_TARGET_TTL,
+    );
+
+    let count_key = TokenRegistryKey::PoolCount(token.clone());
+    env.storage().persistent().set(&count_key, &(count + 1));
+    env.storage().persistent().extend_ttl(
+        &count_key,
+        PERSISTENT_RENEWAL_THRESHOLD,
+        PERSISTENT_TARGET_TTL,
+    );
+}
+
+pub fn get_token_pool_count(env: &Env, token: &Address) -> u32 {
+    let key = TokenRegistryKey::PoolCount(token.clone());
+    let count = env.storage().persistent().get(&key);
+    if count.is_some() {
+        env.storage().persistent().extend_ttl(
+            &key,
+            PERSISTENT_RENEWAL_THRESHOLD,
+            PERSISTENT_TARGET_TTL,
+        );
+    }
+
+    count.unwrap_or_default()
+}
+
+pub fn get_token_pool_position(env: &Env, token: &Address, pool: &Address) -> Option<u32> {
+    let key = TokenRegistryKey::PoolPosition(token.clone(), pool.clone());
+    let position = env.storage().persistent().get(&key);
+    if position.is_some() {
+        env.storage().persistent().extend_ttl(
+            &key,
+            PERSISTENT_RENEWAL_THRESHOLD,
+            PERSISTENT_TARGET_TTL,
+        );
+    }
+
+    position
+}
+
+/// Up to `limit` pools of `token` starting from the `start` position, in
+/// creation order.
+pub fn get_pools_for_token(env: &Env, token: &Address, start: u32, limit: u32) -> Vec<Address> {
+    let end = start
+        .saturating_add(limit)
+        .min(get_token_pool_count(env, token));
+
+    let mut pools = Vec::new(env);
+    let mut index = start;
+    while index < end {
+        let shard_key =
+            TokenRegistryKey::PoolShard(token.clone(), index / TOKEN_REGISTRY_SHARD_SIZE);
+        let shard: Vec<Address> = env
+            .storage()
+            .persistent()
+            .get(&shard_key)
+            .expect("Factory: get_pools_for_token: Token pools shard not found");
+        env.storage().persistent().extend_ttl(
+            &shard_key,
+            PERSISTENT_RENEWAL_THRESHOLD,
+            PERSISTENT_TARGET_TTL,
+        );
+
+        let from = index % TOKEN_REGISTRY_SHARD_SIZE;
+        let to = TOKEN_REGISTRY_SHARD_SIZE.min(from + end - index);
+        pools.append(&shard.slice(from..to));
+        index += to - from;
+    }
+
+    pools
+}
@@ -1,5 +1,15 @@
 mod admin_change;
 mod config;
+mod deprecation;
+mod fee_tiers;
+mod initial_liquidity;
+mod pagination;
+mod permissionless;
 mod pool_coexistence;
 mod queries;
 mod setup;
+mod stake_compound;
+mod templates;
+mod token_registry;
+mod upgrade;
+mod valuation;
@@ -0,0 +1,313 @@
+use super::setup::{
+    deploy_factory_contract, generate_lp_init_info, install_and_deploy_token_contract, lp_contract,
+};
+use crate::{contract::FactoryClient, storage::PoolDeprecation, token_contract};
+
+use phoenix::utils::{LiquidityPoolInitInfo, PoolType};
+use soroban_sdk::{
+    testutils::{Address as _, Ledger},
+    Address, Env, String,
+};
+
+fn setup<'a>(
+    env: &Env,
+) -> (
+    FactoryClient<'a>,
+    Address,
+    LiquidityPoolInitInfo,
+    token_contract::Client<'a>,
+    token_contract::Client<'a>,
+) {
+    env.mock_all_auths();
+    env.cost_estimate().budget().reset_unlimited();
+
+    let admin = Address::generate(env);
+    let mut token1: token_contract::Client = install_and_deploy_token_contract(
+        env,
+        admin.clone(),
+        7,
+        String::from_str(env, "Phoenix"),
+        String::from_str(env, "PHO"),
+    );
+    let mut token2 = install_and_deploy_token_contract(
+        env,
+        admin.clone(),
+        7,
+        String::from_str(env, "Stellar"),
+        String::from_str(env, "XLM"),
+    );
+    if token2.address < token1.address {
+        core::mem::swap(&mut token1, &mut token2);
+    }
+
+    let factory = deploy_factory_contract(env, Some(admin.clone()));
+    let lp_init_info = generate_lp_init_info(
+        token1.address.clone(),
+        token2.address.clone(),
+        Address::generate(env),
+        admin.clone(),
+        Address::generate(env),
+    );
+
+    (factory, admin, lp_init_info, token1, token2)
+}
+
+fn create_pool_with_tier(
+    env: &Env,
+    factory: &FactoryClient,
+    admin: &Address,
+    lp_init_info: &LiquidityPoolInitInfo,
+    fee_tier: i64,
+) -> Address {
+    let mut lp_init_info = lp_init_info.clone();
+    lp_init_info.swap_fee_bps = fee_tier;
+    factory.create_liquidity_pool(
+        admin,
+        &lp_init_info,
+        &String::from_str(env, "Pool"),
+        &String::from_str(env, "PHO/XLM"),
+        &PoolType::Xyk,
+        &None::<u64>,
+        &100i64,
+        &1_000,
+        &None,
+    )
+}
+
+fn provide_liquidity(
+    env: &Env,
+    pool: &Address,
+    user: &Address,
+    token1: &token_contract::Client,
+    token2: &token_contract::Client,
+    amount: i128,
+) {
+    token1.mint(user, &amount);
+    token2.mint(user, &amount);
+    lp_contract::Client::new(env, pool).provide_liquidity(
+        user,
+        &Some(amount),
+        &None::<i128>,
+        &Some(amount),
+        &None::<i128>,
+        &None::<i64>,
+        &None::<u64>,
+        &false,
+    );
+}
+
+#[test]
+fn deprecated_pool_is_removed_from_routing_and_marked() {
+    let env = Env::default();
+    let (factory, admin, lp_init_info, _, _) = setup(&env);
+    let token_a = lp_init_info.token_init_info.token_a.clone();
+    let token_b = lp_init_info.token_init_info.token_b.clone();
+
+    let pool_30 = create_pool_with_tier(&env, &factory, &admin, &lp_init_info, 30);
+    let pool_5 = create_pool_with_tier(&env, &factory, &admin, &lp_init_info, 5);
+    assert_eq!(
+        factory.query_for_pool_by_token_pair(&token_a, &token_b),
+        pool_30
+    );
+
+    env.ledger().with_mut(|li| li.timestamp = 1_000);
+    factory.deprecate_pool(&pool_30, &Some(pool_5.clone()), &false);
+
+    // the replacement takes over the default routing of the pair
+    assert_eq!(
+        factory.query_for_pool_by_token_pair(&token_a, &token_b),
+        pool_5
+    );
+    assert_eq!(
+        factory.query_pool_by_pair_type(&token_b, &token_a, &PoolType::Xyk),
+        pool_5
+    );
+    assert!(factory
+        .try_query_pool_by_pair_type_and_tier(&token_a, &token_b, &PoolType::Xyk, &30)
+        .is_err());
+
+    // the deprecated pool stays in the registry, marked as such
+    assert_eq!(factory.query_pools().len(), 2);
+    let details = factory.query_pool_details(&pool_30);
+    assert!(details.deprecated);
+    assert_eq!(details.replacement, Some(pool_5.clone()));
+    assert_eq!(
+        factory.query_pool_deprecation(&pool_30),
+        Some(PoolDeprecation {
+            replacement: Some(pool_5.clone()),
+            deprecated_at: 1_000,
+            swaps_paused: false,
+        })
+    );
+    assert!(!factory.query_pool_details(&pool_5).deprecated);
+    assert_eq!(factory.query_pool_deprecation(&pool_5), None);
+    assert!(!lp_contract::Client::new(&env, &pool_30).query_swaps_paused());
+}
+
+#[test]
+fn deprecation_uses_the_fee_tier_of_the_creation() {
+    let env = Env::default();
+    let (factory, admin, lp_init_info, _, _) = setup(&env);
+    let token_a = lp_init_info.token_init_info.token_a.clone();
+    let token_b = lp_init_info.token_init_info.token_b.clone();
+
+    let pool_30 = create_pool_with_tier(&env, &factory, &admin, &lp_init_info, 30);
+    // the pool admin changes the swap fee, the pool keeps its fee tier
+    lp_contract::Client::new(&env, &pool_30).update_config(
+        &None,
+        &Some(50),
+        &None,
+        &None,
+        &None,
+        &None,
+    );
+    assert_eq!(
+        factory.query_pool_by_pair_type_and_tier(&token_a, &token_b, &PoolType::Xyk, &30),
+        pool_30
+    );
+
+    factory.deprecate_pool(&pool_30, &None, &false);
+    assert!(factory
+        .try_query_pool_by_pair_type_and_tier(&token_a, &token_b, &PoolType::Xyk, &30)
+        .is_err());
+}
+
+#[test]
+fn deprecated_pool_can_pause_swaps_and_still_be_withdrawn_from() {
+    let env = Env::default();
+    let (factory, admin, lp_init_info, token1, token2) = setup(&env);
+    let user = Address::generate(&env);
+
+    let pool = create_pool_with_tier(&env, &factory, &admin, &lp_init_info, 30);
+    provide_liquidity(&env, &pool, &user, &token1, &token2, 1_000_000);
+
+    factory.deprecate_pool(&pool, &None, &true);
+
+    let pool_client = lp_contract::Client::new(&env, &pool);
+    assert!(pool_client.query_swaps_paused());
+    assert!(factory
+        .try_query_for_pool_by_token_pair(&token1.address, &token2.address)
+        .is_err());
+
+    token1.mint(&user, &1_000);
+    assert!(pool_client
+        .try_swap(
+            &user,
+            &token1.address,
+            &1_000,
+            &None::<i128>,
+            &None::<i64>,
+            &None::<u64>,
+            &None::<i64>,
+        )
+        .is_err());
+
+    let share_token = token_contract::Client::new(
+        &env,
+        &factory
+            .query_pool_details(&pool)
+            .pool_response
+            .asset_lp_share
+            .address,
+    );
+    let shares = share_token.balance(&user);
+    pool_client.withdraw_liquidity(&user, &shares, &1, &1, &None::<u64>, &None);
+    assert_eq!(share_token.balance(&user), 0);
+    assert_eq!(token1.balance(&user), 999_000 + 1_000);
+}
+
+#[test]
+fn migrate_liquidity_moves_lp_to_the_replacement() {
+    let env = Env::default();
+    let (factory, admin, lp_init_info, token1, token2) = setup(&env);
+    let user = Address::generate(&env);
+
+    let pool_30 = create_pool_with_tier(&env, &factory, &admin, &lp_init_info, 30);
+    let pool_5 = create_pool_with_tier(&env, &factory, &admin, &lp_init_info, 5);
+    provide_liquidity(&env, &pool_30, &user, &token1, &token2, 1_000_000);
+
+    factory.deprecate_pool(&pool_30, &Some(pool_5.clone()), &true);
+
+    let old_shares = token_contract::Client::new(
+        &env,
+        &factory
+            .query_pool_details(&pool_30)
+            .pool_response
+            .asset_lp_share
+            .address,
+    );
+    let new_shares = token_contract::Client::new(
+        &env,
+        &factory
+            .query_pool_details(&pool_5)
+            .pool_response
+            .asset_lp_share
+            .address,
+    );
+
+    let shares = old_shares.balance(&user);
+    let (amount_a, amount_b) =
+        factory.migrate_liquidity(&user, &pool_30, &shares, &1, &1, &None::<i64>);
+    assert_eq!((amount_a, amount_b), (999_000, 999_000));
+
+    assert_eq!(old_shares.balance(&user), 0);
+    assert!(new_shares.balance(&user) > 0);
+    let pool_5_info = factory.query_pool_details(&pool_5).pool_response;
+    assert_eq!(pool_5_info.asset_a.amount, 999_000);
+    assert_eq!(pool_5_info.asset_b.amount, 999_000);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #103)")]
+fn deprecate_unknown_pool_fails() {
+    let env = Env::default();
+    let (factory, _, _, _, _) = setup(&env);
+
+    factory.deprecate_pool(&Address::generate(&env), &None, &false);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #118)")]
+fn deprecate_pool_twice_fails() {
+    let env = Env::default();
+    let (factory, admin, lp_init_info, _, _) = setup(&env);
+    let pool = create_pool_with_tier(&env, &factory, &admin, &lp_init_info, 30);
+
+    factory.deprecate_pool(&pool, &None, &false);
+    factory.deprecate_pool(&pool, &None, &false);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #119)")]
+fn deprecate_pool_with_deprecated_replacement_fails() {
+    let env = Env::default();
+    let (factory, admin, lp_init_info, _, _) = setup(&env);
+    let pool_30 = create_pool_with_tier(&env, &factory, &admin, &lp_init_info, 30);
+    let pool_5 = create_pool_with_tier(&env, &factory, &admin, &lp_init_info, 5);
+
+    factory.deprecate_pool(&pool_5, &None, &false);
+    factory.deprecate_pool(&pool_30, &Some(pool_5), &false);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #119)")]
+fn deprecate_pool_replaced_by_itself_fails() {
+    let env = Env::default();
+    let (factory, admin, lp_init_info, _, _) = setup(&env);
+    let pool = create_pool_with_tier(&env, &factory, &admin, &lp_init_info, 30);
+
+    factory.deprecate_pool(&pool, &Some(pool.clone()), &false);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #120)")]
+fn migrate_liquidity_without_replacement_fails() {
+    let env = Env::default();
+    let (factory, admin, lp_init_info, token1, token2) = setup(&env);
+    let user = Address::generate(&env);
+    let pool = create_pool_with_tier(&env, &factory, &admin, &lp_init_info, 30);
+    provide_liquidity(&env, &pool, &user, &token1, &token2, 1_000_000);
+
+    factory.deprecate_pool(&pool, &None, &false);
+    factory.migrate_liquidity(&user, &pool, &1_000, &1, &1, &None::<i64>);
+}
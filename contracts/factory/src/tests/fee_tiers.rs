@@ -0,0 +1,140 @@
+use super::setup::{
+    deploy_factory_contract, generate_lp_init_info, install_and_deploy_token_contract,
+};
+use crate::{contract::FactoryClient, token_contract};
+
+use phoenix::utils::{LiquidityPoolInitInfo, PoolType};
+use soroban_sdk::{testutils::Address as _, Address, Env, String};
+
+fn setup<'a>(env: &Env) -> (FactoryClient<'a>, Address, LiquidityPoolInitInfo) {
+    env.mock_all_auths();
+    env.cost_estimate().budget().reset_unlimited();
+
+    let admin = Address::generate(env);
+    let mut token1: token_contract::Client = install_and_deploy_token_contract(
+        env,
+        admin.clone(),
+        7,
+        String::from_str(env, "Phoenix"),
+        String::from_str(env, "PHO"),
+    );
+    let mut token2 = install_and_deploy_token_contract(
+        env,
+        admin.clone(),
+        7,
+        String::from_str(env, "Stellar"),
+        String::from_str(env, "XLM"),
+    );
+    if token2.address < token1.address {
+        core::mem::swap(&mut token1, &mut token2);
+    }
+
+    let factory = deploy_factory_contract(env, Some(admin.clone()));
+    let lp_init_info = generate_lp_init_info(
+        token1.address.clone(),
+        token2.address.clone(),
+        Address::generate(env),
+        admin.clone(),
+        Address::generate(env),
+    );
+
+    (factory, admin, lp_init_info)
+}
+
+fn create_pool_with_tier(
+    env: &Env,
+    factory: &FactoryClient,
+    admin: &Address,
+    lp_init_info: &LiquidityPoolInitInfo,
+    fee_tier: i64,
+) -> Address {
+    let mut lp_init_info = lp_init_info.clone();
+    lp_init_info.swap_fee_bps = fee_tier;
+    factory.create_liquidity_pool(
+        admin,
+        &lp_init_info,
+        &String::from_str(env, "Pool"),
+        &String::from_str(env, "PHO/XLM"),
+        &PoolType::Xyk,
+        &None::<u64>,
+        &100i64,
+        &1_000,
+        &None,
+    )
+}
+
+#[test]
+fn pair_has_one_pool_per_fee_tier() {
+    let env = Env::default();
+    let (factory, admin, lp_init_info) = setup(&env);
+    let token_a = lp_init_info.token_init_info.token_a.clone();
+    let token_b = lp_init_info.token_init_info.token_b.clone();
+
+    let pool_30 = create_pool_with_tier(&env, &factory, &admin, &lp_init_info, 30);
+    let pool_5 = create_pool_with_tier(&env, &factory, &admin, &lp_init_info, 5);
+    let pool_100 = create_pool_with_tier(&env, &factory, &admin, &lp_init_info, 100);
+
+    assert_ne!(pool_30, pool_5);
+    assert_ne!(pool_30, pool_100);
+    assert_ne!(pool_5, pool_100);
+    assert_eq!(factory.query_pools().len(), 3);
+
+    for (pool, fee_tier) in [(&pool_30, 30), (&pool_5, 5), (&pool_100, 100)] {
+        assert_eq!(
+            &factory.query_pool_by_pair_type_and_tier(
+                &token_a,
+                &token_b,
+                &PoolType::Xyk,
+                &fee_tier
+            ),
+            pool
+        );
+        assert_eq!(
+            &factory.query_pool_by_pair_type_and_tier(
+                &token_b,
+                &token_a,
+                &PoolType::Xyk,
+                &fee_tier
+            ),
+            pool
+        );
+        assert_eq!(factory.query_pool_details(pool).total_fee_bps, fee_tier);
+    }
+
+    // the pair lookups without a tier keep resolving the first pool
+    assert_eq!(
+        factory.query_for_pool_by_token_pair(&token_a, &token_b),
+        pool_30
+    );
+    assert_eq!(
+        factory.query_pool_by_pair_type(&token_b, &token_a, &PoolType::Xyk),
+        pool_30
+    );
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #103)")]
+fn query_pool_by_missing_fee_tier_fails() {
+    let env = Env::default();
+    let (factory, admin, lp_init_info) = setup(&env);
+
+    create_pool_with_tier(&env, &factory, &admin, &lp_init_info, 30);
+
+    factory.query_pool_by_pair_type_and_tier(
+        &lp_init_info.token_init_info.token_a,
+        &lp_init_info.token_init_info.token_b,
+        &PoolType::Xyk,
+        &5,
+    );
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #117)")]
+fn second_pool_in_the_same_fee_tier_fails() {
+    let env = Env::default();
+    let (factory, admin, lp_init_info) = setup(&env);
+
+    create_pool_with_tier(&env, &factory, &admin, &lp_init_info, 30);
+    create_pool_with_tier(&env, &factory, &admin, &lp_init_info, 5);
+    create_pool_with_tier(&env, &factory, &admin, &lp_init_info, 5);
+}
@@ -0,0 +1,197 @@
+use super::setup::{
+    blend_lp, deploy_factory_contract, generate_lp_init_info, install_and_deploy_token_contract,
+    install_blend_lp,
+};
+use crate::{contract::FactoryClient, storage::InitialLiquidity, token_contract};
+
+use phoenix::utils::{LiquidityPoolInitInfo, PoolType};
+use soroban_sdk::{testutils::Address as _, Address, Env, String};
+
+fn setup<'a>(
+    env: &Env,
+) -> (
+    FactoryClient<'a>,
+    Address,
+    LiquidityPoolInitInfo,
+    token_contract::Client<'a>,
+    token_contract::Client<'a>,
+) {
+    env.mock_all_auths();
+    env.cost_estimate().budget().reset_unlimited();
+
+    let admin = Address::generate(env);
+    let mut token1 = install_and_deploy_token_contract(
+        env,
+        admin.clone(),
+        7,
+        String::from_str(env, "Phoenix"),
+        String::from_str(env, "PHO"),
+    );
+    let mut token2 = install_and_deploy_token_contract(
+        env,
+        admin.clone(),
+        7,
+        String::from_str(env, "Stellar"),
+        String::from_str(env, "XLM"),
+    );
+    if token2.address < token1.address {
+        core::mem::swap(&mut token1, &mut token2);
+    }
+
+    let factory = deploy_factory_contract(env, Some(admin.clone()));
+    factory.set_blend_wasm_hash(&install_blend_lp(env));
+    let lp_init_info = generate_lp_init_info(
+        token1.address.clone(),
+        token2.address.clone(),
+        Address::generate(env),
+        admin.clone(),
+        Address::generate(env),
+    );
+
+    token1.mint(&admin, &10_000_000);
+    token2.mint(&admin, &10_000_000);
+
+    (factory, admin, lp_init_info, token1, token2)
+}
+
+fn create_pool(
+    env: &Env,
+    factory: &FactoryClient,
+    admin: &Address,
+    lp_init_info: &LiquidityPoolInitInfo,
+    pool_type: PoolType,
+    initial_liquidity: &InitialLiquidity,
+) -> Address {
+    let amp = match pool_type {
+        PoolType::Stable => Some(10),
+        PoolType::Xyk | PoolType::Blend => None,
+    };
+    factory.create_liquidity_pool(
+        admin,
+        lp_init_info,
+        &String::from_str(env, "Pool"),
+        &String::from_str(env, "PHO/XLM"),
+        &pool_type,
+        &amp,
+        &100i64,
+        &1_000,
+        &Some(initial_liquidity.clone()),
+    )
+}
+
+fn initial_liquidity(amount_a: i128, amount_b: i128) -> InitialLiquidity {
+    InitialLiquidity {
+        amount_a,
+        amount_b,
+        min_trading_balance_a: 0,
+        min_trading_balance_b: 0,
+    }
+}
+
+#[test]
+fn pool_is_created_with_the_liquidity_of_its_creator() {
+    let env = Env::default();
+    let (factory, admin, lp_init_info, token1, token2) = setup(&env);
+
+    let pool = create_pool(
+        &env,
+        &factory,
+        &admin,
+        &lp_init_info,
+        PoolType::Xyk,
+        &initial_liquidity(1_000_000, 4_000_000),
+    );
+
+    let pool_response = factory.query_pool_details(&pool).pool_response;
+    assert_eq!(pool_response.asset_a.amount, 1_000_000);
+    assert_eq!(pool_response.asset_b.amount, 4_000_000);
+    assert_eq!(token1.balance(&admin), 9_000_000);
+    assert_eq!(token2.balance(&admin), 6_000_000);
+    assert!(
+        token_contract::Client::new(&env, &pool_response.asset_lp_share.address).balance(&admin)
+            > 0
+    );
+}
+
+#[test]
+fn stable_pool_is_created_with_the_liquidity_of_its_creator() {
+    let env = Env::default();
+    let (factory, admin, lp_init_info, token1, token2) = setup(&env);
+
+    let pool = create_pool(
+        &env,
+        &factory,
+        &admin,
+        &lp_init_info,
+        PoolType::Stable,
+        &initial_liquidity(1_000_000, 1_000_000),
+    );
+
+    let pool_response = factory.query_pool_details(&pool).pool_response;
+    assert_eq!(pool_response.asset_a.amount, 1_000_000);
+    assert_eq!(pool_response.asset_b.amount, 1_000_000);
+    assert_eq!(token1.balance(&admin), 9_000_000);
+    assert_eq!(token2.balance(&admin), 9_000_000);
+}
+
+#[test]
+fn blend_pool_is_created_with_liquidity_and_trading_floors() {
+    let env = Env::default();
+    let (factory, admin, lp_init_info, _, _) = setup(&env);
+
+    let pool = create_pool(
+        &env,
+        &factory,
+        &admin,
+        &lp_init_info,
+        PoolType::Blend,
+        &InitialLiquidity {
+            min_trading_balance_a: 5_000_000,
+            min_trading_balance_b: 5_000_000,
+            ..initial_liquidity(1_000_000, 1_000_000)
+        },
+    );
+
+    let pool_response = factory.query_pool_details(&pool).pool_response;
+    assert_eq!(pool_response.asset_a.amount, 1_000_000);
+    assert_eq!(pool_response.asset_b.amount, 1_000_000);
+    assert_eq!(
+        blend_lp::Client::new(&env, &pool).min_trading_balances(),
+        (5_000_000, 5_000_000)
+    );
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #122)")]
+fn create_pool_with_zero_initial_amount_fails() {
+    let env = Env::default();
+    let (factory, admin, lp_init_info, _, _) = setup(&env);
+
+    create_pool(
+        &env,
+        &factory,
+        &admin,
+        &lp_init_info,
+        PoolType::Xyk,
+        &initial_liquidity(1_000_000, 0),
+    );
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #122)")]
+fn create_xyk_pool_with_trading_floors_fails() {
+    let env = Env::default();
+    let (factory, admin, lp_init_info, _, _) = setup(&env);
+
+    create_pool(
+        &env,
+        &factory,
+        &admin,
+        &lp_init_info,
+        PoolType::Xyk,
+        &InitialLiquidity {
+            min_trading_balance_a: 5_000_000,
+            ..initial_liquidity(1_000_000, 1_000_000)
+        },
+    );
+}
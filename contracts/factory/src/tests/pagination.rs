@@ -0,0 +1,239 @@
+use super::setup::{
+    deploy_factory_contract, generate_lp_init_info, install_and_deploy_token_contract,
+    install_blend_lp, lp_contract,
+};
+use crate::{contract::FactoryClient, token_contract};
+
+use phoenix::utils::{LiquidityPoolInitInfo, PoolType};
+use soroban_sdk::{testutils::Address as _, vec, Address, Env, String};
+
+fn deploy_token<'a>(env: &Env, admin: &Address) -> token_contract::Client<'a> {
+    install_and_deploy_token_contract(
+        env,
+        admin.clone(),
+        7,
+        String::from_str(env, "Token"),
+        String::from_str(env, "TKN"),
+    )
+}
+
+fn lp_init_info_for(
+    env: &Env,
+    admin: &Address,
+    token_a: &Address,
+    token_b: &Address,
+) -> LiquidityPoolInitInfo {
+    let (token_a, token_b) = if token_a < token_b {
+        (token_a, token_b)
+    } else {
+        (token_b, token_a)
+    };
+    generate_lp_init_info(
+        token_a.clone(),
+        token_b.clone(),
+        Address::generate(env),
+        admin.clone(),
+        Address::generate(env),
+    )
+}
+
+fn create_pool(
+    env: &Env,
+    factory: &FactoryClient,
+    admin: &Address,
+    lp_init_info: &LiquidityPoolInitInfo,
+    pool_type: PoolType,
+) -> Address {
+    factory.create_liquidity_pool(
+        admin,
+        lp_init_info,
+        &String::from_str(env, "Pool"),
+        &String::from_str(env, "POOL"),
+        &pool_type,
+        &None::<u64>,
+        &100i64,
+        &1_000,
+        &None,
+    )
+}
+
+#[test]
+fn paginated_pools_span_registry_shards() {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.cost_estimate().budget().reset_unlimited();
+
+    let admin = Address::generate(&env);
+    let token_a = deploy_token(&env, &admin);
+    let token_b = deploy_token(&env, &admin);
+    let factory = deploy_factory_contract(&env, Some(admin.clone()));
+
+    // one pool per fee tier to fill more than one shard of 50 pools
+    let mut lp_init_info = lp_init_info_for(&env, &admin, &token_a.address, &token_b.address);
+    for fee_tier in 0..53 {
+        lp_init_info.swap_fee_bps = fee_tier;
+        create_pool(&env, &factory, &admin, &lp_init_info, PoolType::Xyk);
+    }
+
+    let all_pools = factory.query_pools();
+    assert_eq!(all_pools.len(), 53);
+
+    // the limit is capped at 50
+    let first_page = factory.query_pools_paginated(&None, &100, &None, &None);
+    assert_eq!(first_page.pools, all_pools.slice(0..50));
+    assert_eq!(first_page.next_start_after, all_pools.get(49));
+
+    let second_page =
+        factory.query_pools_paginated(&first_page.next_start_after, &50, &None, &None);
+    assert_eq!(second_page.pools, all_pools.slice(50..53));
+    assert_eq!(second_page.next_start_after, None);
+
+    let middle = factory.query_pools_paginated(&all_pools.get(47), &4, &None, &None);
+    assert_eq!(middle.pools, all_pools.slice(48..52));
+    assert_eq!(middle.next_start_after, all_pools.get(51));
+
+    let filtered_page = factory.query_pools_paginated(
+        &all_pools.get(48),
+        &10,
+        &Some(PoolType::Xyk),
+        &Some(token_a.address.clone()),
+    );
+    assert_eq!(filtered_page.pools, all_pools.slice(49..53));
+    assert_eq!(filtered_page.next_start_after, None);
+
+    assert!(factory
+        .query_pools_paginated(&all_pools.last(), &10, &None, &None)
+        .pools
+        .is_empty());
+}
+
+#[test]
+fn paginated_pools_filter_by_type_and_token() {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.cost_estimate().budget().reset_unlimited();
+
+    let admin = Address::generate(&env);
+    let token_a = deploy_token(&env, &admin);
+    let token_b = deploy_token(&env, &admin);
+    let token_c = deploy_token(&env, &admin);
+    let factory = deploy_factory_contract(&env, Some(admin.clone()));
+    factory.set_blend_wasm_hash(&install_blend_lp(&env));
+
+    let ab_info = lp_init_info_for(&env, &admin, &token_a.address, &token_b.address);
+    let bc_info = lp_init_info_for(&env, &admin, &token_b.address, &token_c.address);
+    let xyk_ab = create_pool(&env, &factory, &admin, &ab_info, PoolType::Xyk);
+    let blend_ab = create_pool(&env, &factory, &admin, &ab_info, PoolType::Blend);
+    let xyk_bc = create_pool(&env, &factory, &admin, &bc_info, PoolType::Xyk);
+
+    let pools = |start_after: Option<Address>,
+                 limit: u32,
+                 pool_type: Option<PoolType>,
+                 token: Option<Address>| {
+        factory
+            .query_pools_paginated(&start_after, &limit, &pool_type, &token)
+            .pools
+    };
+    assert_eq!(
+        pools(None, 10, Some(PoolType::Xyk), None),
+        vec![&env, xyk_ab.clone(), xyk_bc.clone()]
+    );
+    assert_eq!(
+        pools(None, 10, Some(PoolType::Blend), None),
+        vec![&env, blend_ab.clone()]
+    );
+    assert!(pools(None, 10, Some(PoolType::Stable), None).is_empty());
+    assert_eq!(
+        pools(None, 10, None, Some(token_b.address.clone())),
+        vec![&env, xyk_ab.clone(), blend_ab.clone(), xyk_bc.clone()]
+    );
+    assert_eq!(
+        pools(None, 10, None, Some(token_c.address.clone())),
+        vec![&env, xyk_bc.clone()]
+    );
+    assert_eq!(
+        pools(None, 10, Some(PoolType::Xyk), Some(token_a.address.clone())),
+        vec![&env, xyk_ab.clone()]
+    );
+
+    // the limit counts the scanned pools, a page without matches still
+    // tells where to go on from
+    let first_page = factory.query_pools_paginated(&None, &1, &Some(PoolType::Xyk), &None);
+    assert_eq!(first_page.pools, vec![&env, xyk_ab.clone()]);
+    let second_page = factory.query_pools_paginated(
+        &first_page.next_start_after,
+        &1,
+        &Some(PoolType::Xyk),
+        &None,
+    );
+    assert!(second_page.pools.is_empty());
+    assert_eq!(second_page.next_start_after, Some(blend_ab.clone()));
+    let third_page = factory.query_pools_paginated(
+        &second_page.next_start_after,
+        &1,
+        &Some(PoolType::Xyk),
+        &None,
+    );
+    assert_eq!(third_page.pools, vec![&env, xyk_bc.clone()]);
+    assert_eq!(third_page.next_start_after, None);
+
+    let details =
+        factory.query_pools_details_paginated(&None, &10, &None, &Some(token_c.address.clone()));
+    assert_eq!(
+        details.pools,
+        vec![&env, factory.query_pool_details(&xyk_bc)]
+    );
+}
+
+#[test]
+fn paginated_user_portfolio() {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.cost_estimate().budget().reset_unlimited();
+
+    let admin = Address::generate(&env);
+    let user = Address::generate(&env);
+    let token_a = deploy_token(&env, &admin);
+    let token_b = deploy_token(&env, &admin);
+    let token_c = deploy_token(&env, &admin);
+    let factory = deploy_factory_contract(&env, Some(admin.clone()));
+
+    let ab_info = lp_init_info_for(&env, &admin, &token_a.address, &token_b.address);
+    let bc_info = lp_init_info_for(&env, &admin, &token_b.address, &token_c.address);
+    let first_pool = create_pool(&env, &factory, &admin, &ab_info, PoolType::Xyk);
+    let second_pool = create_pool(&env, &factory, &admin, &bc_info, PoolType::Xyk);
+
+    token_b.mint(&user, &1_000_000);
+    token_c.mint(&user, &1_000_000);
+    lp_contract::Client::new(&env, &second_pool).provide_liquidity(
+        &user,
+        &Some(1_000_000),
+        &None,
+        &Some(1_000_000),
+        &None,
+        &None::<i64>,
+        &None::<u64>,
+        &false,
+    );
+
+    let full_portfolio = factory.query_user_portfolio(&user, &false);
+    assert_eq!(full_portfolio.lp_portfolio.len(), 1);
+
+    let first_page = factory.query_user_portfolio_paginated(&user, &false, &None, &1);
+    assert!(first_page.lp_portfolio.is_empty());
+
+    let second_page = factory.query_user_portfolio_paginated(&user, &false, &Some(first_pool), &1);
+    assert_eq!(second_page, full_portfolio);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #103)")]
+fn paginated_pools_unknown_start_after() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let factory = deploy_factory_contract(&env, Some(admin));
+
+    factory.query_pools_paginated(&Some(Address::generate(&env)), &10, &None, &None);
+}
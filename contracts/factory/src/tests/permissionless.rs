@@ -0,0 +1,290 @@
+use super::setup::{
+    deploy_factory_contract, generate_lp_init_info, install_and_deploy_token_contract, lp_contract,
+    stake_contract,
+};
+use crate::{contract::FactoryClient, storage::PermissionlessPoolConfig, token_contract};
+
+use phoenix::utils::{LiquidityPoolInitInfo, PoolType};
+use soroban_sdk::{
+    testutils::{Address as _, MockAuth, MockAuthInvoke},
+    vec, Address, Env, IntoVal, String,
+};
+
+struct Setup<'a> {
+    factory: FactoryClient<'a>,
+    admin: Address,
+    fee_token: token_contract::Client<'a>,
+    treasury: Address,
+    lp_init_info: LiquidityPoolInitInfo,
+}
+
+fn setup<'a>(env: &Env) -> Setup<'a> {
+    env.mock_all_auths();
+    env.cost_estimate().budget().reset_unlimited();
+
+    let admin = Address::generate(env);
+    let mut token1 = install_and_deploy_token_contract(
+        env,
+        admin.clone(),
+        7,
+        String::from_str(env, "Phoenix"),
+        String::from_str(env, "PHO"),
+    );
+    let mut token2 = install_and_deploy_token_contract(
+        env,
+        admin.clone(),
+        7,
+        String::from_str(env, "Stellar"),
+        String::from_str(env, "XLM"),
+    );
+    if token2.address < token1.address {
+        core::mem::swap(&mut token1, &mut token2);
+    }
+    let fee_token = install_and_deploy_token_contract(
+        env,
+        admin.clone(),
+        7,
+        String::from_str(env, "Fee"),
+        String::from_str(env, "FEE"),
+    );
+
+    let factory = deploy_factory_contract(env, Some(admin.clone()));
+    let treasury = Address::generate(env);
+
+    let mut lp_init_info = generate_lp_init_info(
+        token1.address.clone(),
+        token2.address.clone(),
+        Address::generate(env),
+        admin.clone(),
+        Address::generate(env),
+    );
+    lp_init_info.swap_fee_bps = 30;
+
+    Setup {
+        factory,
+        admin,
+        fee_token,
+        treasury,
+        lp_init_info,
+    }
+}
+
+fn permissionless_config(setup: &Setup, env: &Env, enabled: bool) -> PermissionlessPoolConfig {
+    PermissionlessPoolConfig {
+        enabled,
+        fee_token: setup.fee_token.address.clone(),
+        fee_amount: 100,
+        treasury: setup.treasury.clone(),
+        allowed_fee_tiers: vec![env, 1, 5, 30, 100],
+        max_slippage_bps: 5_000,
+        max_spread_bps: 500,
+        max_referral_bps: 5_000,
+        max_complexity: 10,
+    }
+}
+
+fn create_pool(
+    env: &Env,
+    setup: &Setup,
+    sender: &Address,
+    lp_init_info: &LiquidityPoolInitInfo,
+    pool_type: PoolType,
+) -> Address {
+    setup.factory.create_liquidity_pool(
+        sender,
+        lp_init_info,
+        &String::from_str(env, "Pool"),
+        &String::from_str(env, "PHO/XLM"),
+        &pool_type,
+        &Some(10u64),
+        &100i64,
+        &100,
+        &None,
+    )
+}
+
+#[test]
+fn anyone_creates_xyk_pool_by_paying_the_fee() {
+    let env = Env::default();
+    let setup = setup(&env);
+    let user = Address::generate(&env);
+    setup.fee_token.mint(&user, &1_000);
+
+    assert_eq!(setup.factory.query_permissionless_config(), None);
+    let config = permissionless_config(&setup, &env, true);
+    setup.factory.update_permissionless_config(&config);
+    assert_eq!(setup.factory.query_permissionless_config(), Some(config));
+
+    let pool = create_pool(&env, &setup, &user, &setup.lp_init_info, PoolType::Xyk);
+
+    assert_eq!(setup.factory.query_pools(), vec![&env, pool.clone()]);
+    assert_eq!(setup.fee_token.balance(&user), 900);
+    assert_eq!(setup.fee_token.balance(&setup.treasury), 100);
+
+    // the treasury collects the pool fees and manages the staking rewards
+    let pool_client = lp_contract::Client::new(&env, &pool);
+    assert_eq!(pool_client.query_config().fee_recipient, setup.treasury);
+    let stake_client =
+        stake_contract::Client::new(&env, &pool_client.query_stake_contract_address());
+    assert_eq!(stake_client.query_config().config.manager, setup.treasury);
+}
+
+#[test]
+#[should_panic(expected = "Error(Auth, InvalidAction)")]
+fn permissionless_creator_cannot_update_pool_config() {
+    let env = Env::default();
+    let setup = setup(&env);
+    let user = Address::generate(&env);
+    setup.fee_token.mint(&user, &1_000);
+    setup
+        .factory
+        .update_permissionless_config(&permissionless_config(&setup, &env, true));
+
+    // the creator names itself admin, but the factory takes its place
+    let mut lp_init_info = setup.lp_init_info.clone();
+    lp_init_info.admin = user.clone();
+    let pool = create_pool(&env, &setup, &user, &lp_init_info, PoolType::Xyk);
+    let pool_client = lp_contract::Client::new(&env, &pool);
+    assert_eq!(pool_client.query_admin(), setup.factory.address);
+
+    let update_args = (
+        None::<Address>,
+        Some(10_000i64),
+        None::<Address>,
+        Some(10_000i64),
+        Some(10_000i64),
+        None::<i64>,
+    );
+    env.mock_auths(&[MockAuth {
+        address: &user,
+        invoke: &MockAuthInvoke {
+            contract: &pool,
+            fn_name: "update_config",
+            args: update_args.into_val(&env),
+            sub_invokes: &[],
+        },
+    }]);
+    pool_client.update_config(
+        &update_args.0,
+        &update_args.1,
+        &update_args.2,
+        &update_args.3,
+        &update_args.4,
+        &update_args.5,
+    );
+}
+
+#[test]
+fn whitelisted_accounts_keep_their_privileges() {
+    let env = Env::default();
+    let setup = setup(&env);
+    setup
+        .factory
+        .update_permissionless_config(&permissionless_config(&setup, &env, true));
+
+    // fee tier outside of the allowed ones and no creation fee
+    let mut lp_init_info = setup.lp_init_info.clone();
+    lp_init_info.swap_fee_bps = 7;
+    create_pool(&env, &setup, &setup.admin, &lp_init_info, PoolType::Xyk);
+
+    assert_eq!(setup.factory.query_pools().len(), 1);
+    assert_eq!(setup.fee_token.balance(&setup.treasury), 0);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #102)")]
+fn disabled_permissionless_mode_rejects_non_whitelisted() {
+    let env = Env::default();
+    let setup = setup(&env);
+    let user = Address::generate(&env);
+    setup.fee_token.mint(&user, &1_000);
+    setup
+        .factory
+        .update_permissionless_config(&permissionless_config(&setup, &env, false));
+
+    create_pool(&env, &setup, &user, &setup.lp_init_info, PoolType::Xyk);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #113)")]
+fn permissionless_mode_only_creates_xyk_pools() {
+    let env = Env::default();
+    let setup = setup(&env);
+    let user = Address::generate(&env);
+    setup
+        .factory
+        .update_permissionless_config(&permissionless_config(&setup, &env, true));
+
+    create_pool(&env, &setup, &user, &setup.lp_init_info, PoolType::Stable);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #114)")]
+fn permissionless_pool_needs_an_allowed_fee_tier() {
+    let env = Env::default();
+    let setup = setup(&env);
+    let user = Address::generate(&env);
+    setup
+        .factory
+        .update_permissionless_config(&permissionless_config(&setup, &env, true));
+
+    let mut lp_init_info = setup.lp_init_info.clone();
+    lp_init_info.swap_fee_bps = 7;
+    create_pool(&env, &setup, &user, &lp_init_info, PoolType::Xyk);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #115)")]
+fn permissionless_pool_spread_is_capped() {
+    let env = Env::default();
+    let setup = setup(&env);
+    let user = Address::generate(&env);
+    setup
+        .factory
+        .update_permissionless_config(&permissionless_config(&setup, &env, true));
+
+    let mut lp_init_info = setup.lp_init_info.clone();
+    lp_init_info.max_allowed_spread_bps = 501;
+    create_pool(&env, &setup, &user, &lp_init_info, PoolType::Xyk);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #115)")]
+fn permissionless_pool_referral_fee_is_capped() {
+    let env = Env::default();
+    let setup = setup(&env);
+    let user = Address::generate(&env);
+    setup
+        .factory
+        .update_permissionless_config(&permissionless_config(&setup, &env, true));
+
+    let mut lp_init_info = setup.lp_init_info.clone();
+    lp_init_info.max_referral_bps = 5_001;
+    create_pool(&env, &setup, &user, &lp_init_info, PoolType::Xyk);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #115)")]
+fn permissionless_pool_stake_complexity_is_capped() {
+    let env = Env::default();
+    let setup = setup(&env);
+    let user = Address::generate(&env);
+    setup
+        .factory
+        .update_permissionless_config(&permissionless_config(&setup, &env, true));
+
+    let mut lp_init_info = setup.lp_init_info.clone();
+    lp_init_info.stake_init_info.max_complexity = 11;
+    create_pool(&env, &setup, &user, &lp_init_info, PoolType::Xyk);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #116)")]
+fn permissionless_config_needs_fee_tiers() {
+    let env = Env::default();
+    let setup = setup(&env);
+
+    let mut config = permissionless_config(&setup, &env, true);
+    config.allowed_fee_tiers = vec![&env];
+    setup.factory.update_permissionless_config(&config);
+}
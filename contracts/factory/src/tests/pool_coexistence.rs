@@ -78,6 +78,7 @@ fn xyk_then_blend_same_pair_coexist() {
         &None::<u64>,
         &500i64,
         &10_000i64,
+        &None,
     );
 
     // Legacy query returns the Xyk pool, both orders.
@@ -105,6 +106,7 @@ fn xyk_then_blend_same_pair_coexist() {
         &None::<u64>,
         &500i64,
         &10_000i64,
+        &None,
     );
 
     // Different deterministic address — salt prefix did its job.
@@ -181,6 +183,7 @@ fn blend_then_xyk_same_pair_coexist() {
         &None::<u64>,
         &500i64,
         &10_000i64,
+        &None,
     );
 
     // Before any Xyk exists, the type-aware Blend query resolves; the
@@ -201,6 +204,7 @@ fn blend_then_xyk_same_pair_coexist() {
         &None::<u64>,
         &500i64,
         &10_000i64,
+        &None,
     );
 
     assert_ne!(xyk_pool, blend_pool);
@@ -221,11 +225,10 @@ fn blend_then_xyk_same_pair_coexist() {
 }
 
 #[test]
-#[should_panic(expected = "ExistingValue")]
+#[should_panic(expected = "Error(Contract, #117)")]
 fn second_xyk_for_same_pair_fails() {
-    // The salt-prefix change is specifically a Blend-only carve-out — Xyk's
-    // salt is unchanged from production, so a second Xyk for the same pair
-    // must still collide on the deterministic address.
+    // The salt-prefix change is specifically a Blend-only carve-out — a
+    // second Xyk for the same pair and fee tier is still rejected.
     let env = Env::default();
     env.mock_all_auths();
     env.cost_estimate().budget().reset_unlimited();
@@ -251,9 +254,10 @@ fn second_xyk_for_same_pair_fails() {
         &None::<u64>,
         &500i64,
         &10_000i64,
+        &None,
     );
 
-    // Second Xyk for the same pair must blow up on `deploy_v2`.
+    // Second Xyk for the same pair and fee tier must be rejected.
     let _xyk_b = factory.create_liquidity_pool(
         &admin,
         &lp_init_info,
@@ -263,6 +267,7 @@ fn second_xyk_for_same_pair_fails() {
         &None::<u64>,
         &500i64,
         &10_000i64,
+        &None,
     );
 }
 
@@ -297,6 +302,7 @@ fn pair_type_query_unknown_blend_panics() {
         &None::<u64>,
         &500i64,
         &10_000i64,
+        &None,
     );
 
     factory.query_pool_by_pair_type(&token_a.address, &token_b.address, &PoolType::Blend);
@@ -1,5 +1,5 @@
 use super::setup::{deploy_factory_contract, generate_lp_init_info};
-use crate::storage::{Asset, DataKey, LpPortfolio, Stake, StakePortfolio, UserPortfolio};
+use crate::storage::{Asset, LpPortfolio, PoolRegistryKey, Stake, StakePortfolio, UserPortfolio};
 use crate::tests::setup::{
     install_and_deploy_token_contract, lp_contract, stake_contract, ONE_DAY,
 };
@@ -186,6 +186,7 @@ fn test_deploy_multiple_liquidity_pools() {
         &None::<u64>,
         &100i64,
         &1_000,
+        &None,
     );
     let second_lp_contract_addr = factory.create_liquidity_pool(
         &admin.clone(),
@@ -196,6 +197,7 @@ fn test_deploy_multiple_liquidity_pools() {
         &None::<u64>,
         &100i64,
         &1_000,
+        &None,
     );
     let third_lp_contract_addr = factory.create_liquidity_pool(
         &admin.clone(),
@@ -206,6 +208,7 @@ fn test_deploy_multiple_liquidity_pools() {
         &None::<u64>,
         &100i64,
         &1_000,
+        &None,
     );
 
     let first_result = factory.query_pool_details(&lp_contract_addr);
@@ -439,6 +442,7 @@ fn test_queries_by_tuple() {
         &None::<u64>,
         &100i64,
         &1_000,
+        &None,
     );
     let second_lp_contract_addr = factory.create_liquidity_pool(
         &admin.clone(),
@@ -449,6 +453,7 @@ fn test_queries_by_tuple() {
         &None::<u64>,
         &100i64,
         &1_000,
+        &None,
     );
     let third_lp_contract_addr = factory.create_liquidity_pool(
         &admin.clone(),
@@ -459,6 +464,7 @@ fn test_queries_by_tuple() {
         &None::<u64>,
         &100i64,
         &1_000,
+        &None,
     );
 
     let first_result = factory.query_pool_details(&lp_contract_addr);
@@ -583,6 +589,7 @@ fn test_query_user_portfolio_with_stake() {
         &None::<u64>,
         &100i64,
         &1_000,
+        &None,
     );
 
     let first_lp_client = lp_contract::Client::new(&env, &first_lp_contract_addr);
@@ -685,6 +692,7 @@ fn test_query_user_portfolio_with_stake() {
         &None::<u64>,
         &100i64,
         &1_000,
+        &None,
     );
 
     let second_lp_client = lp_contract::Client::new(&env, &second_lp_contract_addr);
@@ -839,6 +847,7 @@ fn test_query_user_portfolio_with_multiple_users_staking_in_multiple_liquidity_p
         &None::<u64>,
         &100i64,
         &1_000,
+        &None,
     );
 
     let first_lp_client = lp_contract::Client::new(&env, &first_lp_contract_addr);
@@ -868,6 +877,7 @@ fn test_query_user_portfolio_with_multiple_users_staking_in_multiple_liquidity_p
         &None::<u64>,
         &100i64,
         &1_000,
+        &None,
     );
 
     let second_lp_client = lp_contract::Client::new(&env, &second_lp_contract_addr);
@@ -1372,6 +1382,7 @@ fn test_query_user_portfolio_without_stake() {
         &None::<u64>,
         &100i64,
         &1_000,
+        &None,
     );
 
     let first_lp_client = lp_contract::Client::new(&env, &lp_contract_addr);
@@ -1460,13 +1471,16 @@ fn test_ttl_extensions_with_multiple_pool_queries() {
         &None::<u64>,
         &100i64,
         &1_000,
+        &None,
     );
 
     // initial TTL verification
     let (initial_instance_ttl, initial_persistent_ttl) = env.as_contract(&factory_address, || {
         (
             env.storage().instance().get_ttl(),
-            env.storage().persistent().get_ttl(&DataKey::LpVec),
+            env.storage()
+                .persistent()
+                .get_ttl(&PoolRegistryKey::Shard(0)),
         )
     });
     // validate initial state
@@ -1487,7 +1501,9 @@ fn test_ttl_extensions_with_multiple_pool_queries() {
     let (instance_ttl1, persistent_ttl1) = env.as_contract(&factory_address, || {
         (
             env.storage().instance().get_ttl(),
-            env.storage().persistent().get_ttl(&DataKey::LpVec),
+            env.storage()
+                .persistent()
+                .get_ttl(&PoolRegistryKey::Shard(0)),
         )
     });
 
@@ -1506,7 +1522,9 @@ fn test_ttl_extensions_with_multiple_pool_queries() {
     let (instance_ttl2, persistent_ttl2) = env.as_contract(&factory_address, || {
         (
             env.storage().instance().get_ttl(),
-            env.storage().persistent().get_ttl(&DataKey::LpVec),
+            env.storage()
+                .persistent()
+                .get_ttl(&PoolRegistryKey::Shard(0)),
         )
     });
     assert_eq!(instance_ttl2, INSTANCE_TARGET_TTL);
@@ -1524,7 +1542,9 @@ fn test_ttl_extensions_with_multiple_pool_queries() {
     let (instance_ttl3, persistent_ttl3) = env.as_contract(&factory_address, || {
         (
             env.storage().instance().get_ttl(),
-            env.storage().persistent().get_ttl(&DataKey::LpVec),
+            env.storage()
+                .persistent()
+                .get_ttl(&PoolRegistryKey::Shard(0)),
         )
     });
     assert_eq!(instance_ttl3, INSTANCE_TARGET_TTL);
@@ -1542,7 +1562,9 @@ fn test_ttl_extensions_with_multiple_pool_queries() {
     let (instance_ttl4, persistent_ttl4) = env.as_contract(&factory_address, || {
         (
             env.storage().instance().get_ttl(),
-            env.storage().persistent().get_ttl(&DataKey::LpVec),
+            env.storage()
+                .persistent()
+                .get_ttl(&PoolRegistryKey::Shard(0)),
         )
     });
     assert_eq!(instance_ttl4, INSTANCE_TARGET_TTL);
@@ -1560,7 +1582,9 @@ fn test_ttl_extensions_with_multiple_pool_queries() {
     let (instance_ttl5, persistent_ttl5) = env.as_contract(&factory_address, || {
         (
             env.storage().instance().get_ttl(),
-            env.storage().persistent().get_ttl(&DataKey::LpVec),
+            env.storage()
+                .persistent()
+                .get_ttl(&PoolRegistryKey::Shard(0)),
         )
     });
     assert_eq!(instance_ttl5, INSTANCE_TARGET_TTL);
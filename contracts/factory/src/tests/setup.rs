@@ -328,12 +328,37 @@ fn update_factory() {
         &None::<u64>,
         &100i64,
         &1_000,
+        &None,
     );
 
     let pool_query = latest_factory_client.query_pool_details(&second_pool);
     assert_eq!(pool_query.pool_response.asset_a.address, token1.address);
     assert_eq!(pool_query.pool_response.asset_b.address, token2.address);
 
+    // pools of the old factory move into the sharded pool registry
+    assert_eq!(
+        latest_factory_client.query_pools(),
+        vec![&env, pool_addr.clone(), second_pool.clone()]
+    );
+    latest_factory_client.backfill_pools_for_tokens(&0, &10);
+    assert_eq!(
+        latest_factory_client
+            .query_pools_paginated(
+                &None,
+                &10,
+                &Some(PoolType::Xyk),
+                &Some(token_a.address.clone())
+            )
+            .pools,
+        vec![&env, pool_addr]
+    );
+    assert_eq!(
+        latest_factory_client
+            .query_pools_paginated(&None, &10, &Some(PoolType::Stable), &None)
+            .pools,
+        Vec::new(&env)
+    );
+
     let new_admin = Address::generate(&env);
     latest_factory_client.propose_admin(&new_admin, &None);
     latest_factory_client.accept_admin();
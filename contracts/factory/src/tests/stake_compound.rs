@@ -0,0 +1,359 @@
+extern crate std;
+
+use phoenix::utils::PoolType;
+use soroban_sdk::{
+    testutils::{Address as _, Ledger},
+    Address, Env, String,
+};
+
+use super::setup::{
+    deploy_factory_contract, generate_lp_init_info, install_and_deploy_token_contract, lp_contract,
+    stake_contract, ONE_DAY,
+};
+use crate::token_contract;
+
+const SIXTY_DAYS: u64 = 60 * ONE_DAY;
+
+struct CompoundSetup<'a> {
+    env: Env,
+    manager: Address,
+    user: Address,
+    token_a: token_contract::Client<'a>,
+    token_b: token_contract::Client<'a>,
+    pool: lp_contract::Client<'a>,
+    stake: stake_contract::Client<'a>,
+}
+
+/// Deploys a XYK pool through the factory, seeds it with liquidity and bonds
+/// 10_000 LP shares for `user` at timestamp 0.
+fn setup<'a>() -> CompoundSetup<'a> {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.cost_estimate().budget().reset_unlimited();
+
+    let admin = Address::generate(&env);
+    let manager = Address::generate(&env);
+    let provider = Address::generate(&env);
+    let user = Address::generate(&env);
+
+    let mut token_a = install_and_deploy_token_contract(
+        &env,
+        admin.clone(),
+        7,
+        String::from_str(&env, "Phoenix"),
+        String::from_str(&env, "PHO"),
+    );
+    let mut token_b = install_and_deploy_token_contract(
+        &env,
+        admin.clone(),
+        7,
+        String::from_str(&env, "Stellar"),
+        String::from_str(&env, "XLM"),
+    );
+    if token_b.address < token_a.address {
+        std::mem::swap(&mut token_a, &mut token_b);
+    }
+
+    let factory = deploy_factory_contract(&env, Some(admin.clone()));
+    let lp_init_info = generate_lp_init_info(
+        token_a.address.clone(),
+        token_b.address.clone(),
+        manager.clone(),
+        admin.clone(),
+        admin.clone(),
+    );
+    let pool_addr = factory.create_liquidity_pool(
+        &admin,
+        &lp_init_info,
+        &String::from_str(&env, "Pool"),
+        &String::from_str(&env, "PHO/XLM"),
+        &PoolType::Xyk,
+        &None::<u64>,
+        &100i64,
+        &1_000,
+        &None,
+    );
+    let pool = lp_contract::Client::new(&env, &pool_addr);
+    let stake = stake_contract::Client::new(&env, &pool.query_stake_contract_address());
+    // the stake contract finds its pool through this index
+    assert_eq!(
+        factory.query_pool_by_stake_contract(&stake.address),
+        Some(pool_addr.clone())
+    );
+
+    token_a.mint(&provider, &10_000_000);
+    token_b.mint(&provider, &10_000_000);
+    pool.provide_liquidity(
+        &provider,
+        &Some(10_000_000),
+        &None,
+        &Some(10_000_000),
+        &None,
+        &None,
+        &None::<u64>,
+        &false,
+    );
+
+    token_a.mint(&user, &10_000);
+    token_b.mint(&user, &10_000);
+    pool.provide_liquidity(
+        &user,
+        &Some(10_000),
+        &None,
+        &Some(10_000),
+        &None,
+        &None,
+        &None::<u64>,
+        &true,
+    );
+
+    token_a.mint(&manager, &1_000_000);
+    token_b.mint(&manager, &1_000_000);
+    env.ledger().with_mut(|li| li.timestamp = SIXTY_DAYS);
+
+    CompoundSetup {
+        env,
+        manager,
+        user,
+        token_a,
+        token_b,
+        pool,
+        stake,
+    }
+}
+
+#[test]
+fn compound_rewards_in_both_pool_tokens() {
+    let CompoundSetup {
+        env,
+        manager,
+        user,
+        token_a,
+        token_b,
+        pool,
+        stake,
+    } = setup();
+
+    stake.create_distribution_flow(&manager, &token_a.address);
+    stake.create_distribution_flow(&manager, &token_b.address);
+
+    // the user owns the whole staked supply, so gets the whole distribution
+    stake.distribute_rewards(&manager, &1_000, &token_a.address);
+    stake.distribute_rewards(&manager, &1_000, &token_b.address);
+    env.ledger().with_mut(|li| li.timestamp += ONE_DAY);
+
+    let total_staked_before = stake.query_total_staked();
+    let shares = stake.compound(&user, &1);
+
+    assert_eq!(shares, 1_000);
+    let staked = stake.query_staked(&user);
+    assert_eq!(staked.stakes.len(), 2);
+    let new_stake = staked.stakes.get(1).unwrap();
+    assert_eq!(new_stake.stake, shares);
+    assert_eq!(new_stake.stake_timestamp, SIXTY_DAYS + ONE_DAY);
+    assert_eq!(staked.total_stake, 10_000 + shares);
+    assert_eq!(staked.last_reward_time, SIXTY_DAYS + ONE_DAY);
+    assert_eq!(stake.query_total_staked(), total_staked_before + shares);
+
+    // the rewards went into the pool, nothing is left with the user or pending
+    assert_eq!(token_a.balance(&user), 0);
+    assert_eq!(token_b.balance(&user), 0);
+    assert_eq!(pool.query_pool_info().asset_a.amount, 10_011_000);
+    assert_eq!(pool.query_pool_info().asset_b.amount, 10_011_000);
+    assert!(stake
+        .query_withdrawable_rewards(&user)
+        .rewards
+        .iter()
+        .all(|reward| reward.reward_amount == 0));
+}
+
+#[test]
+fn compound_single_sided_reward_swaps_half() {
+    let CompoundSetup {
+        env,
+        manager,
+        user,
+        token_a,
+        token_b,
+        pool,
+        stake,
+    } = setup();
+
+    stake.create_distribution_flow(&manager, &token_a.address);
+    stake.distribute_rewards(&manager, &2_000, &token_a.address);
+    env.ledger().with_mut(|li| li.timestamp += ONE_DAY);
+
+    let shares = stake.compound(&user, &900);
+
+    assert!(shares >= 900);
+    assert_eq!(stake.query_staked(&user).total_stake, 10_000 + shares);
+    // the leftover of the swapped side stays with the user, it's dust at most
+    assert!(token_a.balance(&user) + token_b.balance(&user) <= 2);
+    assert_eq!(
+        pool.query_pool_info().asset_a.amount
+            + pool.query_pool_info().asset_b.amount
+            + token_a.balance(&user)
+            + token_b.balance(&user),
+        20_022_000
+    );
+}
+
+#[test]
+fn compound_pays_out_rewards_in_other_tokens() {
+    let CompoundSetup {
+        env,
+        manager,
+        user,
+        token_a,
+        token_b,
+        stake,
+        ..
+    } = setup();
+
+    let other_token = install_and_deploy_token_contract(
+        &env,
+        manager.clone(),
+        7,
+        String::from_str(&env, "Other"),
+        String::from_str(&env, "OTH"),
+    );
+    other_token.mint(&manager, &1_000);
+
+    stake.create_distribution_flow(&manager, &token_a.address);
+    stake.create_distribution_flow(&manager, &token_b.address);
+    stake.create_distribution_flow(&manager, &other_token.address);
+    stake.distribute_rewards(&manager, &1_000, &token_a.address);
+    stake.distribute_rewards(&manager, &1_000, &token_b.address);
+    stake.distribute_rewards(&manager, &1_000, &other_token.address);
+    env.ledger().with_mut(|li| li.timestamp += ONE_DAY);
+
+    stake.compound(&user, &1);
+
+    assert_eq!(other_token.balance(&user), 1_000);
+    assert_eq!(token_a.balance(&user), 0);
+    assert_eq!(token_b.balance(&user), 0);
+}
+
+#[test]
+fn compound_sends_rewards_in_other_tokens_to_the_reward_recipient() {
+    let CompoundSetup {
+        env,
+        manager,
+        user,
+        token_a,
+        stake,
+        ..
+    } = setup();
+
+    let recipient = Address::generate(&env);
+    stake.set_reward_recipient(&user, &Some(recipient.clone()));
+    env.ledger().with_mut(|li| li.timestamp += ONE_DAY);
+    let other_token = install_and_deploy_token_contract(
+        &env,
+        manager.clone(),
+        7,
+        String::from_str(&env, "Other"),
+        String::from_str(&env, "OTH"),
+    );
+    other_token.mint(&manager, &1_000);
+
+    stake.create_distribution_flow(&manager, &token_a.address);
+    stake.create_distribution_flow(&manager, &other_token.address);
+    stake.distribute_rewards(&manager, &2_000, &token_a.address);
+    stake.distribute_rewards(&manager, &1_000, &other_token.address);
+    env.ledger().with_mut(|li| li.timestamp += ONE_DAY);
+
+    let shares = stake.compound(&user, &1);
+
+    // the pool token rewards are compounded, the other ones are delegated
+    assert_eq!(stake.query_staked(&user).total_stake, 10_000 + shares);
+    assert_eq!(other_token.balance(&recipient), 1_000);
+    assert_eq!(other_token.balance(&user), 0);
+    assert_eq!(token_a.balance(&recipient), 0);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #523)")]
+fn compound_fails_below_min_shares_out() {
+    let CompoundSetup {
+        env,
+        manager,
+        user,
+        token_a,
+        token_b,
+        stake,
+        ..
+    } = setup();
+
+    stake.create_distribution_flow(&manager, &token_a.address);
+    stake.create_distribution_flow(&manager, &token_b.address);
+    stake.distribute_rewards(&manager, &1_000, &token_a.address);
+    stake.distribute_rewards(&manager, &1_000, &token_b.address);
+    env.ledger().with_mut(|li| li.timestamp += ONE_DAY);
+
+    stake.compound(&user, &1_001);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #522)")]
+fn compound_fails_without_pending_rewards() {
+    let CompoundSetup {
+        manager,
+        user,
+        token_a,
+        stake,
+        ..
+    } = setup();
+
+    stake.create_distribution_flow(&manager, &token_a.address);
+
+    stake.compound(&user, &1);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #522)")]
+fn compound_fails_with_single_unit_reward() {
+    let CompoundSetup {
+        env,
+        manager,
+        user,
+        token_a,
+        stake,
+        ..
+    } = setup();
+
+    stake.create_distribution_flow(&manager, &token_a.address);
+    stake.distribute_rewards(&manager, &1, &token_a.address);
+    env.ledger().with_mut(|li| li.timestamp += ONE_DAY);
+    assert_eq!(
+        stake
+            .query_withdrawable_rewards(&user)
+            .rewards
+            .get(0)
+            .unwrap()
+            .reward_amount,
+        1
+    );
+
+    stake.compound(&user, &0);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #503)")]
+fn compound_fails_when_the_reward_is_too_small_to_bond() {
+    let CompoundSetup {
+        env,
+        manager,
+        user,
+        token_a,
+        stake,
+        ..
+    } = setup();
+
+    // the swap of a single unit goes through, but the deposit mints no shares
+    stake.create_distribution_flow(&manager, &token_a.address);
+    stake.distribute_rewards(&manager, &3, &token_a.address);
+    env.ledger().with_mut(|li| li.timestamp += ONE_DAY);
+
+    stake.compound(&user, &0);
+}
@@ -0,0 +1,319 @@
+use super::setup::{
+    deploy_factory_contract, install_and_deploy_token_contract, lp_contract, stable_lp,
+    stake_contract,
+};
+use crate::{
+    contract::FactoryClient,
+    storage::{PoolParamsOverrides, PoolTemplate},
+};
+
+use phoenix::utils::PoolType;
+use soroban_sdk::{testutils::Address as _, Address, Env, String};
+
+fn setup<'a>(env: &Env) -> (FactoryClient<'a>, Address, Address, Address) {
+    env.mock_all_auths();
+    env.cost_estimate().budget().reset_unlimited();
+
+    let admin = Address::generate(env);
+    let mut token_a = install_and_deploy_token_contract(
+        env,
+        admin.clone(),
+        7,
+        String::from_str(env, "Phoenix"),
+        String::from_str(env, "PHO"),
+    )
+    .address;
+    let mut token_b = install_and_deploy_token_contract(
+        env,
+        admin.clone(),
+        7,
+        String::from_str(env, "Stellar"),
+        String::from_str(env, "XLM"),
+    )
+    .address;
+    if token_b < token_a {
+        core::mem::swap(&mut token_a, &mut token_b);
+    }
+
+    let factory = deploy_factory_contract(env, Some(admin.clone()));
+
+    (factory, admin, token_a, token_b)
+}
+
+fn template(env: &Env, amp: Option<u64>) -> PoolTemplate {
+    PoolTemplate {
+        fee_recipient: Address::generate(env),
+        swap_fee_bps: 30,
+        max_allowed_slippage_bps: 5_000,
+        default_slippage_bps: 2_500,
+        max_allowed_spread_bps: 500,
+        max_referral_bps: 5_000,
+        max_allowed_fee_bps: 1_000,
+        stake_manager: Address::generate(env),
+        min_bond: 10,
+        min_reward: 10,
+        max_complexity: 10,
+        amp,
+    }
+}
+
+fn create_from_template(
+    env: &Env,
+    factory: &FactoryClient,
+    admin: &Address,
+    token_a: &Address,
+    token_b: &Address,
+    pool_type: PoolType,
+    overrides: &PoolParamsOverrides,
+) -> Address {
+    factory.create_pool_from_template(
+        admin,
+        token_a,
+        token_b,
+        &pool_type,
+        overrides,
+        &String::from_str(env, "Pool"),
+        &String::from_str(env, "PHO/XLM"),
+        &None,
+    )
+}
+
+#[test]
+fn pool_created_from_template_uses_its_defaults() {
+    let env = Env::default();
+    let (factory, admin, token_a, token_b) = setup(&env);
+    let template = template(&env, None);
+
+    factory.set_pool_template(&PoolType::Xyk, &template);
+    assert_eq!(
+        factory.query_pool_template(&PoolType::Xyk),
+        Some(template.clone())
+    );
+    assert_eq!(factory.query_pool_template(&PoolType::Stable), None);
+
+    let pool = create_from_template(
+        &env,
+        &factory,
+        &admin,
+        &token_a,
+        &token_b,
+        PoolType::Xyk,
+        &PoolParamsOverrides::default(),
+    );
+
+    let pool_client = lp_contract::Client::new(&env, &pool);
+    let config = pool_client.query_config();
+    assert_eq!(config.total_fee_bps, template.swap_fee_bps);
+    assert_eq!(config.fee_recipient, template.fee_recipient);
+    assert_eq!(
+        config.max_allowed_slippage_bps,
+        template.max_allowed_slippage_bps
+    );
+    assert_eq!(
+        config.max_allowed_spread_bps,
+        template.max_allowed_spread_bps
+    );
+    assert_eq!(config.max_referral_bps, template.max_referral_bps);
+    // pools created from a template are administered by the factory
+    assert_eq!(pool_client.query_admin(), factory.address);
+
+    let stake_config = stake_contract::Client::new(&env, &config.stake_contract)
+        .query_config()
+        .config;
+    assert_eq!(stake_config.min_bond, template.min_bond);
+    assert_eq!(stake_config.manager, template.stake_manager);
+    assert_eq!(
+        factory.query_pool_by_pair_type_and_tier(&token_a, &token_b, &PoolType::Xyk, &30),
+        pool
+    );
+}
+
+#[test]
+fn overrides_take_precedence_over_the_template() {
+    let env = Env::default();
+    let (factory, admin, token_a, token_b) = setup(&env);
+    factory.set_pool_template(&PoolType::Xyk, &template(&env, None));
+
+    let pool_admin = Address::generate(&env);
+    let pool = create_from_template(
+        &env,
+        &factory,
+        &admin,
+        &token_a,
+        &token_b,
+        PoolType::Xyk,
+        &PoolParamsOverrides {
+            admin: Some(pool_admin.clone()),
+            swap_fee_bps: Some(100),
+            min_bond: Some(1_000),
+            ..Default::default()
+        },
+    );
+
+    let pool_client = lp_contract::Client::new(&env, &pool);
+    let config = pool_client.query_config();
+    assert_eq!(config.total_fee_bps, 100);
+    assert_eq!(pool_client.query_admin(), pool_admin);
+    assert_eq!(
+        stake_contract::Client::new(&env, &config.stake_contract)
+            .query_config()
+            .config
+            .min_bond,
+        1_000
+    );
+}
+
+#[test]
+fn factory_updates_the_config_of_its_pools() {
+    let env = Env::default();
+    let (factory, admin, token_a, token_b) = setup(&env);
+    factory.set_pool_template(&PoolType::Xyk, &template(&env, None));
+    factory.set_pool_template(&PoolType::Stable, &template(&env, Some(10)));
+
+    let xyk_pool = create_from_template(
+        &env,
+        &factory,
+        &admin,
+        &token_a,
+        &token_b,
+        PoolType::Xyk,
+        &PoolParamsOverrides::default(),
+    );
+    let stable_pool = create_from_template(
+        &env,
+        &factory,
+        &admin,
+        &token_a,
+        &token_b,
+        PoolType::Stable,
+        // Xyk and Stable pools of a pair share the salt, so another fee tier
+        &PoolParamsOverrides {
+            swap_fee_bps: Some(5),
+            ..Default::default()
+        },
+    );
+
+    let new_fee_recipient = Address::generate(&env);
+    // the swap fee can be given as long as it stays the fee tier
+    factory.update_pool_config(
+        &xyk_pool,
+        &Some(30),
+        &Some(new_fee_recipient.clone()),
+        &None,
+        &Some(1_000),
+        &Some(100),
+    );
+    factory.update_pool_config(
+        &stable_pool,
+        &None,
+        &Some(new_fee_recipient.clone()),
+        &None,
+        &Some(1_000),
+        &None,
+    );
+
+    let xyk_config = lp_contract::Client::new(&env, &xyk_pool).query_config();
+    assert_eq!(xyk_config.total_fee_bps, 30);
+    assert_eq!(xyk_config.fee_recipient, new_fee_recipient);
+    assert_eq!(xyk_config.max_allowed_spread_bps, 1_000);
+    assert_eq!(xyk_config.max_referral_bps, 100);
+    let stable_config = stable_lp::Client::new(&env, &stable_pool).query_config();
+    assert_eq!(stable_config.total_fee_bps, 5);
+    assert_eq!(stable_config.fee_recipient, new_fee_recipient);
+    assert_eq!(stable_config.max_allowed_spread_bps, 1_000);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #125)")]
+fn update_pool_config_out_of_the_fee_tier_fails() {
+    let env = Env::default();
+    let (factory, admin, token_a, token_b) = setup(&env);
+    factory.set_pool_template(&PoolType::Xyk, &template(&env, None));
+    let pool = create_from_template(
+        &env,
+        &factory,
+        &admin,
+        &token_a,
+        &token_b,
+        PoolType::Xyk,
+        &PoolParamsOverrides::default(),
+    );
+
+    factory.update_pool_config(&pool, &Some(50), &None, &None, &None, &None);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #126)")]
+fn update_stable_pool_referral_fee_fails() {
+    let env = Env::default();
+    let (factory, admin, token_a, token_b) = setup(&env);
+    factory.set_pool_template(&PoolType::Stable, &template(&env, Some(10)));
+    let pool = create_from_template(
+        &env,
+        &factory,
+        &admin,
+        &token_a,
+        &token_b,
+        PoolType::Stable,
+        &PoolParamsOverrides::default(),
+    );
+
+    factory.update_pool_config(&pool, &None, &None, &None, &None, &Some(100));
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #121)")]
+fn create_pool_from_missing_template_fails() {
+    let env = Env::default();
+    let (factory, admin, token_a, token_b) = setup(&env);
+
+    create_from_template(
+        &env,
+        &factory,
+        &admin,
+        &token_a,
+        &token_b,
+        PoolType::Xyk,
+        &PoolParamsOverrides::default(),
+    );
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #105)")]
+fn set_template_with_invalid_min_bond_fails() {
+    let env = Env::default();
+    let (factory, _, _, _) = setup(&env);
+
+    factory.set_pool_template(
+        &PoolType::Xyk,
+        &PoolTemplate {
+            min_bond: 0,
+            ..template(&env, None)
+        },
+    );
+}
+
+#[test]
+#[should_panic(expected = "Factory: Create Liquidity Pool: Amp must be set for stable pool")]
+fn set_stable_template_without_amp_fails() {
+    let env = Env::default();
+    let (factory, _, _, _) = setup(&env);
+
+    factory.set_pool_template(&PoolType::Stable, &template(&env, None));
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #103)")]
+fn update_config_of_unknown_pool_fails() {
+    let env = Env::default();
+    let (factory, _, _, _) = setup(&env);
+
+    factory.update_pool_config(
+        &Address::generate(&env),
+        &Some(50),
+        &None,
+        &None,
+        &None,
+        &None,
+    );
+}
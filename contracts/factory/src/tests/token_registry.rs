@@ -0,0 +1,222 @@
+use super::setup::{
+    deploy_factory_contract, generate_lp_init_info, install_and_deploy_token_contract,
+};
+use crate::{contract::FactoryClient, storage::TokenInfo, token_contract};
+
+use phoenix::utils::PoolType;
+use soroban_sdk::{testutils::Address as _, vec, Address, Bytes, Env, String};
+
+fn deploy_token<'a>(env: &Env, admin: &Address, symbol: &str) -> token_contract::Client<'a> {
+    install_and_deploy_token_contract(
+        env,
+        admin.clone(),
+        7,
+        String::from_str(env, symbol),
+        String::from_str(env, symbol),
+    )
+}
+
+fn token_info(env: &Env, token: &Address, symbol: &str) -> TokenInfo {
+    TokenInfo {
+        address: token.clone(),
+        symbol: String::from_str(env, symbol),
+        decimals: 7,
+        verified: true,
+        logo_hash: None,
+        logo_uri: None,
+    }
+}
+
+fn create_pool(
+    env: &Env,
+    factory: &FactoryClient,
+    admin: &Address,
+    token_x: &Address,
+    token_y: &Address,
+    fee_tier: i64,
+) -> Address {
+    let (token_a, token_b) = if token_x < token_y {
+        (token_x, token_y)
+    } else {
+        (token_y, token_x)
+    };
+    let mut lp_init_info = generate_lp_init_info(
+        token_a.clone(),
+        token_b.clone(),
+        Address::generate(env),
+        admin.clone(),
+        Address::generate(env),
+    );
+    lp_init_info.swap_fee_bps = fee_tier;
+
+    factory.create_liquidity_pool(
+        admin,
+        &lp_init_info,
+        &String::from_str(env, "Pool"),
+        &String::from_str(env, "POOL"),
+        &PoolType::Xyk,
+        &None::<u64>,
+        &100i64,
+        &1_000,
+        &None,
+    )
+}
+
+#[test]
+fn admin_lists_and_updates_tokens() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let admin = Address::generate(&env);
+    let factory = deploy_factory_contract(&env, Some(admin.clone()));
+
+    let pho = deploy_token(&env, &admin, "PHO");
+    let xlm = deploy_token(&env, &admin, "XLM");
+    let usdc = deploy_token(&env, &admin, "USDC");
+
+    assert_eq!(factory.query_token(&pho.address), None);
+    assert!(factory.query_tokens(&None, &10).is_empty());
+
+    let pho_info = token_info(&env, &pho.address, "PHO");
+    let xlm_info = token_info(&env, &xlm.address, "XLM");
+    let usdc_info = TokenInfo {
+        verified: false,
+        ..token_info(&env, &usdc.address, "USDC")
+    };
+    factory.set_token_info(&pho_info);
+    factory.set_token_info(&xlm_info);
+    factory.set_token_info(&usdc_info);
+
+    assert_eq!(factory.query_token(&pho.address), Some(pho_info.clone()));
+    assert_eq!(
+        factory.query_tokens(&None, &10),
+        vec![&env, pho_info.clone(), xlm_info.clone(), usdc_info.clone()]
+    );
+
+    // updating an entry keeps its position in the list
+    let verified_usdc_info = TokenInfo {
+        verified: true,
+        logo_hash: Some(Bytes::from_array(&env, &[1; 32])),
+        logo_uri: Some(String::from_str(&env, "ipfs://usdc-logo")),
+        ..usdc_info
+    };
+    factory.set_token_info(&verified_usdc_info);
+    assert_eq!(
+        factory.query_tokens(&None, &10),
+        vec![&env, pho_info.clone(), xlm_info.clone(), verified_usdc_info]
+    );
+
+    assert_eq!(factory.query_tokens(&None, &1), vec![&env, pho_info]);
+    assert_eq!(
+        factory.query_tokens(&Some(pho.address.clone()), &1),
+        vec![&env, xlm_info]
+    );
+    assert!(factory
+        .query_tokens(&Some(usdc.address.clone()), &10)
+        .is_empty());
+}
+
+#[test]
+fn pools_are_indexed_by_token_at_creation() {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.cost_estimate().budget().reset_unlimited();
+    let admin = Address::generate(&env);
+    let factory = deploy_factory_contract(&env, Some(admin.clone()));
+
+    let pho = deploy_token(&env, &admin, "PHO").address;
+    let xlm = deploy_token(&env, &admin, "XLM").address;
+    let usdc = deploy_token(&env, &admin, "USDC").address;
+
+    let pho_xlm = create_pool(&env, &factory, &admin, &pho, &xlm, 30);
+    let pho_usdc = create_pool(&env, &factory, &admin, &pho, &usdc, 30);
+    let pho_xlm_5 = create_pool(&env, &factory, &admin, &pho, &xlm, 5);
+
+    assert_eq!(
+        factory.query_pools_for_token(&pho, &None, &10),
+        vec![&env, pho_xlm.clone(), pho_usdc.clone(), pho_xlm_5.clone()]
+    );
+    assert_eq!(
+        factory.query_pools_for_token(&xlm, &None, &10),
+        vec![&env, pho_xlm.clone(), pho_xlm_5.clone()]
+    );
+    assert_eq!(
+        factory.query_pools_for_token(&usdc, &None, &10),
+        vec![&env, pho_usdc.clone()]
+    );
+    assert!(factory
+        .query_pools_for_token(&Address::generate(&env), &None, &10)
+        .is_empty());
+
+    // paginated in creation order
+    assert_eq!(
+        factory.query_pools_for_token(&pho, &None, &2),
+        vec![&env, pho_xlm.clone(), pho_usdc.clone()]
+    );
+    assert_eq!(
+        factory.query_pools_for_token(&pho, &Some(pho_usdc), &2),
+        vec![&env, pho_xlm_5.clone()]
+    );
+
+    // backfilling pools already in the index doesn't duplicate them
+    assert_eq!(factory.backfill_pools_for_tokens(&0, &10), 3);
+    assert_eq!(
+        factory.query_pools_for_token(&xlm, &None, &10),
+        vec![&env, pho_xlm, pho_xlm_5]
+    );
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #123)")]
+fn set_token_info_with_wrong_decimals_fails() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let admin = Address::generate(&env);
+    let factory = deploy_factory_contract(&env, Some(admin.clone()));
+    let pho = deploy_token(&env, &admin, "PHO");
+
+    factory.set_token_info(&TokenInfo {
+        decimals: 18,
+        ..token_info(&env, &pho.address, "PHO")
+    });
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #124)")]
+fn query_tokens_after_unknown_token_fails() {
+    let env = Env::default();
+    let factory = deploy_factory_contract(&env, None);
+
+    factory.query_tokens(&Some(Address::generate(&env)), &10);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #103)")]
+fn query_pools_for_token_after_pool_of_another_token_fails() {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.cost_estimate().budget().reset_unlimited();
+    let admin = Address::generate(&env);
+    let factory = deploy_factory_contract(&env, Some(admin.clone()));
+
+    let pho = deploy_token(&env, &admin, "PHO").address;
+    let xlm = deploy_token(&env, &admin, "XLM").address;
+    let usdc = deploy_token(&env, &admin, "USDC").address;
+    let xlm_usdc = create_pool(&env, &factory, &admin, &xlm, &usdc, 30);
+
+    factory.query_pools_for_token(&pho, &Some(xlm_usdc), &10);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #123)")]
+fn set_token_info_with_short_logo_hash_fails() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let admin = Address::generate(&env);
+    let factory = deploy_factory_contract(&env, Some(admin.clone()));
+    let pho = deploy_token(&env, &admin, "PHO");
+
+    factory.set_token_info(&TokenInfo {
+        logo_hash: Some(Bytes::from_array(&env, &[1; 16])),
+        ..token_info(&env, &pho.address, "PHO")
+    });
+}
@@ -0,0 +1,286 @@
+extern crate std;
+
+use super::setup::{
+    deploy_factory_contract, generate_lp_init_info, install_and_deploy_token_contract,
+    install_blend_lp, install_lp_contract, install_stake_wasm, lp_contract,
+};
+use crate::{
+    contract::FactoryClient,
+    storage::{ContractVersion, UpgradeResult},
+};
+
+use phoenix::utils::{LiquidityPoolInitInfo, PoolType};
+use soroban_sdk::{
+    testutils::{Address as _, AuthorizedFunction, Ledger, MockAuth, MockAuthInvoke},
+    vec, Address, BytesN, Env, IntoVal, String, Symbol,
+};
+
+struct Pools {
+    first_xyk: Address,
+    blend: Address,
+    second_xyk: Address,
+}
+
+fn setup<'a>(env: &Env) -> (FactoryClient<'a>, Address, Pools) {
+    env.mock_all_auths();
+    env.cost_estimate().budget().reset_unlimited();
+
+    let admin = Address::generate(env);
+    let mut token_a = install_and_deploy_token_contract(
+        env,
+        admin.clone(),
+        7,
+        String::from_str(env, "Phoenix"),
+        String::from_str(env, "PHO"),
+    );
+    let mut token_b = install_and_deploy_token_contract(
+        env,
+        admin.clone(),
+        7,
+        String::from_str(env, "Stellar"),
+        String::from_str(env, "XLM"),
+    );
+    if token_b.address < token_a.address {
+        core::mem::swap(&mut token_a, &mut token_b);
+    }
+
+    let factory = deploy_factory_contract(env, Some(admin.clone()));
+    factory.set_blend_wasm_hash(&install_blend_lp(env));
+
+    let mut lp_init_info = generate_lp_init_info(
+        token_a.address.clone(),
+        token_b.address.clone(),
+        Address::generate(env),
+        admin.clone(),
+        Address::generate(env),
+    );
+    let create_pool = |lp_init_info: &LiquidityPoolInitInfo, pool_type: PoolType| {
+        factory.create_liquidity_pool(
+            &admin,
+            lp_init_info,
+            &String::from_str(env, "Pool"),
+            &String::from_str(env, "PHO/XLM"),
+            &pool_type,
+            &None::<u64>,
+            &100i64,
+            &1_000,
+            &None,
+        )
+    };
+
+    let first_xyk = create_pool(&lp_init_info, PoolType::Xyk);
+    let blend = create_pool(&lp_init_info, PoolType::Blend);
+    lp_init_info.swap_fee_bps = 30;
+    let second_xyk = create_pool(&lp_init_info, PoolType::Xyk);
+
+    (
+        factory,
+        admin,
+        Pools {
+            first_xyk,
+            blend,
+            second_xyk,
+        },
+    )
+}
+
+#[test]
+fn upgrade_pools_of_a_pool_type() {
+    let env = Env::default();
+    let (factory, admin, pools) = setup(&env);
+    env.ledger().with_mut(|li| li.timestamp = 1_000);
+
+    let new_wasm_hash = install_lp_contract(&env);
+    let results = factory.upgrade_pools(&PoolType::Xyk, &new_wasm_hash, &0, &10);
+
+    assert_eq!(
+        env.auths(),
+        std::vec![(
+            admin.clone(),
+            soroban_sdk::testutils::AuthorizedInvocation {
+                function: AuthorizedFunction::Contract((
+                    factory.address.clone(),
+                    Symbol::new(&env, "upgrade_pools"),
+                    (PoolType::Xyk, new_wasm_hash.clone(), 0u32, 10u32).into_val(&env),
+                )),
+                sub_invocations: std::vec![],
+            },
+        )]
+    );
+    assert_eq!(
+        results,
+        vec![
+            &env,
+            UpgradeResult {
+                contract: pools.first_xyk.clone(),
+                upgraded: true,
+            },
+            UpgradeResult {
+                contract: pools.second_xyk.clone(),
+                upgraded: true,
+            },
+        ]
+    );
+
+    let expected_version = ContractVersion {
+        wasm_hash: new_wasm_hash,
+        version: String::from_str(&env, env!("CARGO_PKG_VERSION")),
+        upgraded_at: 1_000,
+    };
+    assert_eq!(
+        factory.query_contract_version(&pools.first_xyk),
+        Some(expected_version.clone())
+    );
+    assert_eq!(
+        factory.query_contract_version(&pools.second_xyk),
+        Some(expected_version)
+    );
+    assert_eq!(factory.query_contract_version(&pools.blend), None);
+}
+
+#[test]
+fn upgrade_pools_in_batches() {
+    let env = Env::default();
+    let (factory, _, pools) = setup(&env);
+
+    let new_wasm_hash = install_lp_contract(&env);
+    // the blend pool at position 1 is skipped
+    let results = factory.upgrade_pools(&PoolType::Xyk, &new_wasm_hash, &1, &2);
+
+    assert_eq!(
+        results,
+        vec![
+            &env,
+            UpgradeResult {
+                contract: pools.second_xyk.clone(),
+                upgraded: true,
+            },
+        ]
+    );
+    assert_eq!(factory.query_contract_version(&pools.first_xyk), None);
+}
+
+#[test]
+fn failed_pool_upgrade_is_reported() {
+    let env = Env::default();
+    let (factory, _, pools) = setup(&env);
+
+    // not an uploaded wasm
+    let new_wasm_hash = BytesN::from_array(&env, &[7; 32]);
+    let results = factory.upgrade_pools(&PoolType::Blend, &new_wasm_hash, &0, &10);
+
+    assert_eq!(
+        results,
+        vec![
+            &env,
+            UpgradeResult {
+                contract: pools.blend.clone(),
+                upgraded: false,
+            },
+        ]
+    );
+    assert_eq!(factory.query_contract_version(&pools.blend), None);
+}
+
+#[test]
+fn upgrade_pools_of_a_pool_deployed_before_the_factory_was_stored() {
+    let env = Env::default();
+    let (factory, admin, pools) = setup(&env);
+    // pools deployed before the factory was stored only know their admin
+    env.as_contract(&pools.first_xyk, || {
+        env.storage().persistent().remove(&5u32);
+    });
+
+    let new_wasm_hash = install_lp_contract(&env);
+    // only the admin call of the factory is authorized, not the admin
+    // upgrade of the pool
+    let upgrade_pools = || {
+        env.mock_auths(&[MockAuth {
+            address: &admin,
+            invoke: &MockAuthInvoke {
+                contract: &factory.address,
+                fn_name: "upgrade_pools",
+                args: (PoolType::Xyk, new_wasm_hash.clone(), 0u32, 10u32).into_val(&env),
+                sub_invokes: &[],
+            },
+        }]);
+        factory.upgrade_pools(&PoolType::Xyk, &new_wasm_hash, &0, &10)
+    };
+
+    assert_eq!(
+        upgrade_pools(),
+        vec![
+            &env,
+            UpgradeResult {
+                contract: pools.first_xyk.clone(),
+                upgraded: false,
+            },
+            UpgradeResult {
+                contract: pools.second_xyk.clone(),
+                upgraded: true,
+            },
+        ]
+    );
+
+    env.mock_all_auths();
+    lp_contract::Client::new(&env, &pools.first_xyk).migrate_factory(&factory.address);
+
+    assert_eq!(
+        upgrade_pools(),
+        vec![
+            &env,
+            UpgradeResult {
+                contract: pools.first_xyk.clone(),
+                upgraded: true,
+            },
+            UpgradeResult {
+                contract: pools.second_xyk,
+                upgraded: true,
+            },
+        ]
+    );
+}
+
+#[test]
+fn upgrade_stakes_of_a_pool_type() {
+    let env = Env::default();
+    let (factory, _, pools) = setup(&env);
+
+    let new_wasm_hash = install_stake_wasm(&env);
+    let results = factory.upgrade_stakes(&PoolType::Xyk, &new_wasm_hash, &0, &10);
+
+    let first_stake = factory
+        .query_pool_details(&pools.first_xyk)
+        .pool_response
+        .stake_address;
+    let second_stake = factory
+        .query_pool_details(&pools.second_xyk)
+        .pool_response
+        .stake_address;
+    assert_eq!(
+        results,
+        vec![
+            &env,
+            UpgradeResult {
+                contract: first_stake.clone(),
+                upgraded: true,
+            },
+            UpgradeResult {
+                contract: second_stake,
+                upgraded: true,
+            },
+        ]
+    );
+    assert_eq!(
+        factory
+            .query_contract_version(&first_stake)
+            .unwrap()
+            .version,
+        String::from_str(&env, env!("CARGO_PKG_VERSION"))
+    );
+
+    // blend pools have no stake contract
+    assert!(factory
+        .upgrade_stakes(&PoolType::Blend, &new_wasm_hash, &0, &10)
+        .is_empty());
+}
@@ -0,0 +1,354 @@
+use phoenix::utils::PoolType;
+use soroban_sdk::{
+    testutils::{Address as _, Ledger},
+    vec, Address, Env, String,
+};
+
+use super::setup::{
+    deploy_factory_contract, generate_lp_init_info, install_and_deploy_token_contract, lp_contract,
+    stable_lp, stake_contract, ONE_DAY,
+};
+use crate::{
+    contract::FactoryClient,
+    storage::{Asset, ValuedAsset},
+    token_contract,
+};
+
+const SIXTY_DAYS: u64 = 60 * ONE_DAY;
+
+fn deploy_token<'a>(env: &Env, admin: &Address, symbol: &str) -> token_contract::Client<'a> {
+    install_and_deploy_token_contract(
+        env,
+        admin.clone(),
+        7,
+        String::from_str(env, symbol),
+        String::from_str(env, symbol),
+    )
+}
+
+/// Creates a XYK pool of the two tokens, seeded by `provider` with the given
+/// reserves
+#[allow(clippy::too_many_arguments)]
+fn create_seeded_pool<'a>(
+    env: &Env,
+    factory: &FactoryClient,
+    admin: &Address,
+    manager: &Address,
+    provider: &Address,
+    token_x: &token_contract::Client,
+    reserve_x: i128,
+    token_y: &token_contract::Client,
+    reserve_y: i128,
+) -> lp_contract::Client<'a> {
+    let ((token_a, reserve_a), (token_b, reserve_b)) = if token_x.address < token_y.address {
+        ((token_x, reserve_x), (token_y, reserve_y))
+    } else {
+        ((token_y, reserve_y), (token_x, reserve_x))
+    };
+
+    let lp_init_info = generate_lp_init_info(
+        token_a.address.clone(),
+        token_b.address.clone(),
+        manager.clone(),
+        admin.clone(),
+        admin.clone(),
+    );
+    let pool = lp_contract::Client::new(
+        env,
+        &factory.create_liquidity_pool(
+            admin,
+            &lp_init_info,
+            &String::from_str(env, "Pool"),
+            &String::from_str(env, "POOL"),
+            &PoolType::Xyk,
+            &None::<u64>,
+            &100i64,
+            &1_000,
+            &None,
+        ),
+    );
+
+    token_a.mint(provider, &reserve_a);
+    token_b.mint(provider, &reserve_b);
+    pool.provide_liquidity(
+        provider,
+        &Some(reserve_a),
+        &None,
+        &Some(reserve_b),
+        &None,
+        &None,
+        &None::<u64>,
+        &false,
+    );
+
+    pool
+}
+
+#[test]
+fn user_portfolio_valued_in_quote_token() {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.cost_estimate().budget().reset_unlimited();
+
+    let admin = Address::generate(&env);
+    let manager = Address::generate(&env);
+    let provider = Address::generate(&env);
+    let user = Address::generate(&env);
+
+    let pho = deploy_token(&env, &admin, "PHO");
+    let xlm = deploy_token(&env, &admin, "XLM");
+    let usdc = deploy_token(&env, &admin, "USDC");
+    // no pool against USDC, so it can't be priced
+    let eur = deploy_token(&env, &admin, "EUR");
+
+    let factory = deploy_factory_contract(&env, Some(admin.clone()));
+    // 1 PHO = 2 USDC, 1 XLM = 0.25 USDC
+    create_seeded_pool(
+        &env, &factory, &admin, &manager, &provider, &pho, 1_000_000, &usdc, 2_000_000,
+    );
+    let xlm_usdc = create_seeded_pool(
+        &env, &factory, &admin, &manager, &provider, &xlm, 4_000_000, &usdc, 1_000_000,
+    );
+    let pool = create_seeded_pool(
+        &env, &factory, &admin, &manager, &provider, &pho, 1_000_000, &xlm, 8_000_000,
+    );
+    let stake = stake_contract::Client::new(&env, &pool.query_stake_contract_address());
+
+    let (amount_a, amount_b) = if pho.address < xlm.address {
+        (10_000, 80_000)
+    } else {
+        (80_000, 10_000)
+    };
+    pho.mint(&user, &10_000);
+    xlm.mint(&user, &80_000);
+    pool.provide_liquidity(
+        &user,
+        &Some(amount_a),
+        &None,
+        &Some(amount_b),
+        &None,
+        &None,
+        &None::<u64>,
+        &false,
+    );
+    let shares =
+        token_contract::Client::new(&env, &pool.query_share_token_address()).balance(&user);
+    stake.bond(&user, &(shares / 2));
+
+    // the user owns the whole staked supply, so gets the whole distribution
+    env.ledger().with_mut(|li| li.timestamp = SIXTY_DAYS);
+    pho.mint(&manager, &1_000);
+    eur.mint(&manager, &500);
+    stake.create_distribution_flow(&manager, &pho.address);
+    stake.create_distribution_flow(&manager, &eur.address);
+    stake.distribute_rewards(&manager, &1_000, &pho.address);
+    stake.distribute_rewards(&manager, &500, &eur.address);
+    env.ledger().with_mut(|li| li.timestamp += ONE_DAY);
+
+    // the pools are paginated like `query_user_portfolio_paginated`
+    let first_page = factory.query_user_portfolio_valued(&user, &usdc.address, &None, &2);
+    assert!(first_page.lp_positions.is_empty());
+    assert_eq!(first_page.total_value, 0);
+
+    let portfolio = factory.query_user_portfolio_valued(
+        &user,
+        &usdc.address,
+        &Some(xlm_usdc.address.clone()),
+        &1,
+    );
+
+    let expected_value = |asset: &Asset| {
+        if asset.address == pho.address {
+            asset.amount * 2
+        } else if asset.address == xlm.address {
+            asset.amount / 4
+        } else {
+            0
+        }
+    };
+    let valued = |asset: lp_contract::Asset| {
+        let asset = Asset {
+            address: asset.address,
+            amount: asset.amount,
+        };
+        ValuedAsset {
+            value: expected_value(&asset),
+            asset,
+        }
+    };
+
+    assert_eq!(portfolio.quote_token, usdc.address);
+    assert_eq!(portfolio.lp_positions.len(), 1);
+    let lp_position = portfolio.lp_positions.get(0).unwrap();
+    let (share_a, share_b) = pool.query_share(&(shares - shares / 2));
+    assert_eq!(lp_position.pool, pool.address);
+    assert_eq!(lp_position.assets, (valued(share_a), valued(share_b)));
+    assert_eq!(
+        lp_position.value,
+        lp_position.assets.0.value + lp_position.assets.1.value
+    );
+    // 10_000 PHO and 80_000 XLM are worth 40_000 USDC, half of it unstaked
+    assert!((19_990..=20_000).contains(&lp_position.value));
+
+    assert_eq!(portfolio.stake_positions.len(), 1);
+    let stake_position = portfolio.stake_positions.get(0).unwrap();
+    let (staked_a, staked_b) = pool.query_share(&(shares / 2));
+    assert_eq!(stake_position.pool, pool.address);
+    assert_eq!(stake_position.staking_contract, stake.address);
+    assert_eq!(stake_position.staked_shares, shares / 2);
+    assert_eq!(stake_position.assets, (valued(staked_a), valued(staked_b)));
+    assert_eq!(
+        stake_position.pending_rewards,
+        vec![
+            &env,
+            ValuedAsset {
+                asset: Asset {
+                    address: pho.address.clone(),
+                    amount: 1_000,
+                },
+                value: 2_000,
+            },
+            ValuedAsset {
+                asset: Asset {
+                    address: eur.address.clone(),
+                    amount: 500,
+                },
+                value: 0,
+            },
+        ]
+    );
+    assert_eq!(stake_position.rewards_value, 2_000);
+
+    assert_eq!(portfolio.total_lp_value, lp_position.value);
+    assert_eq!(portfolio.total_staked_value, stake_position.value);
+    assert_eq!(portfolio.total_rewards_value, 2_000);
+    assert_eq!(
+        portfolio.total_value,
+        lp_position.value + stake_position.value + 2_000
+    );
+    assert_eq!(portfolio.unpriced_tokens, vec![&env, eur.address.clone()]);
+}
+
+#[test]
+fn user_portfolio_valued_without_positions_is_empty() {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.cost_estimate().budget().reset_unlimited();
+
+    let admin = Address::generate(&env);
+    let pho = deploy_token(&env, &admin, "PHO");
+    let usdc = deploy_token(&env, &admin, "USDC");
+    let factory = deploy_factory_contract(&env, Some(admin.clone()));
+    create_seeded_pool(
+        &env,
+        &factory,
+        &admin,
+        &Address::generate(&env),
+        &Address::generate(&env),
+        &pho,
+        1_000_000,
+        &usdc,
+        2_000_000,
+    );
+
+    let portfolio =
+        factory.query_user_portfolio_valued(&Address::generate(&env), &usdc.address, &None, &10);
+
+    assert!(portfolio.lp_positions.is_empty());
+    assert!(portfolio.stake_positions.is_empty());
+    assert_eq!(portfolio.total_value, 0);
+    assert!(portfolio.unpriced_tokens.is_empty());
+}
+
+#[test]
+fn stable_pools_do_not_price_tokens() {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.cost_estimate().budget().reset_unlimited();
+
+    let admin = Address::generate(&env);
+    let provider = Address::generate(&env);
+    let user = Address::generate(&env);
+    let pho = deploy_token(&env, &admin, "PHO");
+    let eurc = deploy_token(&env, &admin, "EURC");
+    let usdc = deploy_token(&env, &admin, "USDC");
+    let factory = deploy_factory_contract(&env, Some(admin.clone()));
+
+    let pool = create_seeded_pool(
+        &env,
+        &factory,
+        &admin,
+        &Address::generate(&env),
+        &provider,
+        &pho,
+        1_000_000,
+        &eurc,
+        1_000_000,
+    );
+
+    // EURC is only traded against USDC in a stable pool, with reserves that
+    // say nothing about its price
+    let (token_a, token_b) = if eurc.address < usdc.address {
+        (&eurc, &usdc)
+    } else {
+        (&usdc, &eurc)
+    };
+    let stable_pool = stable_lp::Client::new(
+        &env,
+        &factory.create_liquidity_pool(
+            &admin,
+            &generate_lp_init_info(
+                token_a.address.clone(),
+                token_b.address.clone(),
+                Address::generate(&env),
+                admin.clone(),
+                admin.clone(),
+            ),
+            &String::from_str(&env, "Pool Stable"),
+            &String::from_str(&env, "EURC/USDC"),
+            &PoolType::Stable,
+            &Some(10),
+            &100i64,
+            &1_000,
+            &None,
+        ),
+    );
+    let (reserve_a, reserve_b) = if token_a.address == eurc.address {
+        (1_000_000, 3_000_000)
+    } else {
+        (3_000_000, 1_000_000)
+    };
+    token_a.mint(&provider, &reserve_a);
+    token_b.mint(&provider, &reserve_b);
+    stable_pool.provide_liquidity(
+        &provider,
+        &reserve_a,
+        &reserve_b,
+        &None,
+        &None::<u64>,
+        &None,
+        &false,
+    );
+
+    pho.mint(&user, &10_000);
+    eurc.mint(&user, &10_000);
+    pool.provide_liquidity(
+        &user,
+        &Some(10_000),
+        &None,
+        &Some(10_000),
+        &None,
+        &None,
+        &None::<u64>,
+        &false,
+    );
+
+    let portfolio = factory.query_user_portfolio_valued(&user, &usdc.address, &None, &10);
+
+    assert_eq!(portfolio.lp_positions.len(), 1);
+    assert_eq!(portfolio.total_value, 0);
+    // PHO has no pool against USDC either
+    assert_eq!(portfolio.unpriced_tokens.len(), 2);
+    assert!(portfolio.unpriced_tokens.contains(&eurc.address));
+    assert!(portfolio.unpriced_tokens.contains(&pho.address));
+}
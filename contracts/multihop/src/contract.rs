@@ -103,8 +103,7 @@ impl MultihopTrait for Multihop {
         let factory_client = factory_contract::Client::new(&env, &get_factory(&env));
 
         operations.iter().for_each(|op| {
-            let liquidity_pool_addr: Address =
-                resolve_pool_addr(&factory_client, pool_type, &op.offer_asset, &op.ask_asset);
+            let liquidity_pool_addr: Address = resolve_pool_addr(&factory_client, pool_type, &op);
 
             match pool_type {
                 PoolType::Xyk => {
@@ -176,8 +175,7 @@ impl MultihopTrait for Multihop {
         let factory_client = factory_contract::Client::new(&env, &get_factory(&env));
 
         operations.iter().for_each(|op| {
-            let pool_addres: Address =
-                resolve_pool_addr(&factory_client, pool_type, &op.offer_asset, &op.ask_asset);
+            let pool_addres: Address = resolve_pool_addr(&factory_client, pool_type, &op);
 
             // due to different pool libraries we cannot use shorter match statement.
             match pool_type {
@@ -265,8 +263,7 @@ impl MultihopTrait for Multihop {
         let factory_client = factory_contract::Client::new(&env, &get_factory(&env));
 
         operations.iter().for_each(|op| {
-            let pool_address: Address =
-                resolve_pool_addr(&factory_client, pool_type, &op.offer_asset, &op.ask_asset);
+            let pool_address: Address = resolve_pool_addr(&factory_client, pool_type, &op);
 
             // due to different pool libraries we cannot use shorter match statement.
             match pool_type {
@@ -465,16 +462,28 @@ impl Multihop {
 /// * `Stable` and `Blend` use `query_pool_by_pair_type`, which reads the
 ///   `(pool_type, a, b)` storage slot and lets same-pair pools of different
 ///   types coexist without clobbering each other's routing.
+/// * A hop with a `fee_tier` uses `query_pool_by_pair_type_and_tier`, which
+///   picks the pool of that tier among same-pair pools of the same type.
 fn resolve_pool_addr(
     factory_client: &factory_contract::Client,
     pool_type: PoolType,
-    offer_asset: &Address,
-    ask_asset: &Address,
+    op: &Swap,
 ) -> Address {
+    if let Some(fee_tier) = op.fee_tier {
+        return factory_client.query_pool_by_pair_type_and_tier(
+            &op.offer_asset,
+            &op.ask_asset,
+            &pool_type,
+            &fee_tier,
+        );
+    }
+
     match pool_type {
-        PoolType::Xyk => factory_client.query_for_pool_by_token_pair(offer_asset, ask_asset),
+        PoolType::Xyk => {
+            factory_client.query_for_pool_by_token_pair(&op.offer_asset, &op.ask_asset)
+        }
         PoolType::Stable | PoolType::Blend => {
-            factory_client.query_pool_by_pair_type(offer_asset, ask_asset, &pool_type)
+            factory_client.query_pool_by_pair_type(&op.offer_asset, &op.ask_asset, &pool_type)
         }
     }
 }
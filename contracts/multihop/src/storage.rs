@@ -15,6 +15,9 @@ pub struct Swap {
     pub ask_asset: Address,
     pub offer_asset: Address,
     pub ask_asset_min_amount: Option<i128>,
+    /// Fee tier (swap fee in bps) of the pool to route this hop through; the
+    /// default pool of the pair when `None`
+    pub fee_tier: Option<i64>,
 }
 
 #[derive(Clone)]
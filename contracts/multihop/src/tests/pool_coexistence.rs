@@ -102,6 +102,7 @@ fn multihop_routes_xyk_and_blend_independently_for_same_pair() {
         offer_asset: token_a.address.clone(),
         ask_asset: token_b.address.clone(),
         ask_asset_min_amount: None::<i128>,
+        fee_tier: None,
     };
     multihop.swap(
         &recipient_xyk,
@@ -219,6 +220,7 @@ fn simulate_swap_dispatches_per_pool_type() {
         offer_asset: token_a.address.clone(),
         ask_asset: token_b.address.clone(),
         ask_asset_min_amount: None::<i128>,
+        fee_tier: None,
     };
 
     let xyk_sim = multihop.simulate_swap(&vec![&env, swap_op.clone()], &10_000i128, &PoolType::Xyk);
@@ -282,6 +284,7 @@ fn pre_existing_xyk_routes_via_legacy_query_after_factory_upgrade() {
                 offer_asset: token_a.address.clone(),
                 ask_asset: token_b.address.clone(),
                 ask_asset_min_amount: None::<i128>,
+                fee_tier: None,
             },
         ],
         &None::<i64>,
@@ -299,3 +302,100 @@ fn pre_existing_xyk_routes_via_legacy_query_after_factory_upgrade() {
         "recipient must receive token_b via Xyk routing"
     );
 }
+
+#[test]
+fn multihop_picks_the_fee_tier_per_hop() {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.cost_estimate().budget().reset_unlimited();
+
+    let admin = Address::generate(&env);
+    let token_a = deploy_and_mint_tokens(&env, &admin, 50_000_000i128);
+    let token_b = deploy_and_mint_tokens(&env, &admin, 50_000_000i128);
+    let token_c = deploy_and_mint_tokens(&env, &admin, 50_000_000i128);
+
+    let factory = deploy_factory_with_blend_support(&env, admin.clone());
+
+    // same pair in the 0 and 100 bps fee tiers, the first one is the default
+    for fees in [None, Some(100)] {
+        deploy_and_initialize_pool(
+            &env,
+            &factory,
+            admin.clone(),
+            token_a.address.clone(),
+            1_000_000,
+            token_b.address.clone(),
+            1_000_000,
+            fees,
+            PoolType::Xyk,
+        );
+    }
+    deploy_and_initialize_pool(
+        &env,
+        &factory,
+        admin.clone(),
+        token_b.address.clone(),
+        1_000_000,
+        token_c.address.clone(),
+        1_000_000,
+        None,
+        PoolType::Xyk,
+    );
+
+    let multihop = deploy_multihop_contract(&env, admin.clone(), &factory.address);
+
+    let operations = |fee_tier: Option<i64>| {
+        vec![
+            &env,
+            Swap {
+                offer_asset: token_a.address.clone(),
+                ask_asset: token_b.address.clone(),
+                ask_asset_min_amount: None::<i128>,
+                fee_tier,
+            },
+            Swap {
+                offer_asset: token_b.address.clone(),
+                ask_asset: token_c.address.clone(),
+                ask_asset_min_amount: None::<i128>,
+                fee_tier: Some(0),
+            },
+        ]
+    };
+
+    let default_sim = multihop.simulate_swap(&operations(None), &10_000i128, &PoolType::Xyk);
+    let zero_tier_sim = multihop.simulate_swap(&operations(Some(0)), &10_000i128, &PoolType::Xyk);
+    let high_tier_sim = multihop.simulate_swap(&operations(Some(100)), &10_000i128, &PoolType::Xyk);
+
+    assert_eq!(default_sim, zero_tier_sim);
+    assert_eq!(zero_tier_sim.commission_amounts.get(0).unwrap().1, 0);
+    // 1% of the 9_901 ask amount of the first hop
+    assert_eq!(high_tier_sim.commission_amounts.get(0).unwrap().1, 99);
+    assert_eq!(high_tier_sim.commission_amounts.get(1).unwrap().1, 0);
+    assert!(high_tier_sim.ask_amount < zero_tier_sim.ask_amount);
+
+    let recipient = Address::generate(&env);
+    token_a.mint(&recipient, &10_000i128);
+    multihop.swap(
+        &recipient,
+        &operations(Some(100)),
+        &None::<i64>,
+        &10_000i128,
+        &PoolType::Xyk,
+        &None::<u64>,
+        &None::<i64>,
+    );
+    assert_eq!(token_c.balance(&recipient), high_tier_sim.ask_amount);
+
+    let high_tier_pool = factory.query_pool_by_pair_type_and_tier(
+        &token_a.address,
+        &token_b.address,
+        &PoolType::Xyk,
+        &100,
+    );
+    let high_tier_info = xyk_pool::Client::new(&env, &high_tier_pool).query_pool_info();
+    // the commission leaves the pool to the fee recipient
+    assert_eq!(
+        high_tier_info.asset_a.amount + high_tier_info.asset_b.amount,
+        2_000_000 + 10_000 - 9_901
+    );
+}
@@ -61,6 +61,7 @@ fn simulate_swap_single_pool_no_fees() {
             offer_asset: token1.address.clone(),
             ask_asset: token2.address.clone(),
             ask_asset_min_amount: None::<i128>,
+            fee_tier: None,
         },
     ];
 
@@ -173,16 +174,19 @@ fn simulate_swap_three_equal_pools_no_fees() {
                 offer_asset: token1.address.clone(),
                 ask_asset: token2.address.clone(),
                 ask_asset_min_amount: None::<i128>,
+                fee_tier: None,
             },
             Swap {
                 offer_asset: token2.address.clone(),
                 ask_asset: token3.address.clone(),
                 ask_asset_min_amount: None::<i128>,
+                fee_tier: None,
             },
             Swap {
                 offer_asset: token3.address.clone(),
                 ask_asset: token4.address.clone(),
                 ask_asset_min_amount: None::<i128>,
+                fee_tier: None,
             },
         ],
         &50i128,
@@ -212,16 +216,19 @@ fn simulate_swap_three_equal_pools_no_fees() {
                 offer_asset: token3.address.clone(),
                 ask_asset: token4.address.clone(),
                 ask_asset_min_amount: None::<i128>,
+                fee_tier: None,
             },
             Swap {
                 offer_asset: token2.address.clone(),
                 ask_asset: token3.address.clone(),
                 ask_asset_min_amount: None::<i128>,
+                fee_tier: None,
             },
             Swap {
                 offer_asset: token1.address.clone(),
                 ask_asset: token2.address.clone(),
                 ask_asset_min_amount: None::<i128>,
+                fee_tier: None,
             },
         ],
         &50i128,
@@ -292,6 +299,7 @@ fn simulate_swap_single_pool_with_fees() {
             offer_asset: token1.address.clone(),
             ask_asset: token2.address.clone(),
             ask_asset_min_amount: None::<i128>,
+            fee_tier: None,
         },
     ];
 
@@ -405,16 +413,19 @@ fn simulate_swap_three_different_pools_no_fees() {
                 offer_asset: token1.address.clone(),
                 ask_asset: token2.address.clone(),
                 ask_asset_min_amount: None::<i128>,
+                fee_tier: None,
             },
             Swap {
                 offer_asset: token2.address.clone(),
                 ask_asset: token3.address.clone(),
                 ask_asset_min_amount: None::<i128>,
+                fee_tier: None,
             },
             Swap {
                 offer_asset: token3.address.clone(),
                 ask_asset: token4.address.clone(),
                 ask_asset_min_amount: None::<i128>,
+                fee_tier: None,
             },
         ],
         &5_000i128,
@@ -446,16 +457,19 @@ fn simulate_swap_three_different_pools_no_fees() {
                 offer_asset: token3.address.clone(),
                 ask_asset: token4.address.clone(),
                 ask_asset_min_amount: None::<i128>,
+                fee_tier: None,
             },
             Swap {
                 offer_asset: token2.address.clone(),
                 ask_asset: token3.address.clone(),
                 ask_asset_min_amount: None::<i128>,
+                fee_tier: None,
             },
             Swap {
                 offer_asset: token1.address.clone(),
                 ask_asset: token2.address.clone(),
                 ask_asset_min_amount: None::<i128>,
+                fee_tier: None,
             },
         ],
         &4_956i128,
@@ -566,16 +580,19 @@ fn simulate_swap_three_different_pools_with_fees() {
                 offer_asset: token1.address.clone(),
                 ask_asset: token2.address.clone(),
                 ask_asset_min_amount: None::<i128>,
+                fee_tier: None,
             },
             Swap {
                 offer_asset: token2.address.clone(),
                 ask_asset: token3.address.clone(),
                 ask_asset_min_amount: None::<i128>,
+                fee_tier: None,
             },
             Swap {
                 offer_asset: token3.address.clone(),
                 ask_asset: token4.address.clone(),
                 ask_asset_min_amount: None::<i128>,
+                fee_tier: None,
             },
         ],
         &10_000i128,
@@ -629,16 +646,19 @@ fn simulate_swap_three_different_pools_with_fees() {
                 offer_asset: token3.address.clone(),
                 ask_asset: token4.address.clone(),
                 ask_asset_min_amount: None::<i128>,
+                fee_tier: None,
             },
             Swap {
                 offer_asset: token2.address.clone(),
                 ask_asset: token3.address.clone(),
                 ask_asset_min_amount: None::<i128>,
+                fee_tier: None,
             },
             Swap {
                 offer_asset: token1.address.clone(),
                 ask_asset: token2.address.clone(),
                 ask_asset_min_amount: None::<i128>,
+                fee_tier: None,
             },
         ],
         &203_143i128,
@@ -747,6 +767,7 @@ fn simulate_swap_stable_pool() {
             offer_asset: token1.address.clone(),
             ask_asset: token2.address.clone(),
             ask_asset_min_amount: None::<i128>,
+            fee_tier: None,
         },
     ];
 
@@ -79,16 +79,19 @@ fn swap_three_equal_pools_no_fees() {
         offer_asset: token1.address.clone(),
         ask_asset: token2.address.clone(),
         ask_asset_min_amount: None::<i128>,
+        fee_tier: None,
     };
     let swap2 = Swap {
         offer_asset: token2.address.clone(),
         ask_asset: token3.address.clone(),
         ask_asset_min_amount: None::<i128>,
+        fee_tier: None,
     };
     let swap3 = Swap {
         offer_asset: token3.address.clone(),
         ask_asset: token4.address.clone(),
         ask_asset_min_amount: None::<i128>,
+        fee_tier: None,
     };
 
     let operations = vec![&env, swap1, swap2, swap3];
@@ -174,16 +177,19 @@ fn swap_three_equal_pools_no_fees_referral_fee() {
         offer_asset: token1.address.clone(),
         ask_asset: token2.address.clone(),
         ask_asset_min_amount: None::<i128>,
+        fee_tier: None,
     };
     let swap2 = Swap {
         offer_asset: token2.address.clone(),
         ask_asset: token3.address.clone(),
         ask_asset_min_amount: None::<i128>,
+        fee_tier: None,
     };
     let swap3 = Swap {
         offer_asset: token3.address.clone(),
         ask_asset: token4.address.clone(),
         ask_asset_min_amount: None::<i128>,
+        fee_tier: None,
     };
 
     let operations = vec![&env, swap1, swap2, swap3];
@@ -260,6 +266,7 @@ fn swap_single_pool_no_fees() {
         offer_asset: token1.address.clone(),
         ask_asset: token2.address.clone(),
         ask_asset_min_amount: None::<i128>,
+        fee_tier: None,
     };
 
     let operations = vec![&env, swap1];
@@ -316,6 +323,7 @@ fn swap_should_fail_when_spread_exceeds_the_limit() {
         offer_asset: token1.address.clone(),
         ask_asset: token2.address.clone(),
         ask_asset_min_amount: None::<i128>,
+        fee_tier: None,
     };
 
     let operations = vec![&env, swap1];
@@ -370,6 +378,7 @@ fn swap_single_pool_with_fees() {
         offer_asset: token1.address.clone(),
         ask_asset: token2.address.clone(),
         ask_asset_min_amount: None::<i128>,
+        fee_tier: None,
     };
 
     let operations = vec![&env, swap1];
@@ -456,16 +465,19 @@ fn swap_three_different_pools_no_fees() {
         offer_asset: token1.address.clone(),
         ask_asset: token2.address.clone(),
         ask_asset_min_amount: None::<i128>,
+        fee_tier: None,
     };
     let swap2 = Swap {
         offer_asset: token2.address.clone(),
         ask_asset: token3.address.clone(),
         ask_asset_min_amount: None::<i128>,
+        fee_tier: None,
     };
     let swap3 = Swap {
         offer_asset: token3.address.clone(),
         ask_asset: token4.address.clone(),
         ask_asset_min_amount: None::<i128>,
+        fee_tier: None,
     };
 
     let operations = vec![&env, swap1, swap2, swap3];
@@ -552,16 +564,19 @@ fn swap_three_different_pools_with_fees() {
         offer_asset: token1.address.clone(),
         ask_asset: token2.address.clone(),
         ask_asset_min_amount: None::<i128>,
+        fee_tier: None,
     };
     let swap2 = Swap {
         offer_asset: token2.address.clone(),
         ask_asset: token3.address.clone(),
         ask_asset_min_amount: None::<i128>,
+        fee_tier: None,
     };
     let swap3 = Swap {
         offer_asset: token3.address.clone(),
         ask_asset: token4.address.clone(),
         ask_asset_min_amount: None::<i128>,
+        fee_tier: None,
     };
 
     let operations = vec![&env, swap1, swap2, swap3];
@@ -689,16 +704,19 @@ fn test_v_phx_vul_013_add_belief_price_for_every_swap() {
         offer_asset: token1.address.clone(),
         ask_asset: token2.address.clone(),
         ask_asset_min_amount: Some(1_050),
+        fee_tier: None,
     };
     let swap2 = Swap {
         offer_asset: token2.address.clone(),
         ask_asset: token3.address.clone(),
         ask_asset_min_amount: Some(2_100),
+        fee_tier: None,
     };
     let swap3 = Swap {
         offer_asset: token3.address.clone(),
         ask_asset: token4.address.clone(),
         ask_asset_min_amount: Some(3_150),
+        fee_tier: None,
     };
 
     let operations = vec![&env, swap1, swap2, swap3];
@@ -761,6 +779,7 @@ fn test_swap_with_ask_asset_min_amount() {
         offer_asset: token1.address.clone(),
         ask_asset: token2.address.clone(),
         ask_asset_min_amount: Some(1_000),
+        fee_tier: None,
     };
 
     let operations = vec![&env, swap1];
@@ -782,6 +801,7 @@ fn test_swap_with_ask_asset_min_amount() {
         offer_asset: token1.address.clone(),
         ask_asset: token2.address.clone(),
         ask_asset_min_amount: Some(10_000),
+        fee_tier: None,
     };
     let operations = vec![&env, greedy_swap];
     multihop.swap(
@@ -857,16 +877,19 @@ fn swap_three_equal_stable_pool() {
         offer_asset: token1.address.clone(),
         ask_asset: token2.address.clone(),
         ask_asset_min_amount: None::<i128>,
+        fee_tier: None,
     };
     let swap2 = Swap {
         offer_asset: token2.address.clone(),
         ask_asset: token3.address.clone(),
         ask_asset_min_amount: None::<i128>,
+        fee_tier: None,
     };
     let swap3 = Swap {
         offer_asset: token3.address.clone(),
         ask_asset: token4.address.clone(),
         ask_asset_min_amount: None::<i128>,
+        fee_tier: None,
     };
 
     let operations = vec![&env, swap1, swap2, swap3];
@@ -43,16 +43,19 @@ mod tests {
             offer_asset: token1.clone(),
             ask_asset: token2.clone(),
             ask_asset_min_amount: None::<i128>,
+            fee_tier: None,
         };
         let swap2 = Swap {
             offer_asset: token2.clone(),
             ask_asset: token3.clone(),
             ask_asset_min_amount: None::<i128>,
+            fee_tier: None,
         };
         let swap3 = Swap {
             offer_asset: token3.clone(),
             ask_asset: token4.clone(),
             ask_asset_min_amount: None::<i128>,
+            fee_tier: None,
         };
 
         let operations = vec![&env, swap1, swap2, swap3];
@@ -73,16 +76,19 @@ mod tests {
             offer_asset: token3.clone(),
             ask_asset: token4.clone(),
             ask_asset_min_amount: None::<i128>,
+            fee_tier: None,
         };
         let swap2 = Swap {
             offer_asset: token2.clone(),
             ask_asset: token3.clone(),
             ask_asset_min_amount: None::<i128>,
+            fee_tier: None,
         };
         let swap3 = Swap {
             offer_asset: token1.clone(),
             ask_asset: token2.clone(),
             ask_asset_min_amount: None::<i128>,
+            fee_tier: None,
         };
 
         let operations = vec![&env, swap1, swap2, swap3];
@@ -104,11 +110,13 @@ mod tests {
             offer_asset: token1.clone(),
             ask_asset: token2.clone(),
             ask_asset_min_amount: None::<i128>,
+            fee_tier: None,
         };
         let swap2 = Swap {
             offer_asset: token3.clone(),
             ask_asset: token4.clone(),
             ask_asset_min_amount: None::<i128>,
+            fee_tier: None,
         };
 
         let operations = vec![&env, swap1, swap2];
@@ -130,11 +138,13 @@ mod tests {
             offer_asset: token1.clone(),
             ask_asset: token2.clone(),
             ask_asset_min_amount: None::<i128>,
+            fee_tier: None,
         };
         let swap2 = Swap {
             offer_asset: token3.clone(),
             ask_asset: token4.clone(),
             ask_asset_min_amount: None::<i128>,
+            fee_tier: None,
         };
 
         let operations = vec![&env, swap1, swap2];
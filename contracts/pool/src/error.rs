@@ -41,4 +41,6 @@ pub enum ContractError {
     SameAdmin = 330,
     NoAdminChangeInPlace = 331,
     AdminChangeExpired = 332,
+    SwapsPaused = 333,
+    FactoryAlreadySet = 334,
 }
@@ -21,6 +21,8 @@ pub enum DataKey {
     ReserveB = 2,
     Admin = 3,
     Initialized = 4, // TODO: deprecated, remove in next upgrade
+    Factory = 5,
+    SwapsPaused = 6,
 }
 
 impl TryFromVal<Env, DataKey> for Val {
@@ -236,6 +238,56 @@ pub mod utils {
         )
     }
 
+    pub fn save_swaps_paused(e: &Env, paused: bool) {
+        e.storage().persistent().set(&DataKey::SwapsPaused, &paused);
+        e.storage().persistent().extend_ttl(
+            &DataKey::SwapsPaused,
+            PERSISTENT_RENEWAL_THRESHOLD,
+            PERSISTENT_TARGET_TTL,
+        );
+    }
+
+    pub fn is_swaps_paused(e: &Env) -> bool {
+        let paused = e
+            .storage()
+            .persistent()
+            .get(&DataKey::SwapsPaused)
+            .unwrap_or(false);
+        if paused {
+            e.storage().persistent().extend_ttl(
+                &DataKey::SwapsPaused,
+                PERSISTENT_RENEWAL_THRESHOLD,
+                PERSISTENT_TARGET_TTL,
+            );
+        }
+
+        paused
+    }
+
+    pub fn save_factory(e: &Env, factory: &Address) {
+        e.storage().persistent().set(&DataKey::Factory, factory);
+        e.storage().persistent().extend_ttl(
+            &DataKey::Factory,
+            PERSISTENT_RENEWAL_THRESHOLD,
+            PERSISTENT_TARGET_TTL,
+        );
+    }
+
+    /// Factory that deployed the pool, unknown for pools deployed before it
+    /// was stored
+    pub fn get_factory(e: &Env) -> Option<Address> {
+        let factory = e.storage().persistent().get(&DataKey::Factory);
+        if factory.is_some() {
+            e.storage().persistent().extend_ttl(
+                &DataKey::Factory,
+                PERSISTENT_RENEWAL_THRESHOLD,
+                PERSISTENT_TARGET_TTL,
+            );
+        }
+
+        factory
+    }
+
     pub fn save_admin_old(e: &Env, address: Address) {
         e.storage().persistent().set(&DataKey::Admin, &address);
         e.storage().persistent().extend_ttl(
@@ -58,4 +58,11 @@ pub enum ContractError {
     /// The LP share token is still minted/burned normally; the caller
     /// must just pass `auto_stake=false` and skip the unstake hint.
     StakingDisabled = 338,
+
+    /// `swap` invoked while swaps are paused, e.g. on a deprecated pool.
+    /// `withdraw_liquidity` is unaffected.
+    SwapsPaused = 339,
+
+    /// `migrate_factory` called on a pool that already knows its factory.
+    FactoryAlreadySet = 340,
 }
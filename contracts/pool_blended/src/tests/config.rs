@@ -454,3 +454,33 @@ fn test_initialize_with_maximum_allowed_swap_fee_bps_over_the_cap_should_fail()
         ),
     );
 }
+
+#[test]
+#[should_panic(expected = "Error(Contract, #340)")]
+fn migrate_factory_of_pool_with_factory_fails() {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.cost_estimate().budget().reset_unlimited();
+
+    let admin = Address::generate(&env);
+    let mut token1 = deploy_token_contract(&env, &admin);
+    let mut token2 = deploy_token_contract(&env, &admin);
+    if token2.address < token1.address {
+        std::mem::swap(&mut token1, &mut token2);
+    }
+
+    let pool = deploy_liquidity_pool_contract(
+        &env,
+        Some(admin),
+        (&token1.address, &token2.address),
+        0i64,
+        None,
+        None,
+        None,
+        Address::generate(&env),
+        Address::generate(&env),
+    );
+
+    // the pool was deployed with its factory already
+    pool.migrate_factory(&Address::generate(&env));
+}
@@ -97,6 +97,16 @@ pub trait StableLiquidityPoolTrait {
     // Migration entrypoint
     fn upgrade(e: Env, new_wasm_hash: BytesN<32>);
 
+    // Records the factory of a pool deployed before the factory was stored,
+    // so `upgrade` and `set_swaps_paused` accept the factory from then on
+    fn migrate_factory(env: Env, factory: Address);
+
+    // Pauses or resumes swaps, e.g. when the factory deprecates the pool.
+    // Liquidity can still be withdrawn while swaps are paused.
+    fn set_swaps_paused(env: Env, paused: bool);
+
+    fn query_swaps_paused(env: Env) -> bool;
+
     // QUERIES
 
     // Returns the configuration structure containing the addresses
@@ -389,6 +399,11 @@ impl StableLiquidityPoolTrait for StableLiquidityPool {
             .instance()
             .extend_ttl(INSTANCE_RENEWAL_THRESHOLD, INSTANCE_TARGET_TTL);
 
+        if utils::is_swaps_paused(&env) {
+            log!(&env, "Pool Stable: Swap: Swaps are paused");
+            panic_with_error!(env, ContractError::SwapsPaused);
+        }
+
         do_swap(
             env,
             sender,
@@ -557,10 +572,51 @@ impl StableLiquidityPoolTrait for StableLiquidityPool {
     }
 
     fn upgrade(env: Env, new_wasm_hash: BytesN<32>) {
+        // the factory upgrades the pools it deployed, older pools are
+        // upgraded by their admin
+        let upgrade_authority: Address =
+            utils::get_factory(&env).unwrap_or_else(|| utils::get_admin_old(&env));
+        upgrade_authority.require_auth();
+
+        env.deployer().update_current_contract_wasm(new_wasm_hash);
+    }
+
+    fn migrate_factory(env: Env, factory: Address) {
         let admin: Address = utils::get_admin_old(&env);
         admin.require_auth();
+        env.storage()
+            .instance()
+            .extend_ttl(INSTANCE_RENEWAL_THRESHOLD, INSTANCE_TARGET_TTL);
 
-        env.deployer().update_current_contract_wasm(new_wasm_hash);
+        if utils::get_factory(&env).is_some() {
+            log!(&env, "Pool Stable: MigrateFactory: Factory already set");
+            panic_with_error!(&env, ContractError::FactoryAlreadySet);
+        }
+
+        utils::save_factory(&env, &factory);
+
+        env.events()
+            .publish(("migrate_factory", "factory"), factory);
+    }
+
+    fn set_swaps_paused(env: Env, paused: bool) {
+        let authority: Address =
+            utils::get_factory(&env).unwrap_or_else(|| utils::get_admin_old(&env));
+        authority.require_auth();
+        env.storage()
+            .instance()
+            .extend_ttl(INSTANCE_RENEWAL_THRESHOLD, INSTANCE_TARGET_TTL);
+
+        utils::save_swaps_paused(&env, paused);
+
+        env.events().publish(("set_swaps_paused", "paused"), paused);
+    }
+
+    fn query_swaps_paused(env: Env) -> bool {
+        env.storage()
+            .instance()
+            .extend_ttl(INSTANCE_RENEWAL_THRESHOLD, INSTANCE_TARGET_TTL);
+        utils::is_swaps_paused(&env)
     }
 
     // Queries
@@ -983,6 +1039,7 @@ impl StableLiquidityPool {
             },
         );
         utils::save_admin_old(&env, admin);
+        utils::save_factory(&env, &factory_addr);
         utils::save_total_shares(&env, 0);
         utils::save_pool_balance_a(&env, 0);
         utils::save_pool_balance_b(&env, 0);
@@ -33,4 +33,6 @@ pub enum ContractError {
     SameAdmin = 426,
     NoAdminChangeInPlace = 427,
     AdminChangeExpired = 428,
+    SwapsPaused = 429,
+    FactoryAlreadySet = 430,
 }
@@ -23,6 +23,8 @@ pub enum DataKey {
     Amp = 5,
     MaxPrecision = 6,
     TokenPrecision = 7,
+    Factory = 8,
+    SwapsPaused = 9,
 }
 
 impl TryFromVal<Env, DataKey> for Val {
@@ -275,6 +277,38 @@ pub mod utils {
         )
     }
 
+    pub fn save_swaps_paused(e: &Env, paused: bool) {
+        e.storage().instance().set(&DataKey::SwapsPaused, &paused);
+        e.storage()
+            .instance()
+            .extend_ttl(INSTANCE_RENEWAL_THRESHOLD, INSTANCE_TARGET_TTL);
+    }
+
+    pub fn is_swaps_paused(e: &Env) -> bool {
+        e.storage()
+            .instance()
+            .get(&DataKey::SwapsPaused)
+            .unwrap_or(false)
+    }
+
+    pub fn save_factory(e: &Env, factory: &Address) {
+        e.storage().instance().set(&DataKey::Factory, factory);
+        e.storage()
+            .instance()
+            .extend_ttl(INSTANCE_RENEWAL_THRESHOLD, INSTANCE_TARGET_TTL);
+    }
+
+    /// Factory that deployed the pool, unknown for pools deployed before it
+    /// was stored
+    pub fn get_factory(e: &Env) -> Option<Address> {
+        let factory = e.storage().instance().get(&DataKey::Factory);
+        e.storage()
+            .instance()
+            .extend_ttl(INSTANCE_RENEWAL_THRESHOLD, INSTANCE_TARGET_TTL);
+
+        factory
+    }
+
     pub fn save_admin_old(e: &Env, address: Address) {
         e.storage().instance().set(&DataKey::Admin, &address);
         e.storage()
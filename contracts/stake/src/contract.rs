@@ -1,23 +1,32 @@
-use phoenix::ttl::{INSTANCE_RENEWAL_THRESHOLD, INSTANCE_TARGET_TTL};
+use phoenix::{
+    ttl::{INSTANCE_RENEWAL_THRESHOLD, INSTANCE_TARGET_TTL},
+    utils::AdminChange,
+};
 use soroban_sdk::{
     contract, contractimpl, contractmeta, log, map, panic_with_error, vec, Address, BytesN, Env,
-    Vec,
+    IntoVal, Map, String, Symbol, Vec,
 };
 
 use crate::{
     distribution::{
-        calculate_pending_rewards, get_reward_history, get_total_staked_history,
-        save_reward_history, save_total_staked_history,
+        calculate_annualized_reward, calculate_pending_rewards, get_reward_history,
+        get_total_staked_history, save_reward_history, save_total_staked_history,
     },
     error::ContractError,
-    msg::{ConfigResponse, StakedResponse, WithdrawableReward, WithdrawableRewardsResponse},
+    msg::{
+        AnnualizedReward, AnnualizedRewardsResponse, ConfigResponse, StakeRewardsResponse,
+        StakedResponse, WithdrawableReward, WithdrawableRewardsResponse,
+    },
     storage::{
-        get_config, get_stakes, save_config, save_stakes,
+        get_config, get_pool, get_reward_recipient, get_stakes, remove_reward_recipient,
+        save_config, save_pool, save_reward_recipient, save_stakes,
         utils::{
-            self, add_distribution, get_admin_old, get_distributions, get_total_staked_counter,
-            is_initialized, set_initialized,
+            self, add_distribution, get_admin_old, get_claimable_distributions, get_distributions,
+            get_total_staked_counter, is_initialized, remove_distribution, restore_distribution,
+            set_initialized,
         },
-        BondingInfo, Config, Stake, ADMIN,
+        BondingInfo, Config, PoolResponse, SimulateSwapResponse, Stake, UnbondStrategy, ADMIN,
+        PENDING_ADMIN,
     },
     token_contract,
 };
@@ -37,12 +46,41 @@ pub trait StakingTrait {
 
     fn unbond(env: Env, sender: Address, stake_amount: i128, stake_timestamp: u64);
 
+    /// Unbonds `amount` of LP shares across the stakes of `sender`, consuming
+    /// them in the order given by `strategy` and splitting the last one if needed.
+    fn unbond_amount(env: Env, sender: Address, amount: i128, strategy: UnbondStrategy);
+
+    /// Moves the stake of `stake_amount` that `from` bonded at
+    /// `stake_timestamp` to `to`. The stake keeps its timestamp, so its age
+    /// multiplier is preserved. Fails once `to` holds `max_complexity` stakes.
+    fn transfer_stake(
+        env: Env,
+        from: Address,
+        to: Address,
+        stake_amount: i128,
+        stake_timestamp: u64,
+    );
+
+    /// Delegates the rewards of `sender` to `recipient`, or back to `sender`
+    /// itself when `None` is passed.
+    fn set_reward_recipient(env: Env, sender: Address, recipient: Option<Address>);
+
     fn create_distribution_flow(env: Env, sender: Address, asset: Address);
 
+    /// Stops `asset` from taking new rewards. Rewards distributed before the
+    /// removal can still be withdrawn.
+    fn remove_distribution_flow(env: Env, sender: Address, asset: Address);
+
     fn distribute_rewards(env: Env, sender: Address, amount: i128, reward_token: Address);
 
     fn withdraw_rewards(env: Env, sender: Address);
 
+    /// Settles the pending rewards of `sender`, provides the ones paid in the
+    /// pool's own tokens as liquidity to the owning pool and bonds the minted
+    /// LP shares as a fresh stake. Returns the amount of LP shares bonded.
+    /// The owning pool must expose the XYK `provide_liquidity`/`swap` interface.
+    fn compound(env: Env, sender: Address, min_shares_out: i128) -> i128;
+
     // QUERIES
 
     fn query_config(env: Env) -> ConfigResponse;
@@ -53,12 +91,37 @@ pub trait StakingTrait {
 
     fn query_total_staked(env: Env) -> i128;
 
-    // fn query_annualized_rewards(env: Env) -> AnnualizedRewardsResponse;
+    /// Annualised reward per staked LP share for every distribution asset,
+    /// extrapolated from the rewards distributed in the last `lookback` seconds.
+    fn query_annualized_rewards(env: Env, lookback: u64) -> AnnualizedRewardsResponse;
 
     fn query_withdrawable_rewards(env: Env, address: Address) -> WithdrawableRewardsResponse;
 
+    /// Breaks the withdrawable rewards of `address` down by stake, in the
+    /// order of its stakes.
+    fn query_stake_rewards(env: Env, address: Address) -> Vec<StakeRewardsResponse>;
+
+    fn query_reward_recipient(env: Env, address: Address) -> Address;
+
     fn migrate_admin_key(env: Env) -> Result<(), ContractError>;
 
+    fn update_config(
+        env: Env,
+        manager: Option<Address>,
+        min_bond: Option<i128>,
+        min_reward: Option<i128>,
+    ) -> Result<Config, ContractError>;
+
+    fn propose_admin(
+        env: Env,
+        new_admin: Address,
+        time_limit: Option<u64>,
+    ) -> Result<Address, ContractError>;
+
+    fn revoke_admin_change(env: Env) -> Result<(), ContractError>;
+
+    fn accept_admin(env: Env) -> Result<Address, ContractError>;
+
     // fn query_distributed_rewards(env: Env, asset: Address) -> u128;
 
     // fn query_undistributed_rewards(env: Env, asset: Address) -> u128;
@@ -184,7 +247,7 @@ impl StakingTrait for Staking {
         // the contract. The pool's `withdraw_liquidity(auto_unstake = …)`
         // helper calls this entrypoint directly, so the fix flows through
         // there too.
-        settle_user_rewards(&env, &sender, &mut stakes);
+        settle_user_rewards(&env, &get_reward_recipient(&env, &sender), &mut stakes);
 
         remove_stake(&env, &mut stakes.stakes, stake_amount, stake_timestamp);
         stakes.total_stake = stakes
@@ -206,6 +269,149 @@ impl StakingTrait for Staking {
         env.events().publish(("unbond", "amount"), stake_amount);
     }
 
+    fn unbond_amount(env: Env, sender: Address, amount: i128, strategy: UnbondStrategy) {
+        sender.require_auth();
+
+        env.storage()
+            .instance()
+            .extend_ttl(INSTANCE_RENEWAL_THRESHOLD, INSTANCE_TARGET_TTL);
+
+        let config = get_config(&env);
+
+        let mut stakes = get_stakes(&env, &sender);
+
+        if amount <= 0 || amount > stakes.total_stake {
+            log!(
+                &env,
+                "Stake: Unbond amount: Amount must be positive and not exceed the total stake"
+            );
+            panic_with_error!(&env, ContractError::InvalidUnbondAmount);
+        }
+
+        // Same as in `unbond` - rewards have to be settled before the stakes change
+        settle_user_rewards(&env, &get_reward_recipient(&env, &sender), &mut stakes);
+
+        consume_stakes(&env, &mut stakes.stakes, amount, strategy);
+        stakes.total_stake = stakes.total_stake.checked_sub(amount).unwrap_or_else(|| {
+            log!(&env, "Stake: Unbond amount: underflow occured.");
+            panic_with_error!(&env, ContractError::ContractMathError);
+        });
+
+        let lp_token_client = token_contract::Client::new(&env, &config.lp_token);
+        lp_token_client.transfer(&env.current_contract_address(), &sender, &amount);
+
+        save_stakes(&env, &sender, &stakes);
+        utils::decrease_total_staked(&env, &amount);
+
+        env.events().publish(("unbond", "user"), &sender);
+        env.events().publish(("unbond", "token"), &config.lp_token);
+        env.events().publish(("unbond", "amount"), amount);
+    }
+
+    fn transfer_stake(
+        env: Env,
+        from: Address,
+        to: Address,
+        stake_amount: i128,
+        stake_timestamp: u64,
+    ) {
+        from.require_auth();
+
+        env.storage()
+            .instance()
+            .extend_ttl(INSTANCE_RENEWAL_THRESHOLD, INSTANCE_TARGET_TTL);
+
+        if from == to {
+            log!(
+                &env,
+                "Stake: Transfer stake: Cannot transfer a stake to self"
+            );
+            panic_with_error!(&env, ContractError::StakeTransferToSelf);
+        }
+
+        let mut from_stakes = get_stakes(&env, &from);
+        let mut to_stakes = get_stakes(&env, &to);
+
+        // `to` doesn't sign, so it can't be handed more stakes than the reward
+        // settlement is meant to walk through
+        if to_stakes.stakes.len() >= get_config(&env).max_complexity {
+            log!(
+                &env,
+                "Stake: Transfer stake: Receiver has reached the maximum number of stakes"
+            );
+            panic_with_error!(&env, ContractError::StakeLimitReached);
+        }
+
+        // Both sides are settled up to now, otherwise `to` would be paid the
+        // rewards the moved stake accrued before the transfer a second time
+        settle_user_rewards(&env, &get_reward_recipient(&env, &from), &mut from_stakes);
+        settle_user_rewards(&env, &get_reward_recipient(&env, &to), &mut to_stakes);
+
+        remove_stake(&env, &mut from_stakes.stakes, stake_amount, stake_timestamp);
+        let stake = Stake {
+            stake: stake_amount,
+            stake_timestamp,
+        };
+        from_stakes.total_stake = from_stakes
+            .total_stake
+            .checked_sub(stake.stake)
+            .unwrap_or_else(|| {
+                log!(&env, "Stake: Transfer stake: underflow occured.");
+                panic_with_error!(&env, ContractError::ContractMathError);
+            });
+
+        // keep the stakes of the receiver sorted by their timestamp
+        let position = to_stakes
+            .stakes
+            .iter()
+            .position(|s| s.stake_timestamp > stake.stake_timestamp)
+            .map_or(to_stakes.stakes.len(), |position| position as u32);
+        to_stakes.stakes.insert(position, stake.clone());
+        to_stakes.total_stake = to_stakes
+            .total_stake
+            .checked_add(stake.stake)
+            .unwrap_or_else(|| {
+                log!(&env, "Stake: Transfer stake: overflow occured.");
+                panic_with_error!(&env, ContractError::ContractMathError);
+            });
+
+        save_stakes(&env, &from, &from_stakes);
+        save_stakes(&env, &to, &to_stakes);
+
+        env.events().publish(("transfer_stake", "from"), &from);
+        env.events().publish(("transfer_stake", "to"), &to);
+        env.events()
+            .publish(("transfer_stake", "amount"), stake.stake);
+    }
+
+    fn set_reward_recipient(env: Env, sender: Address, recipient: Option<Address>) {
+        sender.require_auth();
+
+        env.storage()
+            .instance()
+            .extend_ttl(INSTANCE_RENEWAL_THRESHOLD, INSTANCE_TARGET_TTL);
+
+        // Rewards accrued so far belong to the current recipient
+        let mut stakes = get_stakes(&env, &sender);
+        settle_user_rewards(&env, &get_reward_recipient(&env, &sender), &mut stakes);
+        save_stakes(&env, &sender, &stakes);
+
+        match recipient {
+            Some(recipient) if recipient != sender => {
+                save_reward_recipient(&env, &sender, &recipient);
+                env.events()
+                    .publish(("set_reward_recipient", "recipient"), &recipient);
+            }
+            _ => {
+                remove_reward_recipient(&env, &sender);
+                env.events()
+                    .publish(("set_reward_recipient", "recipient"), &sender);
+            }
+        }
+        env.events()
+            .publish(("set_reward_recipient", "user"), &sender);
+    }
+
     fn create_distribution_flow(env: Env, sender: Address, asset: Address) {
         sender.require_auth();
         env.storage()
@@ -218,13 +424,35 @@ impl StakingTrait for Staking {
             panic_with_error!(&env, ContractError::Unauthorized);
         }
 
-        add_distribution(&env, &asset);
-        save_reward_history(&env, &asset, map![&env]);
+        // A removed flow keeps its reward history, so users can still claim
+        // what was distributed before the removal
+        if !restore_distribution(&env, &asset) {
+            add_distribution(&env, &asset);
+            save_reward_history(&env, &asset, map![&env]);
+        }
 
         env.events()
             .publish(("create_distribution_flow", "asset"), &asset);
     }
 
+    fn remove_distribution_flow(env: Env, sender: Address, asset: Address) {
+        sender.require_auth();
+        env.storage()
+            .instance()
+            .extend_ttl(INSTANCE_RENEWAL_THRESHOLD, INSTANCE_TARGET_TTL);
+
+        let config = get_config(&env);
+        if sender != config.manager && sender != config.owner {
+            log!(env, "Stake: remove distribution: Non-authorized removal!");
+            panic_with_error!(&env, ContractError::Unauthorized);
+        }
+
+        remove_distribution(&env, &asset);
+
+        env.events()
+            .publish(("remove_distribution_flow", "asset"), &asset);
+    }
+
     fn distribute_rewards(env: Env, sender: Address, amount: i128, reward_token: Address) {
         sender.require_auth();
 
@@ -275,10 +503,132 @@ impl StakingTrait for Staking {
         env.events().publish(("withdraw_rewards", "user"), &sender);
 
         let mut stakes = get_stakes(&env, &sender);
-        settle_user_rewards(&env, &sender, &mut stakes);
+        settle_user_rewards(&env, &get_reward_recipient(&env, &sender), &mut stakes);
         save_stakes(&env, &sender, &stakes);
     }
 
+    fn compound(env: Env, sender: Address, min_shares_out: i128) -> i128 {
+        sender.require_auth();
+        env.storage()
+            .instance()
+            .extend_ttl(INSTANCE_RENEWAL_THRESHOLD, INSTANCE_TARGET_TTL);
+
+        let config = get_config(&env);
+        let pool = find_owning_pool(&env, &config);
+        let pool_info = query_pool_info(&env, &pool);
+        let token_a = pool_info.asset_a.address;
+        let token_b = pool_info.asset_b.address;
+
+        // Rewards in the pool tokens are reinvested into the position, so they
+        // are settled to the staker even if a reward recipient is delegated.
+        // Any other reward goes to the recipient as on a regular withdrawal.
+        let reward_recipient = get_reward_recipient(&env, &sender);
+        let mut stakes = get_stakes(&env, &sender);
+        let paid_rewards = settle_user_rewards_with(&env, &mut stakes, |asset| {
+            if *asset == token_a || *asset == token_b {
+                sender.clone()
+            } else {
+                reward_recipient.clone()
+            }
+        });
+
+        let mut reward_a = paid_rewards.get(token_a.clone()).unwrap_or(0);
+        let mut reward_b = paid_rewards.get(token_b.clone()).unwrap_or(0);
+
+        // Rewards paid in only one of the pool tokens get half of them swapped
+        // into the other one, so that both sides of the deposit are non-zero
+        match (reward_a > 0, reward_b > 0) {
+            (true, true) => {}
+            (true, false) => {
+                let offer = reward_a / 2;
+                ensure_swap_returns(&env, &pool, &token_a, offer);
+                reward_b = swap_in_pool(&env, &pool, &sender, &token_a, offer);
+                reward_a -= offer;
+            }
+            (false, true) => {
+                let offer = reward_b / 2;
+                ensure_swap_returns(&env, &pool, &token_b, offer);
+                reward_a = swap_in_pool(&env, &pool, &sender, &token_b, offer);
+                reward_b -= offer;
+            }
+            (false, false) => nothing_to_compound(&env),
+        }
+
+        // Reserves may have moved after the swap above, re-read them before
+        // sizing the deposit
+        let pool_info = query_pool_info(&env, &pool);
+        let (deposit_a, deposit_b) = match_pool_ratio(
+            &env,
+            reward_a,
+            reward_b,
+            pool_info.asset_a.amount,
+            pool_info.asset_b.amount,
+        );
+
+        let lp_token_client = token_contract::Client::new(&env, &config.lp_token);
+        let lp_balance_before = lp_token_client.balance(&sender);
+        env.invoke_contract::<()>(
+            &pool,
+            &Symbol::new(&env, "provide_liquidity"),
+            vec![
+                &env,
+                sender.into_val(&env),
+                Some(deposit_a).into_val(&env),
+                None::<i128>.into_val(&env),
+                Some(deposit_b).into_val(&env),
+                None::<i128>.into_val(&env),
+                None::<i64>.into_val(&env),
+                None::<u64>.into_val(&env),
+                false.into_val(&env),
+            ],
+        );
+        let shares = lp_token_client
+            .balance(&sender)
+            .checked_sub(lp_balance_before)
+            .unwrap_or_else(|| {
+                log!(&env, "Stake: Compound: underflow occured.");
+                panic_with_error!(&env, ContractError::ContractMathError);
+            });
+
+        if shares < min_shares_out {
+            log!(
+                &env,
+                "Stake: Compound: Received {} LP shares, less than the requested minimum of {}",
+                shares,
+                min_shares_out
+            );
+            panic_with_error!(&env, ContractError::CompoundSharesBelowMinimum);
+        }
+        if shares < config.min_bond {
+            log!(
+                &env,
+                "Stake: Compound: Trying to stake less than minimum required"
+            );
+            panic_with_error!(&env, ContractError::InvalidBond);
+        }
+
+        lp_token_client.transfer(&sender, &env.current_contract_address(), &shares);
+
+        stakes.total_stake = stakes.total_stake.checked_add(shares).unwrap_or_else(|| {
+            log!(&env, "Stake: Compound: overflow occured.");
+            panic_with_error!(&env, ContractError::ContractMathError);
+        });
+        stakes.stakes.push_back(Stake {
+            stake: shares,
+            stake_timestamp: env.ledger().timestamp(),
+        });
+
+        save_stakes(&env, &sender, &stakes);
+        utils::increase_total_staked(&env, &shares);
+
+        env.events().publish(("compound", "user"), &sender);
+        env.events()
+            .publish(("compound", "token"), &config.lp_token);
+        env.events().publish(("compound", "amount"), shares);
+
+        shares
+    }
+
     // QUERIES
 
     fn query_config(env: Env) -> ConfigResponse {
@@ -316,26 +666,30 @@ impl StakingTrait for Staking {
         get_total_staked_counter(&env)
     }
 
-    // fn query_annualized_rewards(env: Env) -> AnnualizedRewardsResponse {
-    //     let mut aprs = vec![&env];
-    //     let total_stake_amount = get_total_staked_counter(&env);
-    //     let apr_fn_arg: Val = total_stake_amount.into_val(&env);
+    fn query_annualized_rewards(env: Env, lookback: u64) -> AnnualizedRewardsResponse {
+        env.storage()
+            .instance()
+            .extend_ttl(INSTANCE_RENEWAL_THRESHOLD, INSTANCE_TARGET_TTL);
+        if lookback == 0 {
+            log!(
+                &env,
+                "Stake: Query annualized rewards: lookback window must be positive"
+            );
+            panic_with_error!(&env, ContractError::InvalidLookbackWindow);
+        }
 
-    //     for asset in get_distributions(&env) {
-    //         let apr: AnnualizedReward = env.invoke_contract(
-    //             &distribution_address,
-    //             &Symbol::new(&env, "query_annualized_reward"),
-    //             vec![&env, apr_fn_arg],
-    //         );
+        let mut rewards = vec![&env];
+        for asset in get_distributions(&env) {
+            let apr = calculate_annualized_reward(&env, &asset, lookback);
 
-    //         aprs.push_back(AnnualizedReward {
-    //             asset,
-    //             amount: apr.amount,
-    //         });
-    //     }
+            rewards.push_back(AnnualizedReward {
+                asset,
+                amount: apr.to_string(&env),
+            });
+        }
 
-    //     AnnualizedRewardsResponse { rewards: aprs }
-    // }
+        AnnualizedRewardsResponse { rewards }
+    }
 
     fn query_withdrawable_rewards(env: Env, user: Address) -> WithdrawableRewardsResponse {
         env.storage()
@@ -344,7 +698,7 @@ impl StakingTrait for Staking {
         let stakes = get_stakes(&env, &user);
         // iterate over all distributions and calculate withdrawable rewards
         let mut rewards = vec![&env];
-        for asset in get_distributions(&env) {
+        for asset in get_claimable_distributions(&env) {
             let pending_reward = calculate_pending_rewards(&env, &asset, &stakes);
 
             rewards.push_back(WithdrawableReward {
@@ -355,6 +709,48 @@ impl StakingTrait for Staking {
 
         WithdrawableRewardsResponse { rewards }
     }
+
+    fn query_stake_rewards(env: Env, address: Address) -> Vec<StakeRewardsResponse> {
+        env.storage()
+            .instance()
+            .extend_ttl(INSTANCE_RENEWAL_THRESHOLD, INSTANCE_TARGET_TTL);
+        let stakes = get_stakes(&env, &address);
+        let distributions = get_claimable_distributions(&env);
+
+        let mut response = vec![&env];
+        for stake in stakes.stakes.iter() {
+            // pending rewards are the sum of what every single stake earned
+            // since the last settlement
+            let single_stake = BondingInfo {
+                stakes: vec![&env, stake.clone()],
+                total_stake: stake.stake,
+                ..stakes.clone()
+            };
+            let mut rewards = vec![&env];
+            for asset in distributions.iter() {
+                let pending_reward = calculate_pending_rewards(&env, &asset, &single_stake);
+                rewards.push_back(WithdrawableReward {
+                    reward_address: asset,
+                    reward_amount: pending_reward as u128,
+                });
+            }
+            response.push_back(StakeRewardsResponse {
+                stake: stake.stake,
+                stake_timestamp: stake.stake_timestamp,
+                rewards,
+            });
+        }
+
+        response
+    }
+
+    fn query_reward_recipient(env: Env, address: Address) -> Address {
+        env.storage()
+            .instance()
+            .extend_ttl(INSTANCE_RENEWAL_THRESHOLD, INSTANCE_TARGET_TTL);
+        get_reward_recipient(&env, &address)
+    }
+
     fn migrate_admin_key(env: Env) -> Result<(), ContractError> {
         let admin = get_admin_old(&env);
         env.storage().instance().set(&ADMIN, &admin);
@@ -362,6 +758,128 @@ impl StakingTrait for Staking {
         Ok(())
     }
 
+    fn update_config(
+        env: Env,
+        manager: Option<Address>,
+        min_bond: Option<i128>,
+        min_reward: Option<i128>,
+    ) -> Result<Config, ContractError> {
+        let admin = get_admin_old(&env);
+        admin.require_auth();
+        env.storage()
+            .instance()
+            .extend_ttl(INSTANCE_RENEWAL_THRESHOLD, INSTANCE_TARGET_TTL);
+
+        let mut config = get_config(&env);
+
+        if let Some(manager) = manager {
+            config.manager = manager;
+        }
+        if let Some(min_bond) = min_bond {
+            if min_bond <= 0 {
+                log!(
+                    &env,
+                    "Stake: Update Config: Minimum amount of lp share tokens to bond can not be smaller or equal to 0"
+                );
+                panic_with_error!(&env, ContractError::InvalidMinBond);
+            }
+            config.min_bond = min_bond;
+        }
+        if let Some(min_reward) = min_reward {
+            if min_reward <= 0 {
+                log!(
+                    &env,
+                    "Stake: Update Config: min_reward must be bigger than 0!"
+                );
+                panic_with_error!(&env, ContractError::InvalidMinReward);
+            }
+            config.min_reward = min_reward;
+        }
+
+        save_config(&env, config.clone());
+
+        env.events().publish(("Stake", "Updated Config"), ());
+
+        Ok(config)
+    }
+
+    fn propose_admin(
+        env: Env,
+        new_admin: Address,
+        time_limit: Option<u64>,
+    ) -> Result<Address, ContractError> {
+        let current_admin = get_admin_old(&env);
+        current_admin.require_auth();
+
+        if current_admin == new_admin {
+            log!(&env, "Trying to set new admin as new");
+            panic_with_error!(&env, ContractError::SameAdmin);
+        }
+
+        env.storage().instance().set(
+            &PENDING_ADMIN,
+            &AdminChange {
+                new_admin: new_admin.clone(),
+                time_limit,
+            },
+        );
+
+        env.events().publish(
+            ("Stake: ", "Admin replacement requested by old admin: "),
+            &current_admin,
+        );
+        env.events()
+            .publish(("Stake: ", "Replace with new admin: "), &new_admin);
+
+        Ok(new_admin)
+    }
+
+    fn revoke_admin_change(env: Env) -> Result<(), ContractError> {
+        let current_admin = get_admin_old(&env);
+        current_admin.require_auth();
+
+        if !env.storage().instance().has(&PENDING_ADMIN) {
+            log!(&env, "No admin change in place");
+            panic_with_error!(&env, ContractError::NoAdminChangeInPlace);
+        }
+
+        env.storage().instance().remove(&PENDING_ADMIN);
+
+        env.events().publish(("Stake: ", "Undo admin change: "), ());
+
+        Ok(())
+    }
+
+    fn accept_admin(env: Env) -> Result<Address, ContractError> {
+        let admin_change_info: AdminChange = env
+            .storage()
+            .instance()
+            .get(&PENDING_ADMIN)
+            .unwrap_or_else(|| {
+                log!(&env, "No admin change request is in place");
+                panic_with_error!(&env, ContractError::NoAdminChangeInPlace);
+            });
+
+        let pending_admin = admin_change_info.new_admin;
+        pending_admin.require_auth();
+
+        if let Some(time_limit) = admin_change_info.time_limit {
+            if env.ledger().timestamp() > time_limit {
+                log!(&env, "Admin change expired");
+                panic_with_error!(&env, ContractError::AdminChangeExpired);
+            }
+        }
+
+        env.storage().instance().remove(&PENDING_ADMIN);
+
+        utils::save_admin_old(&env, &pending_admin);
+
+        env.events()
+            .publish(("Stake: ", "Accepted new admin: "), &pending_admin);
+
+        Ok(pending_admin)
+    }
+
     // fn query_distributed_rewards(env: Env, asset: Address) -> u128 {
     //     let staking_rewards = find_stake_rewards_by_asset(&env, &asset).unwrap();
     //     let unds_rew_fn_arg: Val = asset.into_val(&env);
@@ -389,10 +907,16 @@ impl StakingTrait for Staking {
 impl Staking {
     #[allow(dead_code)]
     pub fn update(env: Env, new_wasm_hash: BytesN<32>) {
-        let admin = get_admin_old(&env);
-        admin.require_auth();
+        // the owner is the factory that deployed the pool of this stake
+        let owner = get_config(&env).owner;
+        owner.require_auth();
         env.deployer().update_current_contract_wasm(new_wasm_hash);
     }
+
+    #[allow(dead_code)]
+    pub fn query_version(env: Env) -> String {
+        String::from_str(&env, env!("CARGO_PKG_VERSION"))
+    }
 }
 
 // Function to remove a stake from the vector
@@ -411,7 +935,34 @@ fn remove_stake(env: &Env, stakes: &mut Vec<Stake>, stake: i128, stake_timestamp
     }
 }
 
-/// Pay out all pending rewards for `sender` and roll `last_reward_time`
+/// Take `amount` out of the stakes, walking them in the order of `strategy`.
+/// Fully consumed stakes are removed, the last one touched is reduced in place
+/// and keeps its original timestamp.
+fn consume_stakes(env: &Env, stakes: &mut Vec<Stake>, amount: i128, strategy: UnbondStrategy) {
+    let mut remaining = amount;
+    while remaining > 0 {
+        let index = match strategy {
+            UnbondStrategy::NewestFirst => stakes.len().checked_sub(1),
+            UnbondStrategy::OldestFirst => (!stakes.is_empty()).then_some(0),
+        }
+        .unwrap_or_else(|| {
+            log!(&env, "Stake: Consume stakes: Not enough stakes to unbond");
+            panic_with_error!(&env, ContractError::InvalidUnbondAmount);
+        });
+
+        let mut stake = stakes.get_unchecked(index);
+        if stake.stake <= remaining {
+            remaining -= stake.stake;
+            stakes.remove(index);
+        } else {
+            stake.stake -= remaining;
+            remaining = 0;
+            stakes.set(index, stake);
+        }
+    }
+}
+
+/// Pay out all pending rewards of `stakes` to `recipient` and roll `last_reward_time`
 /// forward to the current ledger timestamp. Mutates `stakes` in place but
 /// does NOT persist — caller is responsible for `save_stakes` after any
 /// further mutations (e.g. removing a stake entry in `unbond`).
@@ -419,19 +970,156 @@ fn remove_stake(env: &Env, stakes: &mut Vec<Stake>, stake: i128, stake_timestamp
 /// MUST be called BEFORE any mutation to `stakes.stakes`, because
 /// `calculate_pending_rewards` iterates that vector and unattributes any
 /// removed entry's accrued share otherwise.
-fn settle_user_rewards(env: &Env, sender: &Address, stakes: &mut BondingInfo) {
-    for asset in get_distributions(env) {
+///
+/// Returns the amount paid out for each distribution asset.
+fn settle_user_rewards(
+    env: &Env,
+    recipient: &Address,
+    stakes: &mut BondingInfo,
+) -> Map<Address, i128> {
+    settle_user_rewards_with(env, stakes, |_| recipient.clone())
+}
+
+/// Like `settle_user_rewards`, paying the rewards of each asset to the
+/// address `recipient_of` picks for it.
+fn settle_user_rewards_with(
+    env: &Env,
+    stakes: &mut BondingInfo,
+    recipient_of: impl Fn(&Address) -> Address,
+) -> Map<Address, i128> {
+    let mut paid_rewards = Map::new(env);
+    for asset in get_claimable_distributions(env) {
         let pending_reward = calculate_pending_rewards(env, &asset, stakes);
         env.events()
             .publish(("withdraw_rewards", "reward_token"), &asset);
 
         token_contract::Client::new(env, &asset).transfer(
             &env.current_contract_address(),
-            sender,
+            &recipient_of(&asset),
             &pending_reward,
         );
+        paid_rewards.set(asset, pending_reward);
     }
     stakes.last_reward_time = env.ledger().timestamp();
+
+    paid_rewards
+}
+
+/// Returns the pool whose LP shares are staked in this contract. The stake
+/// contract is deployed by the pool, so the pool address is only known to the
+/// factory (`Config.owner`) - it's looked up there once and cached.
+fn find_owning_pool(env: &Env, config: &Config) -> Address {
+    if let Some(pool) = get_pool(env) {
+        return pool;
+    }
+
+    let pool: Option<Address> = env.invoke_contract(
+        &config.owner,
+        &Symbol::new(env, "query_pool_by_stake_contract"),
+        vec![env, env.current_contract_address().into_val(env)],
+    );
+    let pool = pool.unwrap_or_else(|| {
+        log!(
+            env,
+            "Stake: Find owning pool: Pool not found in the factory"
+        );
+        panic_with_error!(env, ContractError::PoolNotFound);
+    });
+    save_pool(env, &pool);
+
+    pool
+}
+
+fn nothing_to_compound(env: &Env) -> ! {
+    log!(
+        env,
+        "Stake: Compound: No pending rewards in the pool tokens to compound"
+    );
+    panic_with_error!(env, ContractError::NothingToCompound);
+}
+
+/// Rewards paid in a single pool token need half of them swapped; too small
+/// a half would buy nothing and make the pool reject the swap.
+fn ensure_swap_returns(env: &Env, pool: &Address, offer_asset: &Address, offer_amount: i128) {
+    let returns_something = offer_amount > 0 && {
+        let simulation: SimulateSwapResponse = env.invoke_contract(
+            pool,
+            &Symbol::new(env, "simulate_swap"),
+            vec![env, offer_asset.into_val(env), offer_amount.into_val(env)],
+        );
+        simulation.ask_amount > 0
+    };
+    if !returns_something {
+        log!(
+            env,
+            "Stake: Compound: Swapping half of the reward would return nothing"
+        );
+        panic_with_error!(env, ContractError::NothingToCompound);
+    }
+}
+
+fn query_pool_info(env: &Env, pool: &Address) -> PoolResponse {
+    env.invoke_contract(pool, &Symbol::new(env, "query_pool_info"), Vec::new(env))
+}
+
+/// Swaps `offer_amount` of `offer_asset` on behalf of `sender` and returns the
+/// amount of the other pool token received.
+fn swap_in_pool(
+    env: &Env,
+    pool: &Address,
+    sender: &Address,
+    offer_asset: &Address,
+    offer_amount: i128,
+) -> i128 {
+    env.invoke_contract(
+        pool,
+        &Symbol::new(env, "swap"),
+        vec![
+            env,
+            sender.into_val(env),
+            offer_asset.into_val(env),
+            offer_amount.into_val(env),
+            None::<i128>.into_val(env),
+            None::<i64>.into_val(env),
+            None::<u64>.into_val(env),
+            None::<i64>.into_val(env),
+        ],
+    )
+}
+
+/// Scales the larger side of `(amount_a, amount_b)` down so the deposit
+/// follows the current pool ratio and none of it is rejected as slippage.
+fn match_pool_ratio(
+    env: &Env,
+    amount_a: i128,
+    amount_b: i128,
+    reserve_a: i128,
+    reserve_b: i128,
+) -> (i128, i128) {
+    if reserve_a == 0 || reserve_b == 0 {
+        return (amount_a, amount_b);
+    }
+
+    let required_b = amount_a
+        .checked_mul(reserve_b)
+        .and_then(|product| product.checked_div(reserve_a))
+        .unwrap_or_else(|| {
+            log!(env, "Stake: Match pool ratio: overflow occured.");
+            panic_with_error!(env, ContractError::ContractMathError);
+        });
+    if required_b <= amount_b {
+        return (amount_a, required_b);
+    }
+
+    let required_a = amount_b
+        .checked_mul(reserve_a)
+        .and_then(|product| product.checked_div(reserve_b))
+        .unwrap_or_else(|| {
+            log!(env, "Stake: Match pool ratio: overflow occured.");
+            panic_with_error!(env, ContractError::ContractMathError);
+        });
+
+    (required_a, amount_b)
 }
 
 #[cfg(test)]
@@ -554,4 +1242,103 @@ mod tests {
 
         remove_stake(&env, &mut stakes, 150, 1);
     }
+
+    #[test]
+    fn match_pool_ratio_scales_down_the_larger_side() {
+        let env = Env::default();
+
+        assert_eq!(match_pool_ratio(&env, 1_000, 1_000, 100, 200), (500, 1_000));
+        assert_eq!(match_pool_ratio(&env, 1_000, 1_000, 200, 100), (1_000, 500));
+        assert_eq!(match_pool_ratio(&env, 300, 600, 100, 200), (300, 600));
+    }
+
+    #[test]
+    fn match_pool_ratio_keeps_amounts_for_empty_pool() {
+        let env = Env::default();
+
+        assert_eq!(match_pool_ratio(&env, 1_000, 10, 0, 0), (1_000, 10));
+    }
+
+    fn three_stakes(env: &Env) -> Vec<Stake> {
+        vec![
+            env,
+            Stake {
+                stake: 100,
+                stake_timestamp: 1,
+            },
+            Stake {
+                stake: 200,
+                stake_timestamp: 2,
+            },
+            Stake {
+                stake: 150,
+                stake_timestamp: 3,
+            },
+        ]
+    }
+
+    #[test]
+    fn consume_stakes_newest_first_splits_last_touched_stake() {
+        let env = Env::default();
+        let mut stakes = three_stakes(&env);
+
+        consume_stakes(&env, &mut stakes, 250, UnbondStrategy::NewestFirst);
+
+        assert_eq!(
+            stakes,
+            vec![
+                &env,
+                Stake {
+                    stake: 100,
+                    stake_timestamp: 1
+                },
+                Stake {
+                    stake: 100,
+                    stake_timestamp: 2
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn consume_stakes_oldest_first_splits_last_touched_stake() {
+        let env = Env::default();
+        let mut stakes = three_stakes(&env);
+
+        consume_stakes(&env, &mut stakes, 250, UnbondStrategy::OldestFirst);
+
+        assert_eq!(
+            stakes,
+            vec![
+                &env,
+                Stake {
+                    stake: 50,
+                    stake_timestamp: 2
+                },
+                Stake {
+                    stake: 150,
+                    stake_timestamp: 3
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn consume_stakes_exact_total_empties_the_vector() {
+        let env = Env::default();
+        let mut stakes = three_stakes(&env);
+
+        consume_stakes(&env, &mut stakes, 450, UnbondStrategy::NewestFirst);
+
+        assert!(stakes.is_empty());
+    }
+
+    #[test]
+    #[should_panic(expected = "Error(Contract, #524)")]
+    fn consume_stakes_more_than_staked() {
+        let env = Env::default();
+        let mut stakes = three_stakes(&env);
+
+        consume_stakes(&env, &mut stakes, 451, UnbondStrategy::OldestFirst);
+    }
 }
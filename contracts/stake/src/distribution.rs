@@ -5,6 +5,7 @@ use crate::{error::ContractError, storage::BondingInfo};
 use phoenix::ttl::{PERSISTENT_RENEWAL_THRESHOLD, PERSISTENT_TARGET_TTL};
 
 const SECONDS_PER_DAY: u64 = 24 * 60 * 60;
+const SECONDS_PER_YEAR: u64 = 365 * SECONDS_PER_DAY;
 
 #[derive(Clone)]
 #[contracttype]
@@ -134,3 +135,45 @@ pub fn calculate_pending_rewards(
 
     pending_rewards
 }
+
+/// Annualised reward per staked LP share for `reward_token`, based on the
+/// distributions made within the last `lookback` seconds.
+pub fn calculate_annualized_reward(env: &Env, reward_token: &Address, lookback: u64) -> Decimal {
+    let current_timestamp = env.ledger().timestamp();
+    let window_start = current_timestamp.saturating_sub(lookback);
+
+    let reward_history = get_reward_history(env, reward_token);
+    let total_staked_history = get_total_staked_history(env);
+
+    let mut reward_per_share = Decimal::zero();
+    for (distribution_day, reward) in reward_history
+        .iter()
+        .skip_while(|&(day, _)| day <= window_start)
+        .take_while(|&(day, _)| day <= current_timestamp)
+    {
+        let total_staked = total_staked_history.get(distribution_day).unwrap_or(0);
+        if total_staked == 0 {
+            continue;
+        }
+        let (Ok(reward), Ok(total_staked)) = (i128::try_from(reward), i128::try_from(total_staked))
+        else {
+            log!(
+                &env,
+                "Stake: Annualized reward: Math error in reward per share"
+            );
+            panic_with_error!(&env, ContractError::ContractMathError);
+        };
+        reward_per_share = reward_per_share + Decimal::from_ratio(reward, total_staked);
+    }
+
+    let annualized = reward_per_share
+        .atomics()
+        .checked_mul(SECONDS_PER_YEAR as i128)
+        .unwrap_or_else(|| {
+            log!(&env, "Stake: Annualized reward: overflow occured");
+            panic_with_error!(&env, ContractError::ContractMathError);
+        })
+        / lookback as i128;
+
+    Decimal::raw(annualized)
+}
@@ -23,4 +23,11 @@ pub enum ContractError {
     SameAdmin = 518,
     NoAdminChangeInPlace = 519,
     AdminChangeExpired = 520,
+    PoolNotFound = 521,
+    NothingToCompound = 522,
+    CompoundSharesBelowMinimum = 523,
+    InvalidUnbondAmount = 524,
+    StakeTransferToSelf = 525,
+    InvalidLookbackWindow = 526,
+    StakeLimitReached = 527,
 }
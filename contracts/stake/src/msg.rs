@@ -41,3 +41,12 @@ pub struct WithdrawableRewardsResponse {
     /// Amount of rewards assigned for withdrawal from the given address.
     pub rewards: Vec<WithdrawableReward>,
 }
+
+/// Pending rewards of a single stake
+#[contracttype]
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct StakeRewardsResponse {
+    pub stake: i128,
+    pub stake_timestamp: u64,
+    pub rewards: Vec<WithdrawableReward>,
+}
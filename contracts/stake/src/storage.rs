@@ -2,6 +2,7 @@ use phoenix::ttl::{PERSISTENT_RENEWAL_THRESHOLD, PERSISTENT_TARGET_TTL};
 use soroban_sdk::{contracttype, symbol_short, Address, Env, Symbol, Vec};
 
 pub const ADMIN: Symbol = symbol_short!("ADMIN");
+pub(crate) const PENDING_ADMIN: Symbol = symbol_short!("p_admin");
 
 #[contracttype]
 #[derive(Clone, Debug, Eq, PartialEq)]
@@ -42,6 +43,66 @@ pub fn save_config(env: &Env, config: Config) {
     );
 }
 
+const POOL: Symbol = symbol_short!("POOL");
+
+/// Address of the liquidity pool whose LP shares are staked here. Not known at
+/// construction time - it is looked up through the factory on first use and
+/// cached afterwards.
+pub fn get_pool(env: &Env) -> Option<Address> {
+    let pool = env.storage().persistent().get(&POOL);
+    env.storage().persistent().has(&POOL).then(|| {
+        env.storage().persistent().extend_ttl(
+            &POOL,
+            PERSISTENT_RENEWAL_THRESHOLD,
+            PERSISTENT_TARGET_TTL,
+        );
+    });
+
+    pool
+}
+
+pub fn save_pool(env: &Env, pool: &Address) {
+    env.storage().persistent().set(&POOL, pool);
+    env.storage().persistent().extend_ttl(
+        &POOL,
+        PERSISTENT_RENEWAL_THRESHOLD,
+        PERSISTENT_TARGET_TTL,
+    );
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Asset {
+    /// Address of the asset
+    pub address: Address,
+    /// The total amount of those tokens in the pool
+    pub amount: i128,
+}
+
+/// Mirror of the pool's `PoolResponse`, used to decode `query_pool_info`.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PoolResponse {
+    /// The asset A in the pool together with asset amounts
+    pub asset_a: Asset,
+    /// The asset B in the pool together with asset amounts
+    pub asset_b: Asset,
+    /// The total amount of LP tokens currently issued
+    pub asset_lp_share: Asset,
+    /// The address of the Stake contract for the liquidity pool
+    pub stake_address: Address,
+}
+
+/// Mirror of the pool's `SimulateSwapResponse`, used to decode `simulate_swap`.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SimulateSwapResponse {
+    pub ask_amount: i128,
+    pub commission_amount: i128,
+    pub spread_amount: i128,
+    pub total_return: i128,
+}
+
 #[contracttype]
 #[derive(Clone, Debug, Eq, PartialEq, Default)]
 pub struct Stake {
@@ -51,6 +112,17 @@ pub struct Stake {
     pub stake_timestamp: u64,
 }
 
+/// Order in which `unbond_amount` consumes the stakes of a user
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum UnbondStrategy {
+    /// Start with the most recent stakes, keeping the aged ones and their
+    /// reward multiplier intact
+    NewestFirst,
+    /// Start with the oldest stakes
+    OldestFirst,
+}
+
 #[contracttype]
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub struct BondingInfo {
@@ -97,6 +169,45 @@ pub fn save_stakes(env: &Env, key: &Address, bonding_info: &BondingInfo) {
     );
 }
 
+#[derive(Clone)]
+#[contracttype]
+pub enum DelegationDataKey {
+    RewardRecipient(Address),
+}
+
+/// Address receiving the rewards of `staker` - the staker itself unless a
+/// different recipient was delegated.
+pub fn get_reward_recipient(env: &Env, staker: &Address) -> Address {
+    let key = DelegationDataKey::RewardRecipient(staker.clone());
+    match env.storage().persistent().get(&key) {
+        Some(recipient) => {
+            env.storage().persistent().extend_ttl(
+                &key,
+                PERSISTENT_RENEWAL_THRESHOLD,
+                PERSISTENT_TARGET_TTL,
+            );
+            recipient
+        }
+        None => staker.clone(),
+    }
+}
+
+pub fn save_reward_recipient(env: &Env, staker: &Address, recipient: &Address) {
+    let key = DelegationDataKey::RewardRecipient(staker.clone());
+    env.storage().persistent().set(&key, recipient);
+    env.storage().persistent().extend_ttl(
+        &key,
+        PERSISTENT_RENEWAL_THRESHOLD,
+        PERSISTENT_TARGET_TTL,
+    );
+}
+
+pub fn remove_reward_recipient(env: &Env, staker: &Address) {
+    env.storage()
+        .persistent()
+        .remove(&DelegationDataKey::RewardRecipient(staker.clone()));
+}
+
 pub mod utils {
     use crate::error::ContractError;
 
@@ -113,6 +224,7 @@ pub mod utils {
         Distributions = 2,
         Initialized = 3,
         StakeRewards = 4,
+        RemovedDistributions = 5,
     }
 
     impl TryFromVal<Env, DataKey> for Val {
@@ -241,9 +353,13 @@ pub mod utils {
             panic_with_error!(&e, ContractError::DistributionExists);
         }
         distributions.push_back(asset.clone());
+        save_distributions(e, &distributions);
+    }
+
+    fn save_distributions(e: &Env, distributions: &Vec<Address>) {
         e.storage()
             .persistent()
-            .set(&DataKey::Distributions, &distributions);
+            .set(&DataKey::Distributions, distributions);
         e.storage().persistent().extend_ttl(
             &DataKey::Distributions,
             PERSISTENT_RENEWAL_THRESHOLD,
@@ -251,6 +367,77 @@ pub mod utils {
         );
     }
 
+    /// Moves `asset` out of the active distributions. A removed distribution
+    /// takes no new rewards, but what was distributed before stays claimable.
+    pub fn remove_distribution(e: &Env, asset: &Address) {
+        let mut distributions = get_distributions(e);
+        let index = distributions.first_index_of(asset).unwrap_or_else(|| {
+            log!(&e, "Stake: Remove distribution: Distribution not found");
+            panic_with_error!(&e, ContractError::DistributionNotFound);
+        });
+        distributions.remove(index);
+        save_distributions(e, &distributions);
+
+        let mut removed = get_removed_distributions(e);
+        if !removed.contains(asset) {
+            removed.push_back(asset.clone());
+        }
+        save_removed_distributions(e, &removed);
+    }
+
+    /// Brings a removed distribution back, returns whether `asset` was one.
+    pub fn restore_distribution(e: &Env, asset: &Address) -> bool {
+        let mut removed = get_removed_distributions(e);
+        match removed.first_index_of(asset) {
+            Some(index) => {
+                removed.remove(index);
+                save_removed_distributions(e, &removed);
+                add_distribution(e, asset);
+                true
+            }
+            None => false,
+        }
+    }
+
+    pub fn get_removed_distributions(e: &Env) -> Vec<Address> {
+        let removed = e
+            .storage()
+            .persistent()
+            .get(&DataKey::RemovedDistributions)
+            .unwrap_or_else(|| soroban_sdk::vec![e]);
+        e.storage()
+            .persistent()
+            .has(&DataKey::RemovedDistributions)
+            .then(|| {
+                e.storage().persistent().extend_ttl(
+                    &DataKey::RemovedDistributions,
+                    PERSISTENT_RENEWAL_THRESHOLD,
+                    PERSISTENT_TARGET_TTL,
+                )
+            });
+
+        removed
+    }
+
+    fn save_removed_distributions(e: &Env, removed: &Vec<Address>) {
+        e.storage()
+            .persistent()
+            .set(&DataKey::RemovedDistributions, removed);
+        e.storage().persistent().extend_ttl(
+            &DataKey::RemovedDistributions,
+            PERSISTENT_RENEWAL_THRESHOLD,
+            PERSISTENT_TARGET_TTL,
+        );
+    }
+
+    /// Active and removed distributions - everything users may have rewards in
+    pub fn get_claimable_distributions(e: &Env) -> Vec<Address> {
+        let mut distributions = get_distributions(e);
+        distributions.append(&get_removed_distributions(e));
+
+        distributions
+    }
+
     pub fn get_distributions(e: &Env) -> Vec<Address> {
         let distributions = e
             .storage()
@@ -1,4 +1,9 @@
+mod admin_change;
+mod annualized_rewards;
 mod bond;
+mod config;
 mod distribution;
 mod setup;
+mod transfer_stake;
+mod unbond_amount;
 mod unbond_settles_rewards;
@@ -0,0 +1,170 @@
+extern crate std;
+
+use pretty_assertions::assert_eq;
+use soroban_sdk::{
+    testutils::{Address as _, Ledger},
+    vec, Address, Env, String,
+};
+
+use super::setup::{deploy_staking_contract, deploy_token_contract, ONE_DAY, ONE_WEEK, SIXTY_DAYS};
+use crate::msg::{AnnualizedReward, AnnualizedRewardsResponse};
+
+const DEFAULT_COMPLEXITY: u32 = 7;
+
+#[test]
+fn annualized_rewards_over_lookback_window() {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.cost_estimate().budget().reset_unlimited();
+
+    let admin = Address::generate(&env);
+    let user = Address::generate(&env);
+    let lp_token = deploy_token_contract(&env, &admin);
+    let reward_token = deploy_token_contract(&env, &admin);
+    let staking = deploy_staking_contract(
+        &env,
+        admin.clone(),
+        &lp_token.address,
+        &Address::generate(&env),
+        &admin,
+        &DEFAULT_COMPLEXITY,
+    );
+
+    staking.create_distribution_flow(&admin, &reward_token.address);
+
+    // nothing distributed yet
+    assert_eq!(
+        staking.query_annualized_rewards(&ONE_WEEK),
+        AnnualizedRewardsResponse {
+            rewards: vec![
+                &env,
+                AnnualizedReward {
+                    asset: reward_token.address.clone(),
+                    amount: String::from_str(&env, "0")
+                }
+            ]
+        }
+    );
+
+    lp_token.mint(&user, &2_000);
+    reward_token.mint(&admin, &9_000);
+    staking.bond(&user, &2_000);
+    env.ledger().with_mut(|li| li.timestamp = SIXTY_DAYS);
+
+    // 1_000 per day on 2_000 staked shares is 0.5 per share per day
+    for _ in 0..7 {
+        env.ledger().with_mut(|li| li.timestamp += ONE_DAY);
+        staking.distribute_rewards(&admin, &1_000, &reward_token.address);
+    }
+
+    assert_eq!(
+        staking.query_annualized_rewards(&ONE_WEEK),
+        AnnualizedRewardsResponse {
+            rewards: vec![
+                &env,
+                AnnualizedReward {
+                    asset: reward_token.address.clone(),
+                    amount: String::from_str(&env, "182.5")
+                }
+            ]
+        }
+    );
+
+    // the rate doubles for the last day only
+    env.ledger().with_mut(|li| li.timestamp += ONE_DAY);
+    staking.distribute_rewards(&admin, &2_000, &reward_token.address);
+
+    assert_eq!(
+        staking.query_annualized_rewards(&ONE_DAY),
+        AnnualizedRewardsResponse {
+            rewards: vec![
+                &env,
+                AnnualizedReward {
+                    asset: reward_token.address.clone(),
+                    amount: String::from_str(&env, "365")
+                }
+            ]
+        }
+    );
+    // the oldest distribution drops out of the weekly window
+    assert_eq!(
+        staking.query_annualized_rewards(&ONE_WEEK),
+        AnnualizedRewardsResponse {
+            rewards: vec![
+                &env,
+                AnnualizedReward {
+                    asset: reward_token.address.clone(),
+                    amount: String::from_str(&env, "208.571428571428571428")
+                }
+            ]
+        }
+    );
+}
+
+#[test]
+fn annualized_rewards_for_each_distribution() {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.cost_estimate().budget().reset_unlimited();
+
+    let admin = Address::generate(&env);
+    let user = Address::generate(&env);
+    let lp_token = deploy_token_contract(&env, &admin);
+    let reward_token = deploy_token_contract(&env, &admin);
+    let second_reward_token = deploy_token_contract(&env, &admin);
+    let staking = deploy_staking_contract(
+        &env,
+        admin.clone(),
+        &lp_token.address,
+        &Address::generate(&env),
+        &admin,
+        &DEFAULT_COMPLEXITY,
+    );
+
+    staking.create_distribution_flow(&admin, &reward_token.address);
+    staking.create_distribution_flow(&admin, &second_reward_token.address);
+
+    lp_token.mint(&user, &1_000);
+    reward_token.mint(&admin, &1_000);
+    staking.bond(&user, &1_000);
+
+    env.ledger().with_mut(|li| li.timestamp = ONE_DAY);
+    staking.distribute_rewards(&admin, &1_000, &reward_token.address);
+
+    assert_eq!(
+        staking.query_annualized_rewards(&ONE_DAY),
+        AnnualizedRewardsResponse {
+            rewards: vec![
+                &env,
+                AnnualizedReward {
+                    asset: reward_token.address.clone(),
+                    amount: String::from_str(&env, "365")
+                },
+                AnnualizedReward {
+                    asset: second_reward_token.address.clone(),
+                    amount: String::from_str(&env, "0")
+                }
+            ]
+        }
+    );
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #526)")]
+fn annualized_rewards_with_empty_lookback_window() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let lp_token = deploy_token_contract(&env, &admin);
+    let staking = deploy_staking_contract(
+        &env,
+        admin.clone(),
+        &lp_token.address,
+        &Address::generate(&env),
+        &admin,
+        &DEFAULT_COMPLEXITY,
+    );
+
+    staking.query_annualized_rewards(&0);
+}
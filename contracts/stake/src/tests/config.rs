@@ -0,0 +1,59 @@
+use pretty_assertions::assert_eq;
+use soroban_sdk::{testutils::Address as _, Address, Env};
+
+use crate::{error::ContractError, storage::Config, tests::setup::deploy_staking_contract};
+
+#[test]
+fn update_config() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let lp_token = Address::generate(&env);
+    let manager = Address::generate(&env);
+    let owner = Address::generate(&env);
+    let new_manager = Address::generate(&env);
+
+    let staking = deploy_staking_contract(&env, admin.clone(), &lp_token, &manager, &owner, &7u32);
+
+    let expected = Config {
+        lp_token,
+        min_bond: 5_000,
+        min_reward: 2_000,
+        manager: new_manager.clone(),
+        owner,
+        max_complexity: 7,
+    };
+    assert_eq!(
+        staking.update_config(&Some(new_manager), &Some(5_000), &Some(2_000)),
+        expected
+    );
+    assert_eq!(staking.query_config().config, expected);
+
+    // nothing changes when no values are passed
+    assert_eq!(staking.update_config(&None, &None, &None), expected);
+}
+
+#[test]
+fn update_config_rejects_invalid_minimums() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let staking = deploy_staking_contract(
+        &env,
+        Address::generate(&env),
+        &Address::generate(&env),
+        &Address::generate(&env),
+        &Address::generate(&env),
+        &7u32,
+    );
+
+    assert_eq!(
+        staking.try_update_config(&None, &Some(0), &None),
+        Err(Ok(ContractError::InvalidMinBond))
+    );
+    assert_eq!(
+        staking.try_update_config(&None, &None, &Some(-1)),
+        Err(Ok(ContractError::InvalidMinReward))
+    );
+}
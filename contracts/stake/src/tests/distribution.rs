@@ -8,8 +8,10 @@ use super::setup::{deploy_staking_contract, deploy_token_contract};
 use pretty_assertions::assert_eq;
 
 use crate::{
-    msg::{WithdrawableReward, WithdrawableRewardsResponse},
-    tests::setup::SIXTY_DAYS,
+    contract::StakingClient,
+    msg::{StakeRewardsResponse, WithdrawableReward, WithdrawableRewardsResponse},
+    tests::setup::{ONE_DAY, SIXTY_DAYS},
+    token_contract,
 };
 
 #[test]
@@ -415,113 +417,6 @@ fn fund_rewards_without_establishing_distribution() {
 // }
 //
 // #[test]
-// fn calculate_apr() {
-//     let env = Env::default();
-//     env.mock_all_auths();
-//     env.cost_estimate().budget().reset_unlimited();
-//
-//     let admin = Address::generate(&env);
-//     let user = Address::generate(&env);
-//     let manager = Address::generate(&env);
-//
-//     let lp_token = deploy_token_contract(&env, &admin);
-//     let reward_token = deploy_token_contract(&env, &admin);
-//
-//     let staking = deploy_staking_contract(
-//         &env,
-//         admin.clone(),
-//         &lp_token.address,
-//         &manager,
-//         &admin,
-//         &50u32,
-//     );
-//
-//     staking.create_distribution_flow(
-//         &admin,
-//         &reward_token.address,
-//         &BytesN::from_array(&env, &[1; 32]),
-//         &10,
-//         &100,
-//         &1,
-//     );
-//
-//     let reward_amount: u128 = 100_000;
-//     reward_token.mint(&admin, &(reward_amount as i128));
-//
-//     // whole year of distribution
-//     let reward_duration = 60 * 60 * 24 * 365;
-//     staking.fund_distribution(
-//         &SIXTY_DAYS,
-//         &reward_duration,
-//         &reward_token.address,
-//         &(reward_amount as i128),
-//     );
-//
-//     // nothing bonded, no rewards
-//     assert_eq!(
-//         staking.query_annualized_rewards(),
-//         AnnualizedRewardsResponse {
-//             rewards: vec![
-//                 &env,
-//                 AnnualizedReward {
-//                     asset: reward_token.address.clone(),
-//                     amount: String::from_str(&env, "0")
-//                 }
-//             ]
-//         }
-//     );
-//
-//     // bond tokens for user to enable distribution for him
-//     lp_token.mint(&user, &1000);
-//     env.ledger().with_mut(|li| {
-//         li.timestamp += ONE_DAY;
-//     });
-//     staking.bond(&user, &1000);
-//     // simulate moving forward 60 days for the full APR multiplier
-//     env.ledger().with_mut(|li| {
-//         li.timestamp = SIXTY_DAYS;
-//     });
-//
-//     // 100k rewards distributed for the 10 months gives ~120% APR
-//     assert_eq!(
-//         staking.query_annualized_rewards(),
-//         AnnualizedRewardsResponse {
-//             rewards: vec![
-//                 &env,
-//                 AnnualizedReward {
-//                     asset: reward_token.address.clone(),
-//                     amount: String::from_str(&env, "119672.131147540983606557")
-//                 }
-//             ]
-//         }
-//     );
-//
-//     let reward_amount: u128 = 50_000;
-//     reward_token.mint(&admin, &(reward_amount as i128));
-//
-//     staking.fund_distribution(
-//         &(2 * &SIXTY_DAYS),
-//         &reward_duration,
-//         &reward_token.address,
-//         &(reward_amount as i128),
-//     );
-//
-//     // having another 50k in rewards increases APR
-//     assert_eq!(
-//         staking.query_annualized_rewards(),
-//         AnnualizedRewardsResponse {
-//             rewards: vec![
-//                 &env,
-//                 AnnualizedReward {
-//                     asset: reward_token.address.clone(),
-//                     amount: String::from_str(&env, "150000")
-//                 }
-//             ]
-//         }
-//     );
-// }
-//
-// #[test]
 // #[should_panic(expected = "Stake: create distribution: Non-authorized creation!")]
 // fn add_distribution_should_fail_when_not_authorized() {
 //     let env = Env::default();
@@ -1192,3 +1087,165 @@ fn distribute_rewards_daily_multiple_times_same_stakes() {
 
     assert_eq!(reward_token.balance(&staking.address), 0);
 }
+
+/// Bonds 1_000 LP shares for a user and distributes 1_000 reward tokens to
+/// them through a flow created by the manager.
+fn setup_single_distribution<'a>(
+    env: &Env,
+) -> (
+    StakingClient<'a>,
+    token_contract::Client<'a>,
+    Address,
+    Address,
+    Address,
+) {
+    env.mock_all_auths();
+    env.cost_estimate().budget().reset_unlimited();
+
+    let admin = Address::generate(env);
+    let user = Address::generate(env);
+    let manager = Address::generate(env);
+    let lp_token = deploy_token_contract(env, &admin);
+    let reward_token = deploy_token_contract(env, &admin);
+
+    let staking = deploy_staking_contract(
+        env,
+        admin.clone(),
+        &lp_token.address,
+        &manager,
+        &admin,
+        &7u32,
+    );
+
+    lp_token.mint(&user, &1_000);
+    reward_token.mint(&admin, &2_000);
+    staking.bond(&user, &1_000);
+    staking.create_distribution_flow(&manager, &reward_token.address);
+
+    env.ledger().with_mut(|li| li.timestamp = SIXTY_DAYS);
+    staking.distribute_rewards(&admin, &1_000, &reward_token.address);
+    env.ledger().with_mut(|li| li.timestamp += ONE_DAY);
+
+    (staking, reward_token, admin, manager, user)
+}
+
+#[test]
+fn remove_distribution_flow_keeps_earned_rewards_claimable() {
+    let env = Env::default();
+    let (staking, reward_token, admin, manager, user) = setup_single_distribution(&env);
+
+    staking.remove_distribution_flow(&manager, &reward_token.address);
+
+    assert_eq!(
+        staking.query_withdrawable_rewards(&user),
+        WithdrawableRewardsResponse {
+            rewards: vec![
+                &env,
+                WithdrawableReward {
+                    reward_address: reward_token.address.clone(),
+                    reward_amount: 1_000
+                }
+            ]
+        }
+    );
+
+    staking.withdraw_rewards(&user);
+    assert_eq!(reward_token.balance(&user), 1_000);
+
+    // re-creating the flow keeps the history instead of starting a new one
+    staking.create_distribution_flow(&manager, &reward_token.address);
+    env.ledger().with_mut(|li| li.timestamp += ONE_DAY);
+    staking.distribute_rewards(&admin, &1_000, &reward_token.address);
+    env.ledger().with_mut(|li| li.timestamp += ONE_DAY);
+
+    staking.withdraw_rewards(&user);
+    assert_eq!(reward_token.balance(&user), 2_000);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #514)")]
+fn distribute_rewards_to_removed_distribution_flow() {
+    let env = Env::default();
+    let (staking, reward_token, admin, manager, _) = setup_single_distribution(&env);
+
+    staking.remove_distribution_flow(&manager, &reward_token.address);
+
+    staking.distribute_rewards(&admin, &1_000, &reward_token.address);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #514)")]
+fn remove_distribution_flow_twice() {
+    let env = Env::default();
+    let (staking, reward_token, _, manager, _) = setup_single_distribution(&env);
+
+    staking.remove_distribution_flow(&manager, &reward_token.address);
+    staking.remove_distribution_flow(&manager, &reward_token.address);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #504)")]
+fn remove_distribution_flow_unauthorized() {
+    let env = Env::default();
+    let (staking, reward_token, _, _, user) = setup_single_distribution(&env);
+
+    staking.remove_distribution_flow(&user, &reward_token.address);
+}
+
+#[test]
+fn stake_rewards_are_broken_down_by_stake_age() {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.cost_estimate().budget().reset_unlimited();
+
+    let admin = Address::generate(&env);
+    let user = Address::generate(&env);
+    let manager = Address::generate(&env);
+    let lp_token = deploy_token_contract(&env, &admin);
+    let reward_token = deploy_token_contract(&env, &admin);
+
+    let staking = deploy_staking_contract(
+        &env,
+        admin.clone(),
+        &lp_token.address,
+        &manager,
+        &admin,
+        &7u32,
+    );
+    staking.create_distribution_flow(&manager, &reward_token.address);
+
+    lp_token.mint(&user, &2_000);
+    staking.bond(&user, &1_000);
+    env.ledger().with_mut(|li| li.timestamp = 30 * ONE_DAY);
+    staking.bond(&user, &1_000);
+
+    env.ledger().with_mut(|li| li.timestamp = SIXTY_DAYS);
+    reward_token.mint(&admin, &1_000);
+    staking.distribute_rewards(&admin, &1_000, &reward_token.address);
+    env.ledger().with_mut(|li| li.timestamp += ONE_DAY);
+
+    // the younger stake earns half of its share
+    let stake_rewards =
+        |stake: i128, stake_timestamp: u64, reward_amount: u128| StakeRewardsResponse {
+            stake,
+            stake_timestamp,
+            rewards: vec![
+                &env,
+                WithdrawableReward {
+                    reward_address: reward_token.address.clone(),
+                    reward_amount,
+                },
+            ],
+        };
+    assert_eq!(
+        staking.query_stake_rewards(&user),
+        vec![
+            &env,
+            stake_rewards(1_000, 0, 500),
+            stake_rewards(1_000, 30 * ONE_DAY, 250),
+        ]
+    );
+
+    staking.withdraw_rewards(&user);
+    assert_eq!(reward_token.balance(&user), 750);
+}
@@ -0,0 +1,294 @@
+extern crate std;
+
+use pretty_assertions::assert_eq;
+use soroban_sdk::{
+    testutils::{Address as _, Ledger},
+    vec, Address, Env,
+};
+
+use super::setup::{deploy_staking_contract, deploy_token_contract, ONE_DAY, SIXTY_DAYS};
+use crate::storage::Stake;
+
+const DEFAULT_COMPLEXITY: u32 = 7;
+
+#[test]
+fn transfer_stake_keeps_timestamp() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let cold = Address::generate(&env);
+    let custody = Address::generate(&env);
+    let lp_token = deploy_token_contract(&env, &admin);
+    let staking = deploy_staking_contract(
+        &env,
+        admin.clone(),
+        &lp_token.address,
+        &Address::generate(&env),
+        &admin,
+        &DEFAULT_COMPLEXITY,
+    );
+
+    lp_token.mint(&cold, &3_000);
+    lp_token.mint(&custody, &1_000);
+    staking.bond(&cold, &1_000);
+    env.ledger().with_mut(|li| li.timestamp = ONE_DAY);
+    staking.bond(&cold, &2_000);
+    env.ledger().with_mut(|li| li.timestamp = 2 * ONE_DAY);
+    staking.bond(&custody, &1_000);
+
+    staking.transfer_stake(&cold, &custody, &2_000, &ONE_DAY);
+
+    let cold_staked = staking.query_staked(&cold);
+    assert_eq!(
+        cold_staked.stakes,
+        vec![
+            &env,
+            Stake {
+                stake: 1_000,
+                stake_timestamp: 0,
+            }
+        ]
+    );
+    assert_eq!(cold_staked.total_stake, 1_000);
+
+    let custody_staked = staking.query_staked(&custody);
+    assert_eq!(
+        custody_staked.stakes,
+        vec![
+            &env,
+            Stake {
+                stake: 2_000,
+                stake_timestamp: ONE_DAY,
+            },
+            Stake {
+                stake: 1_000,
+                stake_timestamp: 2 * ONE_DAY,
+            }
+        ]
+    );
+    assert_eq!(custody_staked.total_stake, 3_000);
+    assert_eq!(staking.query_total_staked(), 4_000);
+
+    // the new owner can unbond the transferred stake
+    staking.unbond(&custody, &2_000, &ONE_DAY);
+    assert_eq!(lp_token.balance(&custody), 2_000);
+}
+
+#[test]
+fn transfer_stake_does_not_pay_rewards_twice() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let from = Address::generate(&env);
+    let to = Address::generate(&env);
+    let lp_token = deploy_token_contract(&env, &admin);
+    let reward_token = deploy_token_contract(&env, &admin);
+    let staking = deploy_staking_contract(
+        &env,
+        admin.clone(),
+        &lp_token.address,
+        &Address::generate(&env),
+        &admin,
+        &DEFAULT_COMPLEXITY,
+    );
+
+    lp_token.mint(&from, &1_000);
+    reward_token.mint(&admin, &2_000);
+    staking.bond(&from, &1_000);
+    staking.create_distribution_flow(&admin, &reward_token.address);
+
+    env.ledger().with_mut(|li| li.timestamp = SIXTY_DAYS);
+    staking.distribute_rewards(&admin, &1_000, &reward_token.address);
+    env.ledger().with_mut(|li| li.timestamp += ONE_DAY);
+
+    staking.transfer_stake(&from, &to, &1_000, &0);
+    assert_eq!(reward_token.balance(&from), 1_000);
+
+    env.ledger().with_mut(|li| li.timestamp += ONE_DAY);
+    staking.distribute_rewards(&admin, &1_000, &reward_token.address);
+    env.ledger().with_mut(|li| li.timestamp += ONE_DAY);
+
+    staking.withdraw_rewards(&from);
+    staking.withdraw_rewards(&to);
+    assert_eq!(reward_token.balance(&from), 1_000);
+    assert_eq!(reward_token.balance(&to), 1_000);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #509)")]
+fn transfer_stake_not_found() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let from = Address::generate(&env);
+    let lp_token = deploy_token_contract(&env, &admin);
+    let staking = deploy_staking_contract(
+        &env,
+        admin.clone(),
+        &lp_token.address,
+        &Address::generate(&env),
+        &admin,
+        &DEFAULT_COMPLEXITY,
+    );
+
+    lp_token.mint(&from, &1_000);
+    staking.bond(&from, &1_000);
+
+    staking.transfer_stake(&from, &Address::generate(&env), &1_000, &ONE_DAY);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #525)")]
+fn transfer_stake_to_self() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let from = Address::generate(&env);
+    let lp_token = deploy_token_contract(&env, &admin);
+    let staking = deploy_staking_contract(
+        &env,
+        admin.clone(),
+        &lp_token.address,
+        &Address::generate(&env),
+        &admin,
+        &DEFAULT_COMPLEXITY,
+    );
+
+    lp_token.mint(&from, &1_000);
+    staking.bond(&from, &1_000);
+
+    staking.transfer_stake(&from, &from, &1_000, &0);
+}
+
+#[test]
+fn transfer_stake_picks_the_stake_by_amount_and_timestamp() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let from = Address::generate(&env);
+    let to = Address::generate(&env);
+    let lp_token = deploy_token_contract(&env, &admin);
+    let staking = deploy_staking_contract(
+        &env,
+        admin.clone(),
+        &lp_token.address,
+        &Address::generate(&env),
+        &admin,
+        &DEFAULT_COMPLEXITY,
+    );
+
+    // both stakes share the timestamp
+    lp_token.mint(&from, &3_000);
+    staking.bond(&from, &1_000);
+    staking.bond(&from, &2_000);
+
+    staking.transfer_stake(&from, &to, &2_000, &0);
+
+    assert_eq!(
+        staking.query_staked(&from).stakes,
+        vec![
+            &env,
+            Stake {
+                stake: 1_000,
+                stake_timestamp: 0,
+            }
+        ]
+    );
+    assert_eq!(
+        staking.query_staked(&to).stakes,
+        vec![
+            &env,
+            Stake {
+                stake: 2_000,
+                stake_timestamp: 0,
+            }
+        ]
+    );
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #527)")]
+fn transfer_stake_to_receiver_at_max_complexity_fails() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let from = Address::generate(&env);
+    let to = Address::generate(&env);
+    let lp_token = deploy_token_contract(&env, &admin);
+    let staking = deploy_staking_contract(
+        &env,
+        admin.clone(),
+        &lp_token.address,
+        &Address::generate(&env),
+        &admin,
+        &2,
+    );
+
+    lp_token.mint(&from, &3_000);
+    for _ in 0..3 {
+        staking.bond(&from, &1_000);
+    }
+
+    staking.transfer_stake(&from, &to, &1_000, &0);
+    staking.transfer_stake(&from, &to, &1_000, &0);
+    staking.transfer_stake(&from, &to, &1_000, &0);
+}
+
+#[test]
+fn delegated_reward_recipient_receives_rewards() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let cold = Address::generate(&env);
+    let hot = Address::generate(&env);
+    let lp_token = deploy_token_contract(&env, &admin);
+    let reward_token = deploy_token_contract(&env, &admin);
+    let staking = deploy_staking_contract(
+        &env,
+        admin.clone(),
+        &lp_token.address,
+        &Address::generate(&env),
+        &admin,
+        &DEFAULT_COMPLEXITY,
+    );
+
+    lp_token.mint(&cold, &1_000);
+    reward_token.mint(&admin, &3_000);
+    staking.bond(&cold, &1_000);
+    staking.create_distribution_flow(&admin, &reward_token.address);
+
+    assert_eq!(staking.query_reward_recipient(&cold), cold);
+    staking.set_reward_recipient(&cold, &Some(hot.clone()));
+    assert_eq!(staking.query_reward_recipient(&cold), hot);
+
+    env.ledger().with_mut(|li| li.timestamp = SIXTY_DAYS);
+    staking.distribute_rewards(&admin, &1_000, &reward_token.address);
+    env.ledger().with_mut(|li| li.timestamp += ONE_DAY);
+
+    // anyone can trigger the withdrawal, the rewards land with the delegate
+    staking.withdraw_rewards(&cold);
+    assert_eq!(reward_token.balance(&hot), 1_000);
+    assert_eq!(reward_token.balance(&cold), 0);
+
+    env.ledger().with_mut(|li| li.timestamp += ONE_DAY);
+    staking.distribute_rewards(&admin, &1_000, &reward_token.address);
+    env.ledger().with_mut(|li| li.timestamp += ONE_DAY);
+    staking.distribute_rewards(&admin, &1_000, &reward_token.address);
+    env.ledger().with_mut(|li| li.timestamp += ONE_DAY);
+
+    // clearing the delegation pays out what was accrued so far to the delegate
+    staking.set_reward_recipient(&cold, &None);
+    assert_eq!(staking.query_reward_recipient(&cold), cold);
+    assert_eq!(reward_token.balance(&hot), 3_000);
+
+    staking.unbond(&cold, &1_000, &0);
+    assert_eq!(reward_token.balance(&cold), 0);
+    assert_eq!(lp_token.balance(&cold), 1_000);
+}
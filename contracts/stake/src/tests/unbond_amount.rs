@@ -0,0 +1,144 @@
+extern crate std;
+
+use pretty_assertions::assert_eq;
+use soroban_sdk::{
+    testutils::{Address as _, Ledger},
+    vec, Address, Env,
+};
+
+use super::setup::{deploy_staking_contract, deploy_token_contract, ONE_DAY, SIXTY_DAYS};
+use crate::{
+    contract::StakingClient,
+    storage::{Stake, UnbondStrategy},
+    token_contract,
+};
+
+const DEFAULT_COMPLEXITY: u32 = 7;
+
+/// Bonds 1_000, 2_000 and 3_000 LP shares for `user` on days 0, 1 and 2.
+fn setup_three_stakes<'a>(
+    env: &Env,
+) -> (
+    StakingClient<'a>,
+    token_contract::Client<'a>,
+    Address,
+    Address,
+) {
+    env.mock_all_auths();
+    env.cost_estimate().budget().reset_unlimited();
+
+    let admin = Address::generate(env);
+    let user = Address::generate(env);
+    let lp_token = deploy_token_contract(env, &admin);
+    let staking = deploy_staking_contract(
+        env,
+        admin.clone(),
+        &lp_token.address,
+        &Address::generate(env),
+        &admin,
+        &DEFAULT_COMPLEXITY,
+    );
+
+    lp_token.mint(&user, &6_000);
+    staking.bond(&user, &1_000);
+    env.ledger().with_mut(|li| li.timestamp = ONE_DAY);
+    staking.bond(&user, &2_000);
+    env.ledger().with_mut(|li| li.timestamp = 2 * ONE_DAY);
+    staking.bond(&user, &3_000);
+
+    (staking, lp_token, admin, user)
+}
+
+#[test]
+fn unbond_amount_newest_first() {
+    let env = Env::default();
+    let (staking, lp_token, _, user) = setup_three_stakes(&env);
+
+    staking.unbond_amount(&user, &4_000, &UnbondStrategy::NewestFirst);
+
+    let staked = staking.query_staked(&user);
+    assert_eq!(
+        staked.stakes,
+        vec![
+            &env,
+            Stake {
+                stake: 1_000,
+                stake_timestamp: 0,
+            },
+            Stake {
+                stake: 1_000,
+                stake_timestamp: ONE_DAY,
+            },
+        ]
+    );
+    assert_eq!(staked.total_stake, 2_000);
+    assert_eq!(staking.query_total_staked(), 2_000);
+    assert_eq!(lp_token.balance(&user), 4_000);
+    assert_eq!(lp_token.balance(&staking.address), 2_000);
+}
+
+#[test]
+fn unbond_amount_oldest_first() {
+    let env = Env::default();
+    let (staking, lp_token, _, user) = setup_three_stakes(&env);
+
+    staking.unbond_amount(&user, &1_500, &UnbondStrategy::OldestFirst);
+
+    let staked = staking.query_staked(&user);
+    assert_eq!(
+        staked.stakes,
+        vec![
+            &env,
+            Stake {
+                stake: 1_500,
+                stake_timestamp: ONE_DAY,
+            },
+            Stake {
+                stake: 3_000,
+                stake_timestamp: 2 * ONE_DAY,
+            },
+        ]
+    );
+    assert_eq!(staked.total_stake, 4_500);
+    assert_eq!(lp_token.balance(&user), 1_500);
+}
+
+#[test]
+fn unbond_amount_settles_rewards_first() {
+    let env = Env::default();
+    let (staking, _, admin, user) = setup_three_stakes(&env);
+
+    let reward_token = deploy_token_contract(&env, &admin);
+    reward_token.mint(&admin, &6_000);
+    staking.create_distribution_flow(&admin, &reward_token.address);
+
+    env.ledger()
+        .with_mut(|li| li.timestamp = SIXTY_DAYS + 2 * ONE_DAY);
+    staking.distribute_rewards(&admin, &6_000, &reward_token.address);
+    env.ledger().with_mut(|li| li.timestamp += ONE_DAY);
+
+    // the whole distribution is owed to the single user, including the share of
+    // the stakes that are about to be removed
+    staking.unbond_amount(&user, &6_000, &UnbondStrategy::NewestFirst);
+
+    assert_eq!(reward_token.balance(&user), 6_000);
+    assert_eq!(staking.query_staked(&user).stakes.len(), 0);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #524)")]
+fn unbond_amount_more_than_staked() {
+    let env = Env::default();
+    let (staking, _, _, user) = setup_three_stakes(&env);
+
+    staking.unbond_amount(&user, &6_001, &UnbondStrategy::NewestFirst);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #524)")]
+fn unbond_amount_zero() {
+    let env = Env::default();
+    let (staking, _, _, user) = setup_three_stakes(&env);
+
+    staking.unbond_amount(&user, &0, &UnbondStrategy::OldestFirst);
+}
@@ -0,0 +1,277 @@
+use phoenix::{
+    ttl::{INSTANCE_RENEWAL_THRESHOLD, INSTANCE_TARGET_TTL},
+    utils::AdminChange,
+};
+use soroban_sdk::{
+    contract, contractimpl, contractmeta, log, panic_with_error, Address, Env, Symbol, Val, Vec,
+};
+
+use crate::{
+    error::ContractError,
+    storage::{
+        get_admin, get_config, get_operation, next_operation_id, remove_operation, save_admin,
+        save_config, save_operation, Config, Operation, PENDING_ADMIN,
+    },
+};
+
+// Metadata that is added on to the WASM custom section
+contractmeta!(key = "Description", val = "Phoenix Protocol Timelock");
+
+#[contract]
+pub struct Timelock;
+
+#[allow(dead_code)]
+pub trait TimelockTrait {
+    /// Queues a call to `function` of `target`, executable once `delay`
+    /// seconds have passed. `delay` can't be shorter than the minimum delay.
+    fn queue(
+        env: Env,
+        target: Address,
+        function: Symbol,
+        args: Vec<Val>,
+        delay: u64,
+    ) -> Result<u64, ContractError>;
+
+    fn cancel(env: Env, operation_id: u64) -> Result<(), ContractError>;
+
+    /// Executes a queued operation once its eta is reached and before the
+    /// grace period ends. Anyone can execute, the timelock is the caller.
+    fn execute(env: Env, operation_id: u64) -> Result<Val, ContractError>;
+
+    fn query_operation(env: Env, operation_id: u64) -> Option<Operation>;
+
+    fn query_config(env: Env) -> Config;
+
+    fn query_admin(env: Env) -> Address;
+
+    fn propose_admin(
+        env: Env,
+        new_admin: Address,
+        time_limit: Option<u64>,
+    ) -> Result<Address, ContractError>;
+
+    fn revoke_admin_change(env: Env) -> Result<(), ContractError>;
+
+    fn accept_admin(env: Env) -> Result<Address, ContractError>;
+}
+
+#[contractimpl]
+impl TimelockTrait for Timelock {
+    fn queue(
+        env: Env,
+        target: Address,
+        function: Symbol,
+        args: Vec<Val>,
+        delay: u64,
+    ) -> Result<u64, ContractError> {
+        get_admin(&env).require_auth();
+        env.storage()
+            .instance()
+            .extend_ttl(INSTANCE_RENEWAL_THRESHOLD, INSTANCE_TARGET_TTL);
+
+        if delay < get_config(&env).min_delay {
+            log!(
+                &env,
+                "Timelock: Queue: Delay is shorter than the minimum delay"
+            );
+            panic_with_error!(&env, ContractError::DelayTooShort);
+        }
+
+        let operation_id = next_operation_id(&env);
+        let eta = env.ledger().timestamp() + delay;
+        save_operation(
+            &env,
+            operation_id,
+            &Operation {
+                target: target.clone(),
+                function: function.clone(),
+                args,
+                eta,
+            },
+        );
+
+        env.events()
+            .publish(("Timelock", "queue"), (operation_id, target, function, eta));
+
+        Ok(operation_id)
+    }
+
+    fn cancel(env: Env, operation_id: u64) -> Result<(), ContractError> {
+        get_admin(&env).require_auth();
+        env.storage()
+            .instance()
+            .extend_ttl(INSTANCE_RENEWAL_THRESHOLD, INSTANCE_TARGET_TTL);
+
+        if get_operation(&env, operation_id).is_none() {
+            log!(&env, "Timelock: Cancel: Operation not found");
+            panic_with_error!(&env, ContractError::OperationNotFound);
+        }
+        remove_operation(&env, operation_id);
+
+        env.events().publish(("Timelock", "cancel"), operation_id);
+
+        Ok(())
+    }
+
+    fn execute(env: Env, operation_id: u64) -> Result<Val, ContractError> {
+        env.storage()
+            .instance()
+            .extend_ttl(INSTANCE_RENEWAL_THRESHOLD, INSTANCE_TARGET_TTL);
+
+        let operation = get_operation(&env, operation_id).unwrap_or_else(|| {
+            log!(&env, "Timelock: Execute: Operation not found");
+            panic_with_error!(&env, ContractError::OperationNotFound);
+        });
+
+        let now = env.ledger().timestamp();
+        if now < operation.eta {
+            log!(&env, "Timelock: Execute: Operation is still locked");
+            panic_with_error!(&env, ContractError::OperationNotReady);
+        }
+        if now > operation.eta + get_config(&env).grace_period {
+            log!(&env, "Timelock: Execute: Operation expired");
+            panic_with_error!(&env, ContractError::OperationExpired);
+        }
+
+        // removed first, so an operation can't be executed twice
+        remove_operation(&env, operation_id);
+        let result: Val =
+            env.invoke_contract(&operation.target, &operation.function, operation.args);
+
+        env.events().publish(
+            ("Timelock", "execute"),
+            (operation_id, operation.target, operation.function),
+        );
+
+        Ok(result)
+    }
+
+    fn query_operation(env: Env, operation_id: u64) -> Option<Operation> {
+        env.storage()
+            .instance()
+            .extend_ttl(INSTANCE_RENEWAL_THRESHOLD, INSTANCE_TARGET_TTL);
+        get_operation(&env, operation_id)
+    }
+
+    fn query_config(env: Env) -> Config {
+        env.storage()
+            .instance()
+            .extend_ttl(INSTANCE_RENEWAL_THRESHOLD, INSTANCE_TARGET_TTL);
+        get_config(&env)
+    }
+
+    fn query_admin(env: Env) -> Address {
+        env.storage()
+            .instance()
+            .extend_ttl(INSTANCE_RENEWAL_THRESHOLD, INSTANCE_TARGET_TTL);
+        get_admin(&env)
+    }
+
+    fn propose_admin(
+        env: Env,
+        new_admin: Address,
+        time_limit: Option<u64>,
+    ) -> Result<Address, ContractError> {
+        let current_admin = get_admin(&env);
+        current_admin.require_auth();
+
+        if current_admin == new_admin {
+            log!(&env, "Trying to set new admin as new");
+            panic_with_error!(&env, ContractError::SameAdmin);
+        }
+
+        env.storage().instance().set(
+            &PENDING_ADMIN,
+            &AdminChange {
+                new_admin: new_admin.clone(),
+                time_limit,
+            },
+        );
+
+        env.events().publish(
+            ("Timelock: ", "Admin replacement requested by old admin: "),
+            &current_admin,
+        );
+        env.events()
+            .publish(("Timelock: ", "Replace with new admin: "), &new_admin);
+
+        Ok(new_admin)
+    }
+
+    fn revoke_admin_change(env: Env) -> Result<(), ContractError> {
+        let current_admin = get_admin(&env);
+        current_admin.require_auth();
+
+        if !env.storage().instance().has(&PENDING_ADMIN) {
+            log!(&env, "No admin change in place");
+            panic_with_error!(&env, ContractError::NoAdminChangeInPlace);
+        }
+
+        env.storage().instance().remove(&PENDING_ADMIN);
+
+        env.events()
+            .publish(("Timelock: ", "Undo admin change: "), ());
+
+        Ok(())
+    }
+
+    fn accept_admin(env: Env) -> Result<Address, ContractError> {
+        let admin_change_info: AdminChange = env
+            .storage()
+            .instance()
+            .get(&PENDING_ADMIN)
+            .unwrap_or_else(|| {
+                log!(&env, "No admin change request is in place");
+                panic_with_error!(&env, ContractError::NoAdminChangeInPlace);
+            });
+
+        let pending_admin = admin_change_info.new_admin;
+        pending_admin.require_auth();
+
+        if let Some(time_limit) = admin_change_info.time_limit {
+            if env.ledger().timestamp() > time_limit {
+                log!(&env, "Admin change expired");
+                panic_with_error!(&env, ContractError::AdminChangeExpired);
+            }
+        }
+
+        env.storage().instance().remove(&PENDING_ADMIN);
+
+        save_admin(&env, &pending_admin);
+
+        env.events()
+            .publish(("Timelock: ", "Accepted new admin: "), &pending_admin);
+
+        Ok(pending_admin)
+    }
+}
+
+#[contractimpl]
+impl Timelock {
+    /// The delays are fixed for the lifetime of the contract, as the timelock
+    /// can't call itself. Moving governance to a timelock with other delays
+    /// goes through queued admin changes on the governed contracts.
+    pub fn __constructor(env: Env, admin: Address, min_delay: u64, grace_period: u64) {
+        if grace_period == 0 {
+            log!(
+                &env,
+                "Timelock: Initialize: Grace period must be bigger than 0"
+            );
+            panic_with_error!(&env, ContractError::InvalidConfig);
+        }
+
+        save_admin(&env, &admin);
+        save_config(
+            &env,
+            &Config {
+                min_delay,
+                grace_period,
+            },
+        );
+
+        env.events().publish(
+            ("initialize", "Timelock contract"),
+            (admin, min_delay, grace_period),
+        );
+    }
+}
@@ -0,0 +1,16 @@
+use soroban_sdk::contracterror;
+
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[repr(u32)]
+pub enum ContractError {
+    AdminNotSet = 800,
+    InvalidConfig = 801,
+    DelayTooShort = 802,
+    OperationNotFound = 803,
+    OperationNotReady = 804,
+    OperationExpired = 805,
+    SameAdmin = 806,
+    NoAdminChangeInPlace = 807,
+    AdminChangeExpired = 808,
+}
@@ -0,0 +1,7 @@
+#![no_std]
+mod contract;
+mod error;
+mod storage;
+
+#[cfg(test)]
+mod tests;
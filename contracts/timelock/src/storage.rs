@@ -0,0 +1,120 @@
+use phoenix::ttl::{
+    INSTANCE_RENEWAL_THRESHOLD, INSTANCE_TARGET_TTL, PERSISTENT_RENEWAL_THRESHOLD,
+    PERSISTENT_TARGET_TTL,
+};
+use soroban_sdk::{
+    contracttype, log, panic_with_error, symbol_short, Address, Env, Symbol, Val, Vec,
+};
+
+use crate::error::ContractError;
+
+pub const ADMIN: Symbol = symbol_short!("ADMIN");
+pub(crate) const PENDING_ADMIN: Symbol = symbol_short!("p_admin");
+
+#[derive(Clone)]
+#[contracttype]
+pub enum DataKey {
+    Config,
+    NextOperationId,
+    Operation(u64),
+}
+
+#[contracttype]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Config {
+    /// Minimum time between queueing an operation and executing it
+    pub min_delay: u64,
+    /// Time after its eta during which an operation can still be executed
+    pub grace_period: u64,
+}
+
+/// A call to `function` of `target` with `args`, executable from `eta` on
+#[contracttype]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Operation {
+    pub target: Address,
+    pub function: Symbol,
+    pub args: Vec<Val>,
+    pub eta: u64,
+}
+
+pub fn save_admin(env: &Env, admin: &Address) {
+    env.storage().instance().set(&ADMIN, admin);
+    env.storage()
+        .instance()
+        .extend_ttl(INSTANCE_RENEWAL_THRESHOLD, INSTANCE_TARGET_TTL);
+}
+
+pub fn get_admin(env: &Env) -> Address {
+    let admin = env.storage().instance().get(&ADMIN).unwrap_or_else(|| {
+        log!(env, "Timelock: Admin not set");
+        panic_with_error!(&env, ContractError::AdminNotSet)
+    });
+    env.storage()
+        .instance()
+        .extend_ttl(INSTANCE_RENEWAL_THRESHOLD, INSTANCE_TARGET_TTL);
+
+    admin
+}
+
+pub fn save_config(env: &Env, config: &Config) {
+    env.storage().instance().set(&DataKey::Config, config);
+    env.storage()
+        .instance()
+        .extend_ttl(INSTANCE_RENEWAL_THRESHOLD, INSTANCE_TARGET_TTL);
+}
+
+pub fn get_config(env: &Env) -> Config {
+    let config = env
+        .storage()
+        .instance()
+        .get(&DataKey::Config)
+        .expect("Timelock: Config not set");
+    env.storage()
+        .instance()
+        .extend_ttl(INSTANCE_RENEWAL_THRESHOLD, INSTANCE_TARGET_TTL);
+
+    config
+}
+
+/// Returns the id for a new operation and bumps the counter
+pub fn next_operation_id(env: &Env) -> u64 {
+    let id = env
+        .storage()
+        .instance()
+        .get(&DataKey::NextOperationId)
+        .unwrap_or(0u64);
+    env.storage()
+        .instance()
+        .set(&DataKey::NextOperationId, &(id + 1));
+
+    id
+}
+
+pub fn save_operation(env: &Env, id: u64, operation: &Operation) {
+    let key = DataKey::Operation(id);
+    env.storage().persistent().set(&key, operation);
+    env.storage().persistent().extend_ttl(
+        &key,
+        PERSISTENT_RENEWAL_THRESHOLD,
+        PERSISTENT_TARGET_TTL,
+    );
+}
+
+pub fn get_operation(env: &Env, id: u64) -> Option<Operation> {
+    let key = DataKey::Operation(id);
+    let operation = env.storage().persistent().get(&key);
+    if operation.is_some() {
+        env.storage().persistent().extend_ttl(
+            &key,
+            PERSISTENT_RENEWAL_THRESHOLD,
+            PERSISTENT_TARGET_TTL,
+        );
+    }
+
+    operation
+}
+
+pub fn remove_operation(env: &Env, id: u64) {
+    env.storage().persistent().remove(&DataKey::Operation(id));
+}
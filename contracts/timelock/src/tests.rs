@@ -0,0 +1,3 @@
+mod governance;
+mod setup;
+mod timelock;
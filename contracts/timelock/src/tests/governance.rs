@@ -0,0 +1,100 @@
+use soroban_sdk::{
+    testutils::{Address as _, Ledger},
+    vec, Address, Env, IntoVal, Symbol, Val, Vec,
+};
+
+use super::setup::{
+    create_pool, deploy_factory_contract, deploy_timelock_contract, ONE_DAY, ONE_WEEK,
+};
+
+#[test]
+fn factory_config_changes_go_through_the_timelock() {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.cost_estimate().budget().reset_unlimited();
+    let admin = Address::generate(&env);
+    let creator = Address::generate(&env);
+    let new_creator = Address::generate(&env);
+
+    let timelock = deploy_timelock_contract(&env, &admin, ONE_DAY, ONE_WEEK);
+    let factory = deploy_factory_contract(&env, &timelock.address, &creator);
+    assert_eq!(factory.get_admin(), timelock.address);
+
+    let args: Vec<Val> = vec![
+        &env,
+        None::<Address>.into_val(&env),
+        None::<Val>.into_val(&env),
+        None::<Val>.into_val(&env),
+        None::<Val>.into_val(&env),
+        Some(vec![&env, new_creator.clone()]).into_val(&env),
+        None::<Val>.into_val(&env),
+        None::<u32>.into_val(&env),
+    ];
+    let operation_id = timelock.queue(
+        &factory.address,
+        &Symbol::new(&env, "update_config"),
+        &args,
+        &ONE_DAY,
+    );
+
+    // the admin key alone can't change the factory
+    env.set_auths(&[]);
+    assert!(factory
+        .try_update_config(
+            &None,
+            &None,
+            &None,
+            &None,
+            &Some(vec![&env, new_creator.clone()]),
+            &None,
+            &None,
+        )
+        .is_err());
+
+    env.ledger().with_mut(|li| li.timestamp = ONE_DAY);
+    timelock.execute(&operation_id);
+
+    assert!(factory
+        .get_config()
+        .whitelisted_accounts
+        .contains(new_creator));
+}
+
+#[test]
+fn pool_config_changes_go_through_the_timelock() {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.cost_estimate().budget().reset_unlimited();
+    let admin = Address::generate(&env);
+    let creator = Address::generate(&env);
+
+    let timelock = deploy_timelock_contract(&env, &admin, ONE_DAY, ONE_WEEK);
+    let factory = deploy_factory_contract(&env, &Address::generate(&env), &creator);
+    let pool = create_pool(&env, &factory, &creator, &timelock.address);
+
+    let args: Vec<Val> = vec![
+        &env,
+        None::<Address>.into_val(&env),
+        Some(100i64).into_val(&env),
+        None::<Address>.into_val(&env),
+        None::<i64>.into_val(&env),
+        None::<i64>.into_val(&env),
+        None::<i64>.into_val(&env),
+    ];
+    let operation_id = timelock.queue(
+        &pool.address,
+        &Symbol::new(&env, "update_config"),
+        &args,
+        &ONE_DAY,
+    );
+
+    env.set_auths(&[]);
+    assert!(pool
+        .try_update_config(&None, &Some(50), &None, &None, &None, &None)
+        .is_err());
+
+    env.ledger().with_mut(|li| li.timestamp = ONE_DAY);
+    timelock.execute(&operation_id);
+
+    assert_eq!(pool.query_config().total_fee_bps, 100);
+}
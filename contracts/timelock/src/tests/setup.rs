@@ -0,0 +1,140 @@
+use soroban_sdk::{testutils::Address as _, vec, Address, BytesN, Env, String};
+
+use crate::contract::{Timelock, TimelockClient};
+
+pub const ONE_DAY: u64 = 86_400;
+pub const ONE_WEEK: u64 = 7 * ONE_DAY;
+
+pub mod factory_contract {
+    soroban_sdk::contractimport!(
+        file = "../../target/wasm32-unknown-unknown/release/phoenix_factory.wasm"
+    );
+}
+
+pub mod xyk_pool {
+    soroban_sdk::contractimport!(
+        file = "../../target/wasm32-unknown-unknown/release/phoenix_pool.wasm"
+    );
+}
+
+pub mod token_contract {
+    soroban_sdk::contractimport!(
+        file = "../../target/wasm32-unknown-unknown/release/soroban_token_contract.wasm"
+    );
+}
+
+fn install_wasm(env: &Env, wasm: &[u8]) -> BytesN<32> {
+    env.deployer().upload_contract_wasm(wasm)
+}
+
+pub fn deploy_timelock_contract<'a>(
+    env: &Env,
+    admin: &Address,
+    min_delay: u64,
+    grace_period: u64,
+) -> TimelockClient<'a> {
+    TimelockClient::new(
+        env,
+        &env.register(Timelock, (admin, min_delay, grace_period)),
+    )
+}
+
+pub fn deploy_token_contract<'a>(env: &Env, admin: &Address) -> token_contract::Client<'a> {
+    token_contract::Client::new(
+        env,
+        &env.register(
+            token_contract::WASM,
+            (
+                admin,
+                7u32,
+                String::from_str(env, "Phoenix"),
+                String::from_str(env, "PHO"),
+            ),
+        ),
+    )
+}
+
+/// Deploys a factory governed by `admin`, with `creator` allowed to create pools
+pub fn deploy_factory_contract<'a>(
+    env: &Env,
+    admin: &Address,
+    creator: &Address,
+) -> factory_contract::Client<'a> {
+    let multihop_wasm_hash = install_wasm(
+        env,
+        include_bytes!("../../../../target/wasm32-unknown-unknown/release/phoenix_multihop.wasm"),
+    );
+    let stable_wasm_hash = install_wasm(
+        env,
+        include_bytes!(
+            "../../../../target/wasm32-unknown-unknown/release/phoenix_pool_stable.wasm"
+        ),
+    );
+    let stake_wasm_hash = install_wasm(
+        env,
+        include_bytes!("../../../../target/wasm32-unknown-unknown/release/phoenix_stake.wasm"),
+    );
+
+    factory_contract::Client::new(
+        env,
+        &env.register(
+            factory_contract::WASM,
+            (
+                admin,
+                &multihop_wasm_hash,
+                &install_wasm(env, xyk_pool::WASM),
+                &stable_wasm_hash,
+                &stake_wasm_hash,
+                &install_wasm(env, token_contract::WASM),
+                vec![env, creator.clone()],
+                &7u32,
+            ),
+        ),
+    )
+}
+
+/// Creates a XYK pool through the factory, administered by `admin`
+pub fn create_pool<'a>(
+    env: &Env,
+    factory: &factory_contract::Client,
+    creator: &Address,
+    admin: &Address,
+) -> xyk_pool::Client<'a> {
+    let mut token_a = deploy_token_contract(env, creator).address;
+    let mut token_b = deploy_token_contract(env, creator).address;
+    if token_b < token_a {
+        core::mem::swap(&mut token_a, &mut token_b);
+    }
+
+    let lp_init_info = factory_contract::LiquidityPoolInitInfo {
+        admin: admin.clone(),
+        fee_recipient: admin.clone(),
+        max_allowed_slippage_bps: 5_000,
+        default_slippage_bps: 2_500,
+        max_allowed_spread_bps: 500,
+        swap_fee_bps: 0,
+        max_referral_bps: 5_000,
+        token_init_info: factory_contract::TokenInitInfo { token_a, token_b },
+        stake_init_info: factory_contract::StakeInitInfo {
+            min_bond: 10,
+            min_reward: 10,
+            manager: Address::generate(env),
+            max_complexity: 10,
+        },
+    };
+
+    xyk_pool::Client::new(
+        env,
+        &factory.create_liquidity_pool(
+            creator,
+            &lp_init_info,
+            &String::from_str(env, "Pool"),
+            &String::from_str(env, "PHO/XLM"),
+            &factory_contract::PoolType::Xyk,
+            &None::<u64>,
+            &100i64,
+            &1_000,
+            &None,
+        ),
+    )
+}
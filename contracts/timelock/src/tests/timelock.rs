@@ -0,0 +1,152 @@
+use soroban_sdk::{
+    symbol_short,
+    testutils::{Address as _, Ledger},
+    vec, Address, Env, IntoVal, Symbol,
+};
+
+use super::setup::{deploy_timelock_contract, deploy_token_contract, ONE_DAY, ONE_WEEK};
+use crate::storage::{Config, Operation};
+
+#[test]
+fn queued_operation_executes_after_delay() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let admin = Address::generate(&env);
+    let user = Address::generate(&env);
+
+    let timelock = deploy_timelock_contract(&env, &admin, ONE_DAY, ONE_WEEK);
+    let token = deploy_token_contract(&env, &timelock.address);
+
+    let args = vec![&env, user.into_val(&env), 1_000i128.into_val(&env)];
+    let operation_id = timelock.queue(&token.address, &symbol_short!("mint"), &args, &ONE_DAY);
+    assert_eq!(operation_id, 0);
+    assert_eq!(
+        timelock.query_operation(&operation_id),
+        Some(Operation {
+            target: token.address.clone(),
+            function: symbol_short!("mint"),
+            args,
+            eta: ONE_DAY,
+        })
+    );
+
+    env.ledger().with_mut(|li| li.timestamp = ONE_DAY);
+    // no signature needed, the timelock itself is the token admin
+    env.set_auths(&[]);
+    timelock.execute(&operation_id);
+
+    assert_eq!(token.balance(&user), 1_000);
+    assert_eq!(timelock.query_operation(&operation_id), None);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #802)")]
+fn queue_with_delay_below_minimum_fails() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let admin = Address::generate(&env);
+    let timelock = deploy_timelock_contract(&env, &admin, ONE_DAY, ONE_WEEK);
+
+    timelock.queue(
+        &Address::generate(&env),
+        &Symbol::new(&env, "update_config"),
+        &vec![&env],
+        &(ONE_DAY - 1),
+    );
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #804)")]
+fn execute_before_eta_fails() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let admin = Address::generate(&env);
+    let timelock = deploy_timelock_contract(&env, &admin, ONE_DAY, ONE_WEEK);
+    let token = deploy_token_contract(&env, &timelock.address);
+
+    let args = vec![&env, admin.into_val(&env), 1_000i128.into_val(&env)];
+    let operation_id = timelock.queue(&token.address, &symbol_short!("mint"), &args, &ONE_DAY);
+
+    env.ledger().with_mut(|li| li.timestamp = ONE_DAY - 1);
+    timelock.execute(&operation_id);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #805)")]
+fn execute_after_grace_period_fails() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let admin = Address::generate(&env);
+    let timelock = deploy_timelock_contract(&env, &admin, ONE_DAY, ONE_WEEK);
+    let token = deploy_token_contract(&env, &timelock.address);
+
+    let args = vec![&env, admin.into_val(&env), 1_000i128.into_val(&env)];
+    let operation_id = timelock.queue(&token.address, &symbol_short!("mint"), &args, &ONE_DAY);
+
+    env.ledger()
+        .with_mut(|li| li.timestamp = ONE_DAY + ONE_WEEK + 1);
+    timelock.execute(&operation_id);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #803)")]
+fn cancelled_operation_cannot_be_executed() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let admin = Address::generate(&env);
+    let timelock = deploy_timelock_contract(&env, &admin, ONE_DAY, ONE_WEEK);
+    let token = deploy_token_contract(&env, &timelock.address);
+
+    let args = vec![&env, admin.into_val(&env), 1_000i128.into_val(&env)];
+    let operation_id = timelock.queue(&token.address, &symbol_short!("mint"), &args, &ONE_DAY);
+    timelock.cancel(&operation_id);
+    assert_eq!(timelock.query_operation(&operation_id), None);
+
+    env.ledger().with_mut(|li| li.timestamp = ONE_DAY);
+    timelock.execute(&operation_id);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #803)")]
+fn operation_cannot_be_executed_twice() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let admin = Address::generate(&env);
+    let timelock = deploy_timelock_contract(&env, &admin, ONE_DAY, ONE_WEEK);
+    let token = deploy_token_contract(&env, &timelock.address);
+
+    let args = vec![&env, admin.into_val(&env), 1_000i128.into_val(&env)];
+    let operation_id = timelock.queue(&token.address, &symbol_short!("mint"), &args, &ONE_DAY);
+
+    env.ledger().with_mut(|li| li.timestamp = ONE_DAY);
+    timelock.execute(&operation_id);
+    timelock.execute(&operation_id);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #801)")]
+fn initialize_without_grace_period_fails() {
+    let env = Env::default();
+    deploy_timelock_contract(&env, &Address::generate(&env), ONE_DAY, 0);
+}
+
+#[test]
+fn admin_change_and_config() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let admin = Address::generate(&env);
+    let new_admin = Address::generate(&env);
+    let timelock = deploy_timelock_contract(&env, &admin, ONE_DAY, ONE_WEEK);
+
+    assert_eq!(
+        timelock.query_config(),
+        Config {
+            min_delay: ONE_DAY,
+            grace_period: ONE_WEEK,
+        }
+    );
+
+    timelock.propose_admin(&new_admin, &None);
+    timelock.accept_admin();
+    assert_eq!(timelock.query_admin(), new_admin);
+}
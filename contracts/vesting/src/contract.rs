@@ -1,27 +1,44 @@
+use core::ops::Range;
+
+use curve::Curve;
 use phoenix::{
     ttl::{INSTANCE_RENEWAL_THRESHOLD, INSTANCE_TARGET_TTL},
     utils::{convert_i128_to_u128, convert_u128_to_i128, AdminChange},
 };
 use soroban_sdk::{
-    contract, contractimpl, contractmeta, log, panic_with_error, vec, Address, BytesN, Env, String,
-    Vec,
+    auth::{ContractContext, InvokerContractAuthEntry, SubContractInvocation},
+    contract, contractimpl, contractmeta, log, panic_with_error, vec, Address, BytesN, Env,
+    IntoVal, Map, String, Symbol, Vec,
 };
 
 use crate::storage::MinterInfo;
 #[cfg(feature = "minter")]
-use crate::storage::{get_minter, save_minter};
+use crate::storage::{
+    get_minter, get_minter_emission, save_minter, save_minter_emission, MinterEmission,
+};
 use crate::{
     error::ContractError,
+    stake_contract,
     storage::{
-        get_admin_old, get_config, get_max_vesting_complexity, get_token_info, get_vesting,
-        save_admin_old, save_config, save_max_vesting_complexity, save_token_info, save_vesting,
-        update_vesting, Config, DataKey, VestingCounterKey, VestingInfo, VestingInfoKey,
-        VestingInfoResponse, VestingSchedule, VestingTokenInfo, ADMIN, PENDING_ADMIN, VESTING_KEY,
+        find_vesting, find_vesting_token, get_admin_old, get_airdrop, get_config,
+        get_max_vesting_complexity, get_recipient, get_recipient_position, get_recipients_count,
+        get_stake_bonds, get_stake_whitelist, get_token_info, get_vesting, get_vesting_amendment,
+        get_vesting_bond, get_vesting_count, get_vesting_token, is_airdrop_claimed, is_revocable,
+        is_transferable, next_airdrop_id, remove_airdrop, remove_vesting, remove_vesting_amendment,
+        remove_vesting_bond, save_admin_old, save_airdrop, save_airdrop_claimed, save_config,
+        save_max_vesting_complexity, save_revocable, save_stake_bonds, save_stake_whitelist,
+        save_token_info, save_transferable, save_vesting, save_vesting_amendment,
+        save_vesting_bond, save_vesting_token, update_vesting, ClaimedVesting, Config, DataKey,
+        MerkleAirdrop, VestingAmendment, VestingBond, VestingCounterKey, VestingInfo,
+        VestingInfoKey, VestingInfoResponse, VestingSchedule, VestingTokenInfo, ADMIN,
+        PENDING_ADMIN, VESTING_KEY,
     },
     token_contract,
-    utils::{check_duplications, validate_vesting_schedule},
+    utils::{airdrop_leaf, check_duplications, validate_vesting_schedule, verify_merkle_proof},
 };
 
+const MAX_QUERY_LIMIT: u32 = 100;
+
 // Metadata that is added on to the WASM custom section
 contractmeta!(
     key = "Description",
@@ -36,9 +53,97 @@ pub trait VestingTrait {
 
     fn claim(env: Env, sender: Address, index: u64);
 
+    /// Claims from every schedule of `sender` with a single transfer and
+    /// returns the amount claimed per schedule index.
+    fn claim_all(env: Env, sender: Address) -> Vec<ClaimedVesting>;
+
+    /// Ends a revocable schedule: the already claimable amount is paid out to
+    /// the recipient and the unvested remainder is sent to `beneficiary`.
+    /// A bonded schedule is unbonded first, with its rewards paid out.
+    fn revoke_vesting(
+        env: Env,
+        recipient: Address,
+        index: u64,
+        beneficiary: Address,
+    ) -> Result<(), ContractError>;
+
+    /// Moves the whole remaining balance of a schedule, together with its
+    /// curve, to `new_recipient`. Returns the index under the new recipient.
+    fn transfer_vesting(env: Env, sender: Address, index: u64, new_recipient: Address) -> u64;
+
+    fn set_vesting_transferable(
+        env: Env,
+        recipient: Address,
+        index: u64,
+        transferable: bool,
+    ) -> Result<(), ContractError>;
+
+    fn update_stake_whitelist(
+        env: Env,
+        to_add: Vec<Address>,
+        to_remove: Vec<Address>,
+    ) -> Result<(), ContractError>;
+
+    /// Bonds `amount` of the unclaimed balance of a schedule into a whitelisted
+    /// stake contract. The stake is held by the vesting contract and the
+    /// schedule can't be claimed from until it's unbonded again.
+    fn bond_vesting(env: Env, sender: Address, index: u64, stake_contract: Address, amount: i128);
+
+    /// Unbonds the stake of a schedule and pays out its rewards.
+    fn unbond_vesting(env: Env, sender: Address, index: u64);
+
+    /// Pays out the staking rewards earned by the bonded part of a schedule.
+    fn withdraw_vesting_rewards(env: Env, sender: Address, index: u64);
+
+    /// Commits the root of a merkle tree of vesting schedules and funds them
+    /// with `total_amount` taken from the admin. Schedules can be claimed until
+    /// `end_time`. Returns the id of the airdrop.
+    fn create_airdrop(
+        env: Env,
+        merkle_root: BytesN<32>,
+        total_amount: u128,
+        end_time: u64,
+    ) -> Result<u64, ContractError>;
+
+    /// Creates the airdropped schedule of `recipient` on its first claim and
+    /// pays out whatever has vested already. Returns the index of the schedule,
+    /// later claims go through `claim`.
+    fn claim_with_proof(
+        env: Env,
+        recipient: Address,
+        airdrop_id: u64,
+        curve_params: Curve,
+        amount: u128,
+        proof: Vec<BytesN<32>>,
+    ) -> u64;
+
+    /// Returns the unclaimed funds of an airdrop to the admin once its end time
+    /// has passed and removes it. Returns the amount sent back.
+    fn close_airdrop(env: Env, airdrop_id: u64) -> Result<u128, ContractError>;
+
+    /// Proposes a new curve for a schedule. The remaining balance becomes the
+    /// total of the new curve once the recipient accepts it: a top up is taken
+    /// from the admin right away, a surplus is refunded on acceptance.
+    fn propose_vesting_amendment(
+        env: Env,
+        recipient: Address,
+        index: u64,
+        new_curve: Curve,
+    ) -> Result<(), ContractError>;
+
+    fn accept_vesting_amendment(env: Env, recipient: Address, index: u64);
+
+    /// Drops a pending amendment and returns its top up to the admin.
+    fn cancel_vesting_amendment(
+        env: Env,
+        recipient: Address,
+        index: u64,
+    ) -> Result<(), ContractError>;
+
     fn update(env: Env, new_wash_hash: BytesN<32>);
 
-    fn query_balance(env: Env, address: Address) -> i128;
+    /// Balance of `address` in `token`, the default vesting token when `None`.
+    fn query_balance(env: Env, address: Address, token: Option<Address>) -> i128;
 
     fn query_vesting_info(env: Env, address: Address, index: u64) -> VestingInfoResponse;
 
@@ -46,12 +151,66 @@ pub trait VestingTrait {
 
     fn query_token_info(env: Env) -> VestingTokenInfo;
 
-    fn query_vesting_contract_balance(env: Env) -> i128;
+    /// Balance of the contract in `token`, the default vesting token when `None`.
+    fn query_vesting_contract_balance(env: Env, token: Option<Address>) -> i128;
 
     fn query_available_to_claim(env: Env, address: Address, index: u64) -> i128;
 
+    fn query_stake_whitelist(env: Env) -> Vec<Address>;
+
+    fn query_vesting_bond(env: Env, address: Address, index: u64) -> Option<VestingBond>;
+
+    fn query_airdrop(env: Env, airdrop_id: u64) -> Option<MerkleAirdrop>;
+
+    fn query_airdrop_claimed(env: Env, airdrop_id: u64, address: Address) -> bool;
+
+    fn query_vesting_amendment(env: Env, address: Address, index: u64) -> Option<VestingAmendment>;
+
+    /// Amount still locked by the curves of the schedules vesting `token`, the
+    /// default vesting token when `None`. Covers the schedules of at most
+    /// `limit` recipients (capped at 100) following `start_after`, as listed by
+    /// `query_recipients`; totals over all schedules are the sum of the pages.
+    fn query_total_locked(
+        env: Env,
+        token: Option<Address>,
+        start_after: Option<Address>,
+        limit: u32,
+    ) -> i128;
+
+    /// Amount of `token` that would be claimable at `timestamp`, assuming
+    /// nothing else is claimed until then. Paginated like `query_total_locked`.
+    fn query_total_claimable_at(
+        env: Env,
+        timestamp: u64,
+        token: Option<Address>,
+        start_after: Option<Address>,
+        limit: u32,
+    ) -> i128;
+
+    /// Lists the addresses with vesting schedules, at most `limit` of them
+    /// (capped at 100) following `start_after`.
+    fn query_recipients(env: Env, start_after: Option<Address>, limit: u32) -> Vec<Address>;
+
+    /// Combines the curves of the schedules vesting `token` into one, giving
+    /// the amount still locked at any timestamp from now on. Paginated like
+    /// `query_total_locked`, the calendar of all schedules combines the pages.
+    fn query_unlock_calendar(
+        env: Env,
+        token: Option<Address>,
+        start_after: Option<Address>,
+        limit: u32,
+    ) -> Curve;
+
     fn update_vesting_token(env: Env, new_token_address: Address) -> Result<(), ContractError>;
 
+    /// Saves the current vesting token as the token of schedules created
+    /// before every schedule kept its own token. Has to be done before the
+    /// vesting token is updated, such schedules can't be used until then.
+    fn migrate_vesting_tokens(
+        env: Env,
+        schedules: Vec<VestingInfoKey>,
+    ) -> Result<(), ContractError>;
+
     fn update_max_complexity(env: Env, new_max_complexity: u32) -> Result<(), ContractError>;
     fn query_config(env: Env) -> Config;
 
@@ -90,7 +249,8 @@ impl VestingTrait for Vesting {
         check_duplications(&env, vesting_schedules.clone());
         let max_vesting_complexity = get_max_vesting_complexity(&env);
 
-        let mut total_vested_amount: u128 = 0;
+        let default_token = get_token_info(&env).address;
+        let mut total_vested_amounts: Map<Address, u128> = Map::new(&env);
 
         vesting_schedules.into_iter().for_each(|vesting_schedule| {
             let vested_amount = validate_vesting_schedule(&env, &vesting_schedule.curve)
@@ -105,105 +265,829 @@ impl VestingTrait for Vesting {
                 panic_with_error!(env, ContractError::VestingComplexityTooHigh);
             }
 
-            save_vesting(
+            let index = save_vesting(
                 &env,
                 &vesting_schedule.recipient.clone(),
                 VestingInfo {
                     balance: vested_amount,
-                    recipient: vesting_schedule.recipient,
+                    recipient: vesting_schedule.recipient.clone(),
                     schedule: vesting_schedule.curve.clone(),
                 },
             );
-
-            total_vested_amount = total_vested_amount
+            if vesting_schedule.revocable.unwrap_or(false) {
+                save_revocable(&env, &vesting_schedule.recipient, index, true);
+            }
+            let token = vesting_schedule.token.unwrap_or(default_token.clone());
+            save_vesting_token(&env, &vesting_schedule.recipient, index, &token);
+            let total_vested_amount = total_vested_amounts
+                .get(token.clone())
+                .unwrap_or(0)
                 .checked_add(vested_amount)
                 .unwrap_or_else(|| {
                     log!(&env, "Vesting: Create Vesting Schedule: overflow ocurred.");
                     panic_with_error!(&env, ContractError::ContractMathError);
                 });
+            total_vested_amounts.set(token, total_vested_amount);
         });
 
-        // check if the admin has enough tokens to start the vesting contract
-        let vesting_token = get_token_info(&env);
-        let token_client = token_contract::Client::new(&env, &vesting_token.address);
+        for (token, total_vested_amount) in total_vested_amounts.iter() {
+            // check if the admin has enough tokens to start the vesting contract
+            let token_client = token_contract::Client::new(&env, &token);
+
+            if token_client.balance(&admin) < convert_u128_to_i128(total_vested_amount) {
+                log!(
+                    &env,
+                    "Vesting: Create vesting account: Admin does not have enough tokens to start the vesting schedule"
+                );
+                panic_with_error!(env, ContractError::NoEnoughtTokensToStart);
+            }
+
+            token_client.transfer(
+                &admin,
+                &env.current_contract_address(),
+                &convert_u128_to_i128(total_vested_amount),
+            );
+        }
+    }
+
+    fn claim(env: Env, sender: Address, index: u64) {
+        sender.require_auth();
+        env.storage()
+            .instance()
+            .extend_ttl(INSTANCE_RENEWAL_THRESHOLD, INSTANCE_TARGET_TTL);
+
+        ensure_not_bonded(&env, &sender, index);
+
+        let available_to_claim = Self::query_available_to_claim(env.clone(), sender.clone(), index);
+
+        if available_to_claim <= 0 {
+            log!(&env, "Vesting: Claim: No tokens available to claim");
+            panic_with_error!(env, ContractError::NeverFullyVested);
+        }
+
+        let token_client =
+            token_contract::Client::new(&env, &get_vesting_token(&env, &sender, index));
+
+        let vesting_info = get_vesting(&env, &sender, index);
+        let vested = vesting_info.schedule.value(env.ledger().timestamp());
+
+        let sender_balance = vesting_info.balance;
+        let sender_liquid = sender_balance // this checks if we can withdraw any vesting
+            .checked_sub(vested)
+            .unwrap_or_else(|| panic_with_error!(env, ContractError::NotEnoughBalance));
+
+        if sender_liquid < convert_i128_to_u128(available_to_claim) {
+            log!(
+            &env,
+            "Vesting: Verify Vesting Update Balances: Remaining amount must be at least equal to vested amount"
+        );
+            panic_with_error!(env, ContractError::CantMoveVestingTokens);
+        }
+
+        let updated_balance = sender_balance
+            .checked_sub(convert_i128_to_u128(available_to_claim))
+            .unwrap_or_else(|| {
+                log!(&env, "Vesting: Claim: underflow occured");
+                panic_with_error!(&env, ContractError::ContractMathError);
+            });
+        update_vesting(
+            &env,
+            &sender,
+            index,
+            &VestingInfo {
+                balance: updated_balance,
+                ..vesting_info
+            },
+        );
+
+        token_client.transfer(
+            &env.current_contract_address(),
+            &sender,
+            &(available_to_claim),
+        );
+
+        env.events()
+            .publish(("Claim", "Claimed tokens: "), available_to_claim);
+    }
+
+    fn claim_all(env: Env, sender: Address) -> Vec<ClaimedVesting> {
+        sender.require_auth();
+        env.storage()
+            .instance()
+            .extend_ttl(INSTANCE_RENEWAL_THRESHOLD, INSTANCE_TARGET_TTL);
+
+        let now = env.ledger().timestamp();
+        let mut claimed = vec![&env];
+        let mut total_claimed: Map<Address, u128> = Map::new(&env);
+
+        for index in 0..get_vesting_count(&env, &sender) {
+            // revoked schedules are gone
+            let Some(vesting_info) = find_vesting(&env, &sender, index) else {
+                continue;
+            };
+            // bonded schedules have to be unbonded first
+            if get_vesting_bond(&env, &sender, index).is_some() {
+                continue;
+            }
+
+            let available_to_claim = vesting_info
+                .balance
+                .saturating_sub(vesting_info.schedule.value(now));
+            if available_to_claim == 0 {
+                continue;
+            }
+
+            update_vesting(
+                &env,
+                &sender,
+                index,
+                &VestingInfo {
+                    balance: vesting_info.balance - available_to_claim,
+                    ..vesting_info
+                },
+            );
+
+            let token = get_vesting_token(&env, &sender, index);
+            let token_total = total_claimed
+                .get(token.clone())
+                .unwrap_or(0)
+                .checked_add(available_to_claim)
+                .unwrap_or_else(|| {
+                    log!(&env, "Vesting: Claim All: overflow occured");
+                    panic_with_error!(&env, ContractError::ContractMathError);
+                });
+            total_claimed.set(token.clone(), token_total);
+            claimed.push_back(ClaimedVesting {
+                index,
+                amount: convert_u128_to_i128(available_to_claim),
+                token,
+            });
+        }
+
+        if total_claimed.is_empty() {
+            log!(&env, "Vesting: Claim All: No tokens available to claim");
+            panic_with_error!(env, ContractError::NeverFullyVested);
+        }
+
+        // a single transfer per token
+        for (token, amount) in total_claimed.iter() {
+            token_contract::Client::new(&env, &token).transfer(
+                &env.current_contract_address(),
+                &sender,
+                &convert_u128_to_i128(amount),
+            );
+
+            env.events().publish(
+                ("Claim all", "Claimed tokens: "),
+                (token, convert_u128_to_i128(amount)),
+            );
+        }
+
+        claimed
+    }
+
+    fn transfer_vesting(env: Env, sender: Address, index: u64, new_recipient: Address) -> u64 {
+        sender.require_auth();
+        env.storage()
+            .instance()
+            .extend_ttl(INSTANCE_RENEWAL_THRESHOLD, INSTANCE_TARGET_TTL);
+
+        if sender == new_recipient {
+            log!(&env, "Vesting: Transfer Vesting: Cannot transfer to self");
+            panic_with_error!(&env, ContractError::VestingTransferToSelf);
+        }
+
+        let vesting_info = get_vesting(&env, &sender, index);
+        ensure_not_bonded(&env, &sender, index);
+        if !is_transferable(&env, &sender, index) {
+            log!(
+                &env,
+                "Vesting: Transfer Vesting: Vesting schedule is not transferable"
+            );
+            panic_with_error!(&env, ContractError::VestingNotTransferable);
+        }
+        let revocable = is_revocable(&env, &sender, index);
+        let token = get_vesting_token(&env, &sender, index);
+
+        // the amendment was proposed to the old owner, it doesn't carry over
+        drop_pending_amendment(&env, &get_admin_old(&env), &sender, index);
+        remove_vesting(&env, &sender, index);
+        let new_index = save_vesting(
+            &env,
+            &new_recipient,
+            VestingInfo {
+                recipient: new_recipient.clone(),
+                ..vesting_info
+            },
+        );
+        // the schedule keeps its flags with the new owner
+        save_transferable(&env, &new_recipient, new_index, true);
+        if revocable {
+            save_revocable(&env, &new_recipient, new_index, true);
+        }
+        save_vesting_token(&env, &new_recipient, new_index, &token);
+
+        env.events()
+            .publish(("Transfer vesting", "from: "), (sender, index));
+        env.events()
+            .publish(("Transfer vesting", "to: "), (new_recipient, new_index));
+
+        new_index
+    }
+
+    fn set_vesting_transferable(
+        env: Env,
+        recipient: Address,
+        index: u64,
+        transferable: bool,
+    ) -> Result<(), ContractError> {
+        get_admin_old(&env).require_auth();
+        env.storage()
+            .instance()
+            .extend_ttl(INSTANCE_RENEWAL_THRESHOLD, INSTANCE_TARGET_TTL);
+
+        // make sure the schedule exists
+        get_vesting(&env, &recipient, index);
+        save_transferable(&env, &recipient, index, transferable);
+
+        env.events().publish(
+            ("Vesting: Set Transferable", "schedule: "),
+            (recipient, index, transferable),
+        );
+
+        Ok(())
+    }
+
+    fn revoke_vesting(
+        env: Env,
+        recipient: Address,
+        index: u64,
+        beneficiary: Address,
+    ) -> Result<(), ContractError> {
+        get_admin_old(&env).require_auth();
+        env.storage()
+            .instance()
+            .extend_ttl(INSTANCE_RENEWAL_THRESHOLD, INSTANCE_TARGET_TTL);
+
+        let vesting_info = get_vesting(&env, &recipient, index);
+        if !is_revocable(&env, &recipient, index) {
+            log!(&env, "Vesting: Revoke: Vesting schedule is not revocable");
+            panic_with_error!(&env, ContractError::VestingNotRevocable);
+        }
+
+        // the bonded part is unbonded first, revoking can't depend on the
+        // recipient unbonding
+        if get_vesting_bond(&env, &recipient, index).is_some() {
+            unbond_from_stake(&env, &recipient, index);
+        }
+
+        // whatever is still locked by the curve has not vested yet
+        let unvested = vesting_info
+            .schedule
+            .value(env.ledger().timestamp())
+            .min(vesting_info.balance);
+        let claimable = vesting_info.balance - unvested;
+        let token_client =
+            token_contract::Client::new(&env, &get_vesting_token(&env, &recipient, index));
+
+        drop_pending_amendment(&env, &get_admin_old(&env), &recipient, index);
+        remove_vesting(&env, &recipient, index);
+
+        if claimable > 0 {
+            token_client.transfer(
+                &env.current_contract_address(),
+                &recipient,
+                &convert_u128_to_i128(claimable),
+            );
+        }
+        if unvested > 0 {
+            token_client.transfer(
+                &env.current_contract_address(),
+                &beneficiary,
+                &convert_u128_to_i128(unvested),
+            );
+        }
+
+        env.events()
+            .publish(("Revoke", "Revoked vesting of: "), (recipient, index));
+        env.events()
+            .publish(("Revoke", "Paid out to recipient: "), claimable);
+        env.events().publish(
+            ("Revoke", "Returned to beneficiary: "),
+            (beneficiary, unvested),
+        );
+
+        Ok(())
+    }
+
+    fn update_stake_whitelist(
+        env: Env,
+        to_add: Vec<Address>,
+        to_remove: Vec<Address>,
+    ) -> Result<(), ContractError> {
+        get_admin_old(&env).require_auth();
+        env.storage()
+            .instance()
+            .extend_ttl(INSTANCE_RENEWAL_THRESHOLD, INSTANCE_TARGET_TTL);
+
+        let mut whitelist = get_stake_whitelist(&env);
+        for stake in to_add.iter() {
+            if !whitelist.contains(&stake) {
+                whitelist.push_back(stake);
+            }
+        }
+        for stake in to_remove.iter() {
+            if let Some(position) = whitelist.first_index_of(&stake) {
+                whitelist.remove(position);
+            }
+        }
+        save_stake_whitelist(&env, &whitelist);
+
+        env.events()
+            .publish(("Vesting: Update Stake Whitelist", "added: "), to_add);
+        env.events()
+            .publish(("Vesting: Update Stake Whitelist", "removed: "), to_remove);
+
+        Ok(())
+    }
+
+    fn bond_vesting(env: Env, sender: Address, index: u64, stake_contract: Address, amount: i128) {
+        sender.require_auth();
+        env.storage()
+            .instance()
+            .extend_ttl(INSTANCE_RENEWAL_THRESHOLD, INSTANCE_TARGET_TTL);
+
+        if !get_stake_whitelist(&env).contains(&stake_contract) {
+            log!(&env, "Vesting: Bond: Stake contract is not whitelisted");
+            panic_with_error!(&env, ContractError::StakeNotWhitelisted);
+        }
+
+        let vesting_info = get_vesting(&env, &sender, index);
+        if let Some(bond) = get_vesting_bond(&env, &sender, index) {
+            if bond.stake_contract != stake_contract {
+                log!(
+                    &env,
+                    "Vesting: Bond: Schedule is already bonded in another stake contract"
+                );
+                panic_with_error!(&env, ContractError::VestingBondedElsewhere);
+            }
+            log!(
+                &env,
+                "Vesting: Bond: Schedule is already bonded and must be unbonded first"
+            );
+            panic_with_error!(&env, ContractError::VestingStaked);
+        }
+
+        if amount <= 0 || convert_i128_to_u128(amount) > vesting_info.balance {
+            log!(
+                &env,
+                "Vesting: Bond: Amount must be positive and within the unclaimed balance"
+            );
+            panic_with_error!(&env, ContractError::InvalidBondAmount);
+        }
+
+        let vesting_token = get_vesting_token(&env, &sender, index);
+        let stake_client = stake_contract::Client::new(&env, &stake_contract);
+        if stake_client.query_config().config.lp_token != vesting_token {
+            log!(
+                &env,
+                "Vesting: Bond: Stake contract doesn't stake the vesting token"
+            );
+            panic_with_error!(&env, ContractError::StakeTokenMismatch);
+        }
+
+        env.authorize_as_current_contract(vec![
+            &env,
+            InvokerContractAuthEntry::Contract(SubContractInvocation {
+                context: ContractContext {
+                    contract: vesting_token,
+                    fn_name: Symbol::new(&env, "transfer"),
+                    args: (
+                        env.current_contract_address(),
+                        stake_contract.clone(),
+                        amount,
+                    )
+                        .into_val(&env),
+                },
+                sub_invocations: vec![&env],
+            }),
+        ]);
+        stake_client.bond(&env.current_contract_address(), &amount);
+
+        // the schedule owns the stake just made, its age counts from now
+        save_vesting_bond(
+            &env,
+            &sender,
+            index,
+            &VestingBond {
+                stake_contract: stake_contract.clone(),
+                amount: convert_i128_to_u128(amount),
+                stake_timestamp: env.ledger().timestamp(),
+                unpaid_rewards: Map::new(&env),
+            },
+        );
+        let mut bonds = get_stake_bonds(&env, &stake_contract);
+        bonds.push_back(VestingInfoKey {
+            recipient: sender.clone(),
+            index,
+        });
+        save_stake_bonds(&env, &stake_contract, &bonds);
+
+        env.events()
+            .publish(("Bond vesting", "recipient: "), (sender, index));
+        env.events()
+            .publish(("Bond vesting", "stake: "), (stake_contract, amount));
+    }
+
+    fn unbond_vesting(env: Env, sender: Address, index: u64) {
+        sender.require_auth();
+        env.storage()
+            .instance()
+            .extend_ttl(INSTANCE_RENEWAL_THRESHOLD, INSTANCE_TARGET_TTL);
+
+        get_bond_or_panic(&env, &sender, index);
+        unbond_from_stake(&env, &sender, index);
+    }
+
+    fn withdraw_vesting_rewards(env: Env, sender: Address, index: u64) {
+        sender.require_auth();
+        env.storage()
+            .instance()
+            .extend_ttl(INSTANCE_RENEWAL_THRESHOLD, INSTANCE_TARGET_TTL);
+
+        let bond = get_bond_or_panic(&env, &sender, index);
+        settle_stake_rewards(&env, &bond.stake_contract);
+
+        let mut bond = get_bond_or_panic(&env, &sender, index);
+        pay_bond_rewards(&env, &sender, &mut bond);
+        save_vesting_bond(&env, &sender, index, &bond);
+    }
+
+    fn create_airdrop(
+        env: Env,
+        merkle_root: BytesN<32>,
+        total_amount: u128,
+        end_time: u64,
+    ) -> Result<u64, ContractError> {
+        let admin = get_admin_old(&env);
+        admin.require_auth();
+        env.storage()
+            .instance()
+            .extend_ttl(INSTANCE_RENEWAL_THRESHOLD, INSTANCE_TARGET_TTL);
+
+        if end_time <= env.ledger().timestamp() {
+            log!(
+                &env,
+                "Vesting: Create Airdrop: End time must be in the future"
+            );
+            panic_with_error!(&env, ContractError::AirdropEnded);
+        }
+
+        if total_amount == 0 {
+            log!(
+                &env,
+                "Vesting: Create Airdrop: Total amount must be greater than 0"
+            );
+            panic_with_error!(&env, ContractError::MissingBalance);
+        }
+
+        let token = get_token_info(&env).address;
+        let token_client = token_contract::Client::new(&env, &token);
+        if token_client.balance(&admin) < convert_u128_to_i128(total_amount) {
+            log!(
+                &env,
+                "Vesting: Create Airdrop: Admin does not have enough tokens to fund the airdrop"
+            );
+            panic_with_error!(&env, ContractError::NoEnoughtTokensToStart);
+        }
+
+        token_client.transfer(
+            &admin,
+            &env.current_contract_address(),
+            &convert_u128_to_i128(total_amount),
+        );
+
+        let airdrop_id = next_airdrop_id(&env);
+        save_airdrop(
+            &env,
+            airdrop_id,
+            &MerkleAirdrop {
+                merkle_root: merkle_root.clone(),
+                total_amount,
+                claimed_amount: 0,
+                end_time,
+                token,
+            },
+        );
+
+        env.events()
+            .publish(("Create airdrop", "airdrop id: "), airdrop_id);
+        env.events()
+            .publish(("Create airdrop", "merkle root: "), merkle_root);
+        env.events()
+            .publish(("Create airdrop", "total amount: "), total_amount);
+
+        Ok(airdrop_id)
+    }
+
+    fn claim_with_proof(
+        env: Env,
+        recipient: Address,
+        airdrop_id: u64,
+        curve_params: Curve,
+        amount: u128,
+        proof: Vec<BytesN<32>>,
+    ) -> u64 {
+        recipient.require_auth();
+        env.storage()
+            .instance()
+            .extend_ttl(INSTANCE_RENEWAL_THRESHOLD, INSTANCE_TARGET_TTL);
+
+        let mut airdrop = get_airdrop(&env, airdrop_id).unwrap_or_else(|| {
+            log!(&env, "Vesting: Claim With Proof: No airdrop found");
+            panic_with_error!(&env, ContractError::NoAirdropFound);
+        });
+
+        if env.ledger().timestamp() >= airdrop.end_time {
+            log!(&env, "Vesting: Claim With Proof: Airdrop has ended");
+            panic_with_error!(&env, ContractError::AirdropEnded);
+        }
+
+        if is_airdrop_claimed(&env, airdrop_id, &recipient) {
+            log!(
+                &env,
+                "Vesting: Claim With Proof: Airdrop already claimed by {}",
+                recipient
+            );
+            panic_with_error!(&env, ContractError::AirdropAlreadyClaimed);
+        }
+
+        let leaf = airdrop_leaf(&env, &recipient, &curve_params, amount);
+        if !verify_merkle_proof(&env, leaf, &proof, &airdrop.merkle_root) {
+            log!(&env, "Vesting: Claim With Proof: Invalid merkle proof");
+            panic_with_error!(&env, ContractError::InvalidMerkleProof);
+        }
+
+        let vested_amount =
+            validate_vesting_schedule(&env, &curve_params).expect("Invalid curve and amount");
+        if vested_amount != amount {
+            log!(
+                &env,
+                "Vesting: Claim With Proof: Amount doesn't match the vesting curve"
+            );
+            panic_with_error!(&env, ContractError::InvalidTransferAmount);
+        }
+
+        if get_max_vesting_complexity(&env) <= curve_params.size() {
+            log!(
+                &env,
+                "Vesting: Claim With Proof: Invalid curve complexity for {}",
+                recipient
+            );
+            panic_with_error!(&env, ContractError::VestingComplexityTooHigh);
+        }
+
+        airdrop.claimed_amount = airdrop
+            .claimed_amount
+            .checked_add(amount)
+            .filter(|claimed| *claimed <= airdrop.total_amount)
+            .unwrap_or_else(|| {
+                log!(
+                    &env,
+                    "Vesting: Claim With Proof: Airdrop funding is exhausted"
+                );
+                panic_with_error!(&env, ContractError::TotalVestedOverCapacity);
+            });
+        save_airdrop(&env, airdrop_id, &airdrop);
+        save_airdrop_claimed(&env, airdrop_id, &recipient);
+
+        let available_to_claim =
+            amount.saturating_sub(curve_params.value(env.ledger().timestamp()));
+        let index = save_vesting(
+            &env,
+            &recipient,
+            VestingInfo {
+                balance: amount - available_to_claim,
+                recipient: recipient.clone(),
+                schedule: curve_params,
+            },
+        );
+        save_vesting_token(&env, &recipient, index, &airdrop.token);
+
+        if available_to_claim > 0 {
+            let token_client = token_contract::Client::new(&env, &airdrop.token);
+            token_client.transfer(
+                &env.current_contract_address(),
+                &recipient,
+                &convert_u128_to_i128(available_to_claim),
+            );
+        }
+
+        env.events()
+            .publish(("Claim with proof", "recipient: "), (recipient, index));
+        env.events().publish(
+            ("Claim with proof", "Claimed tokens: "),
+            convert_u128_to_i128(available_to_claim),
+        );
+
+        index
+    }
+
+    fn close_airdrop(env: Env, airdrop_id: u64) -> Result<u128, ContractError> {
+        let admin = get_admin_old(&env);
+        admin.require_auth();
+        env.storage()
+            .instance()
+            .extend_ttl(INSTANCE_RENEWAL_THRESHOLD, INSTANCE_TARGET_TTL);
+
+        let airdrop = get_airdrop(&env, airdrop_id).unwrap_or_else(|| {
+            log!(&env, "Vesting: Close Airdrop: No airdrop found");
+            panic_with_error!(&env, ContractError::NoAirdropFound);
+        });
+
+        if env.ledger().timestamp() < airdrop.end_time {
+            log!(&env, "Vesting: Close Airdrop: Airdrop has not ended yet");
+            panic_with_error!(&env, ContractError::AirdropNotEnded);
+        }
+
+        let unclaimed_amount = airdrop.total_amount - airdrop.claimed_amount;
+        if unclaimed_amount > 0 {
+            let token_client = token_contract::Client::new(&env, &airdrop.token);
+            token_client.transfer(
+                &env.current_contract_address(),
+                &admin,
+                &convert_u128_to_i128(unclaimed_amount),
+            );
+        }
+
+        // the claimed flags stay behind, so a leaf can't be claimed twice
+        remove_airdrop(&env, airdrop_id);
+
+        env.events()
+            .publish(("Close airdrop", "airdrop id: "), airdrop_id);
+        env.events()
+            .publish(("Close airdrop", "unclaimed amount: "), unclaimed_amount);
+
+        Ok(unclaimed_amount)
+    }
+
+    fn propose_vesting_amendment(
+        env: Env,
+        recipient: Address,
+        index: u64,
+        new_curve: Curve,
+    ) -> Result<(), ContractError> {
+        let admin = get_admin_old(&env);
+        admin.require_auth();
+        env.storage()
+            .instance()
+            .extend_ttl(INSTANCE_RENEWAL_THRESHOLD, INSTANCE_TARGET_TTL);
+
+        let vesting_info = get_vesting(&env, &recipient, index);
+        ensure_not_bonded(&env, &recipient, index);
 
-        if token_client.balance(&admin) < convert_u128_to_i128(total_vested_amount) {
+        let new_total =
+            validate_vesting_schedule(&env, &new_curve).expect("Invalid curve and amount");
+        if get_max_vesting_complexity(&env) <= new_curve.size() {
             log!(
                 &env,
-                "Vesting: Create vesting account: Admin does not have enough tokens to start the vesting schedule"
+                "Vesting: Propose Amendment: Invalid curve complexity for {}",
+                recipient
             );
-            panic_with_error!(env, ContractError::NoEnoughtTokensToStart);
+            panic_with_error!(env, ContractError::VestingComplexityTooHigh);
         }
 
-        token_client.transfer(
-            &admin,
-            &env.current_contract_address(),
-            &convert_u128_to_i128(total_vested_amount),
+        let token = get_vesting_token(&env, &recipient, index);
+        let token_client = token_contract::Client::new(&env, &token);
+
+        // a newer proposal replaces the pending one
+        if let Some(pending) = get_vesting_amendment(&env, &recipient, index) {
+            if pending.top_up > 0 {
+                token_contract::Client::new(&env, &pending.token).transfer(
+                    &env.current_contract_address(),
+                    &admin,
+                    &convert_u128_to_i128(pending.top_up),
+                );
+            }
+        }
+
+        let top_up = new_total.saturating_sub(vesting_info.balance);
+        if top_up > 0 {
+            if token_client.balance(&admin) < convert_u128_to_i128(top_up) {
+                log!(
+                    &env,
+                    "Vesting: Propose Amendment: Admin does not have enough tokens to top up the schedule"
+                );
+                panic_with_error!(env, ContractError::NoEnoughtTokensToStart);
+            }
+            token_client.transfer(
+                &admin,
+                &env.current_contract_address(),
+                &convert_u128_to_i128(top_up),
+            );
+        }
+
+        save_vesting_amendment(
+            &env,
+            &recipient,
+            index,
+            &VestingAmendment {
+                curve: new_curve,
+                balance: vesting_info.balance,
+                token,
+                top_up,
+            },
         );
+
+        env.events()
+            .publish(("Propose amendment", "schedule: "), (recipient, index));
+        env.events()
+            .publish(("Propose amendment", "new total: "), new_total);
+
+        Ok(())
     }
 
-    fn claim(env: Env, sender: Address, index: u64) {
-        sender.require_auth();
+    fn accept_vesting_amendment(env: Env, recipient: Address, index: u64) {
+        recipient.require_auth();
         env.storage()
             .instance()
             .extend_ttl(INSTANCE_RENEWAL_THRESHOLD, INSTANCE_TARGET_TTL);
 
-        let available_to_claim = Self::query_available_to_claim(env.clone(), sender.clone(), index);
-
-        if available_to_claim <= 0 {
-            log!(&env, "Vesting: Claim: No tokens available to claim");
-            panic_with_error!(env, ContractError::NeverFullyVested);
-        }
-
-        let token_client = token_contract::Client::new(&env, &get_token_info(&env).address);
-
-        let vesting_info = get_vesting(&env, &sender, index);
-        let vested = vesting_info.schedule.value(env.ledger().timestamp());
+        let amendment = get_vesting_amendment(&env, &recipient, index).unwrap_or_else(|| {
+            log!(&env, "Vesting: Accept Amendment: No amendment found");
+            panic_with_error!(&env, ContractError::NoAmendmentFound);
+        });
 
-        let sender_balance = vesting_info.balance;
-        let sender_liquid = sender_balance // this checks if we can withdraw any vesting
-            .checked_sub(vested)
-            .unwrap_or_else(|| panic_with_error!(env, ContractError::NotEnoughBalance));
+        let vesting_info = get_vesting(&env, &recipient, index);
+        ensure_not_bonded(&env, &recipient, index);
 
-        if sender_liquid < convert_i128_to_u128(available_to_claim) {
+        // the top up was computed against the balance at proposal time
+        if vesting_info.balance != amendment.balance {
             log!(
-            &env,
-            "Vesting: Verify Vesting Update Balances: Remaining amount must be at least equal to vested amount"
-        );
-            panic_with_error!(env, ContractError::CantMoveVestingTokens);
+                &env,
+                "Vesting: Accept Amendment: Balance changed since the amendment was proposed"
+            );
+            panic_with_error!(&env, ContractError::AmendmentOutdated);
+        }
+
+        let new_total =
+            validate_vesting_schedule(&env, &amendment.curve).expect("Invalid curve and amount");
+        let refund = vesting_info.balance.saturating_sub(new_total);
+        if refund > 0 {
+            let token_client = token_contract::Client::new(&env, &amendment.token);
+            token_client.transfer(
+                &env.current_contract_address(),
+                &get_admin_old(&env),
+                &convert_u128_to_i128(refund),
+            );
         }
 
-        let updated_balance = sender_balance
-            .checked_sub(convert_i128_to_u128(available_to_claim))
-            .unwrap_or_else(|| {
-                log!(&env, "Vesting: Claim: underflow occured");
-                panic_with_error!(&env, ContractError::ContractMathError);
-            });
         update_vesting(
             &env,
-            &sender,
+            &recipient,
             index,
             &VestingInfo {
-                balance: updated_balance,
+                balance: new_total,
+                schedule: amendment.curve,
                 ..vesting_info
             },
         );
+        remove_vesting_amendment(&env, &recipient, index);
 
-        token_client.transfer(
-            &env.current_contract_address(),
-            &sender,
-            &(available_to_claim),
-        );
+        env.events()
+            .publish(("Accept amendment", "schedule: "), (recipient, index));
+        env.events()
+            .publish(("Accept amendment", "new total: "), new_total);
+    }
+
+    fn cancel_vesting_amendment(
+        env: Env,
+        recipient: Address,
+        index: u64,
+    ) -> Result<(), ContractError> {
+        let admin = get_admin_old(&env);
+        admin.require_auth();
+        env.storage()
+            .instance()
+            .extend_ttl(INSTANCE_RENEWAL_THRESHOLD, INSTANCE_TARGET_TTL);
+
+        if !drop_pending_amendment(&env, &admin, &recipient, index) {
+            log!(&env, "Vesting: Cancel Amendment: No amendment found");
+            panic_with_error!(&env, ContractError::NoAmendmentFound);
+        }
 
         env.events()
-            .publish(("Claim", "Claimed tokens: "), available_to_claim);
+            .publish(("Cancel amendment", "schedule: "), (recipient, index));
+
+        Ok(())
     }
 
-    fn query_balance(env: Env, address: Address) -> i128 {
+    fn query_balance(env: Env, address: Address, token: Option<Address>) -> i128 {
         env.storage()
             .instance()
             .extend_ttl(INSTANCE_RENEWAL_THRESHOLD, INSTANCE_TARGET_TTL);
-        token_contract::Client::new(&env, &get_token_info(&env).address).balance(&address)
+        let token_address = token.unwrap_or_else(|| get_token_info(&env).address);
+        token_contract::Client::new(&env, &token_address).balance(&address)
     }
 
     fn query_vesting_info(env: Env, address: Address, index: u64) -> VestingInfoResponse {
@@ -216,6 +1100,9 @@ impl VestingTrait for Vesting {
             recipient: vesting_info.recipient,
             schedule: vesting_info.schedule,
             index, // use the query parameter index
+            revocable: is_revocable(&env, &address, index),
+            transferable: is_transferable(&env, &address, index),
+            token: get_vesting_token(&env, &address, index),
         }
     }
 
@@ -242,6 +1129,9 @@ impl VestingTrait for Vesting {
                     recipient: vesting.recipient,
                     schedule: vesting.schedule,
                     index: i,
+                    revocable: is_revocable(&env, &address, i),
+                    transferable: is_transferable(&env, &address, i),
+                    token: get_vesting_token(&env, &address, i),
                 });
             }
         }
@@ -271,11 +1161,11 @@ impl VestingTrait for Vesting {
         get_admin_old(&env)
     }
 
-    fn query_vesting_contract_balance(env: Env) -> i128 {
+    fn query_vesting_contract_balance(env: Env, token: Option<Address>) -> i128 {
         env.storage()
             .instance()
             .extend_ttl(INSTANCE_RENEWAL_THRESHOLD, INSTANCE_TARGET_TTL);
-        let token_address = get_token_info(&env).address;
+        let token_address = token.unwrap_or_else(|| get_token_info(&env).address);
         token_contract::Client::new(&env, &token_address).balance(&env.current_contract_address())
     }
 
@@ -284,6 +1174,9 @@ impl VestingTrait for Vesting {
             .instance()
             .extend_ttl(INSTANCE_RENEWAL_THRESHOLD, INSTANCE_TARGET_TTL);
         let vesting_info = get_vesting(&env, &address, index);
+        if get_vesting_bond(&env, &address, index).is_some() {
+            return 0;
+        }
 
         let difference = vesting_info
             .balance
@@ -297,6 +1190,121 @@ impl VestingTrait for Vesting {
             });
         convert_u128_to_i128(difference)
     }
+
+    fn query_stake_whitelist(env: Env) -> Vec<Address> {
+        env.storage()
+            .instance()
+            .extend_ttl(INSTANCE_RENEWAL_THRESHOLD, INSTANCE_TARGET_TTL);
+        get_stake_whitelist(&env)
+    }
+
+    fn query_vesting_bond(env: Env, address: Address, index: u64) -> Option<VestingBond> {
+        env.storage()
+            .instance()
+            .extend_ttl(INSTANCE_RENEWAL_THRESHOLD, INSTANCE_TARGET_TTL);
+        get_vesting_bond(&env, &address, index)
+    }
+
+    fn query_airdrop(env: Env, airdrop_id: u64) -> Option<MerkleAirdrop> {
+        env.storage()
+            .instance()
+            .extend_ttl(INSTANCE_RENEWAL_THRESHOLD, INSTANCE_TARGET_TTL);
+
+        get_airdrop(&env, airdrop_id)
+    }
+
+    fn query_airdrop_claimed(env: Env, airdrop_id: u64, address: Address) -> bool {
+        env.storage()
+            .instance()
+            .extend_ttl(INSTANCE_RENEWAL_THRESHOLD, INSTANCE_TARGET_TTL);
+
+        is_airdrop_claimed(&env, airdrop_id, &address)
+    }
+
+    fn query_vesting_amendment(env: Env, address: Address, index: u64) -> Option<VestingAmendment> {
+        env.storage()
+            .instance()
+            .extend_ttl(INSTANCE_RENEWAL_THRESHOLD, INSTANCE_TARGET_TTL);
+
+        get_vesting_amendment(&env, &address, index)
+    }
+
+    fn query_total_locked(
+        env: Env,
+        token: Option<Address>,
+        start_after: Option<Address>,
+        limit: u32,
+    ) -> i128 {
+        env.storage()
+            .instance()
+            .extend_ttl(INSTANCE_RENEWAL_THRESHOLD, INSTANCE_TARGET_TTL);
+
+        let now = env.ledger().timestamp();
+        let total_locked = get_vestings_page(&env, token, start_after, limit)
+            .iter()
+            .fold(0u128, |total, vesting| {
+                total + vesting.schedule.value(now).min(vesting.balance)
+            });
+
+        convert_u128_to_i128(total_locked)
+    }
+
+    fn query_total_claimable_at(
+        env: Env,
+        timestamp: u64,
+        token: Option<Address>,
+        start_after: Option<Address>,
+        limit: u32,
+    ) -> i128 {
+        env.storage()
+            .instance()
+            .extend_ttl(INSTANCE_RENEWAL_THRESHOLD, INSTANCE_TARGET_TTL);
+
+        let total_claimable = get_vestings_page(&env, token, start_after, limit)
+            .iter()
+            .fold(0u128, |total, vesting| {
+                total
+                    + vesting
+                        .balance
+                        .saturating_sub(vesting.schedule.value(timestamp))
+            });
+
+        convert_u128_to_i128(total_claimable)
+    }
+
+    fn query_recipients(env: Env, start_after: Option<Address>, limit: u32) -> Vec<Address> {
+        env.storage()
+            .instance()
+            .extend_ttl(INSTANCE_RENEWAL_THRESHOLD, INSTANCE_TARGET_TTL);
+
+        let mut recipients = vec![&env];
+        for position in recipients_page(&env, start_after, limit) {
+            recipients.push_back(get_recipient(&env, position));
+        }
+
+        recipients
+    }
+
+    fn query_unlock_calendar(
+        env: Env,
+        token: Option<Address>,
+        start_after: Option<Address>,
+        limit: u32,
+    ) -> Curve {
+        env.storage()
+            .instance()
+            .extend_ttl(INSTANCE_RENEWAL_THRESHOLD, INSTANCE_TARGET_TTL);
+
+        // claims only take what the curve released, so from now on the locked
+        // amount of every schedule is given by its curve
+        get_vestings_page(&env, token, start_after, limit)
+            .iter()
+            .filter(|vesting| vesting.balance > 0)
+            .fold(Curve::Constant(0), |calendar, vesting| {
+                calendar.combine(&env, &vesting.schedule)
+            })
+    }
+
     fn update_vesting_token(env: Env, new_token_address: Address) -> Result<(), ContractError> {
         get_admin_old(&env).require_auth();
 
@@ -310,6 +1318,15 @@ impl VestingTrait for Vesting {
             panic_with_error!(&env, ContractError::SameTokenAddress);
         }
 
+        // the capacity and the emission of the minter are given in the current token
+        if get_config(&env).is_with_minter {
+            log!(
+                &env,
+                "Vesting: Update Token: The token of a contract with a minter can't be changed"
+            );
+            panic_with_error!(&env, ContractError::CantMoveVestingTokens);
+        }
+
         let new_token_client = token_contract::Client::new(&env, &new_token_address);
         let new_name = new_token_client.name();
         let new_symbol = new_token_client.symbol();
@@ -340,6 +1357,30 @@ impl VestingTrait for Vesting {
         Ok(())
     }
 
+    fn migrate_vesting_tokens(
+        env: Env,
+        schedules: Vec<VestingInfoKey>,
+    ) -> Result<(), ContractError> {
+        get_admin_old(&env).require_auth();
+        env.storage()
+            .instance()
+            .extend_ttl(INSTANCE_RENEWAL_THRESHOLD, INSTANCE_TARGET_TTL);
+
+        let token = get_token_info(&env).address;
+        for schedule in schedules.iter() {
+            // make sure the schedule exists
+            get_vesting(&env, &schedule.recipient, schedule.index);
+            if find_vesting_token(&env, &schedule.recipient, schedule.index).is_none() {
+                save_vesting_token(&env, &schedule.recipient, schedule.index, &token);
+            }
+        }
+
+        env.events()
+            .publish(("Vesting: Migrate Tokens", "token: "), token);
+
+        Ok(())
+    }
+
     fn update_max_complexity(env: Env, new_max_complexity: u32) -> Result<(), ContractError> {
         get_admin_old(&env).require_auth();
 
@@ -590,6 +1631,22 @@ impl Vesting {
                 panic_with_error!(env, ContractError::NotEnoughCapacity);
             });
 
+        // check if the emission schedule allows minting that much by now
+        if let Some(mut emission) = get_minter_emission(&env) {
+            emission.minted = emission
+                .minted
+                .checked_add(convert_i128_to_u128(amount))
+                .filter(|minted| *minted <= emission.curve.value(env.ledger().timestamp()))
+                .unwrap_or_else(|| {
+                    log!(
+                        &env,
+                        "Vesting: Mint: Minting would exceed the emission schedule"
+                    );
+                    panic_with_error!(env, ContractError::NotEnoughCapacity);
+                });
+            save_minter_emission(&env, &emission);
+        }
+
         // mint to recipient
         let token_client = token_contract::Client::new(&env, &get_token_info(&env).address);
         token_client.mint(&env.current_contract_address(), &amount);
@@ -675,6 +1732,79 @@ impl Vesting {
         );
     }
 
+    /// Caps the cumulative amount minted from now on by `curve`, which returns
+    /// the total allowed to be minted by a given timestamp. The schedule can
+    /// only be set once, so it can't be loosened later on.
+    pub fn set_minter_emission_curve(env: Env, sender: Address, curve: Curve) {
+        sender.require_auth();
+        env.storage()
+            .instance()
+            .extend_ttl(INSTANCE_RENEWAL_THRESHOLD, INSTANCE_TARGET_TTL);
+
+        if sender != get_admin_old(&env) {
+            log!(
+                &env,
+                "Vesting: Set Minter Emission Curve: Only contract's admin can set the emission curve"
+            );
+            panic_with_error!(env, ContractError::NotAuthorized);
+        }
+
+        if get_minter(&env).is_none() {
+            log!(&env, "Vesting: Set Minter Emission Curve: Minter not found");
+            panic_with_error!(env, ContractError::MinterNotFound);
+        }
+
+        if get_minter_emission(&env).is_some() {
+            log!(
+                &env,
+                "Vesting: Set Minter Emission Curve: Emission curve is already set"
+            );
+            panic_with_error!(env, ContractError::AlreadyInitialized);
+        }
+
+        if let Err(err) = curve.validate_monotonic_increasing() {
+            log!(
+                &env,
+                "Vesting: Set Minter Emission Curve: Curve must be monotonically increasing"
+            );
+            panic_with_error!(env, ContractError::from(err));
+        }
+
+        save_minter_emission(
+            &env,
+            &MinterEmission {
+                curve: curve.clone(),
+                minted: 0,
+            },
+        );
+
+        env.events()
+            .publish(("Set minter emission curve", "Emission curve: "), curve);
+    }
+
+    /// Returns how much the minter can still mint at the current timestamp.
+    pub fn query_mintable_amount(env: Env) -> u128 {
+        let minter = Self::query_minter(env.clone());
+
+        match get_minter_emission(&env) {
+            Some(emission) => minter.mint_capacity.min(
+                emission
+                    .curve
+                    .value(env.ledger().timestamp())
+                    .saturating_sub(emission.minted),
+            ),
+            None => minter.mint_capacity,
+        }
+    }
+
+    pub fn query_minter_emission(env: Env) -> Option<MinterEmission> {
+        env.storage()
+            .instance()
+            .extend_ttl(INSTANCE_RENEWAL_THRESHOLD, INSTANCE_TARGET_TTL);
+
+        get_minter_emission(&env)
+    }
+
     pub fn query_minter(env: Env) -> MinterInfo {
         env.storage()
             .instance()
@@ -687,3 +1817,174 @@ impl Vesting {
         }
     }
 }
+
+// Positions of at most `limit` recipients following `start_after`
+fn recipients_page(env: &Env, start_after: Option<Address>, limit: u32) -> Range<u64> {
+    let start = match start_after {
+        Some(address) => {
+            get_recipient_position(env, &address).unwrap_or_else(|| {
+                log!(&env, "Vesting: Query Recipients: Unknown start address");
+                panic_with_error!(&env, ContractError::VestingNotFoundForAddress);
+            }) + 1
+        }
+        None => 0,
+    };
+    let end = get_recipients_count(env).min(start + limit.min(MAX_QUERY_LIMIT) as u64);
+
+    start..end
+}
+
+// Collects the schedules vesting `token` of a page of recipients
+fn get_vestings_page(
+    env: &Env,
+    token: Option<Address>,
+    start_after: Option<Address>,
+    limit: u32,
+) -> Vec<VestingInfo> {
+    let token = token.unwrap_or_else(|| get_token_info(env).address);
+    let mut vestings = vec![env];
+    for position in recipients_page(env, start_after, limit) {
+        let recipient = get_recipient(env, position);
+        for index in 0..get_vesting_count(env, &recipient) {
+            if let Some(vesting_info) = find_vesting(env, &recipient, index) {
+                if get_vesting_token(env, &recipient, index) == token {
+                    vestings.push_back(vesting_info);
+                }
+            }
+        }
+    }
+
+    vestings
+}
+
+/// Removes the pending amendment of a schedule, if any, and returns its top up
+/// to the admin. Returns whether there was one.
+fn drop_pending_amendment(env: &Env, admin: &Address, recipient: &Address, index: u64) -> bool {
+    let Some(amendment) = get_vesting_amendment(env, recipient, index) else {
+        return false;
+    };
+
+    if amendment.top_up > 0 {
+        let token_client = token_contract::Client::new(env, &amendment.token);
+        token_client.transfer(
+            &env.current_contract_address(),
+            admin,
+            &convert_u128_to_i128(amendment.top_up),
+        );
+    }
+    remove_vesting_amendment(env, recipient, index);
+
+    true
+}
+
+fn ensure_not_bonded(env: &Env, recipient: &Address, index: u64) {
+    if get_vesting_bond(env, recipient, index).is_some() {
+        log!(
+            &env,
+            "Vesting: Vesting schedule is bonded in a stake contract and must be unbonded first"
+        );
+        panic_with_error!(&env, ContractError::VestingStaked);
+    }
+}
+
+fn get_bond_or_panic(env: &Env, recipient: &Address, index: u64) -> VestingBond {
+    get_vesting_bond(env, recipient, index).unwrap_or_else(|| {
+        log!(&env, "Vesting: Vesting schedule is not bonded");
+        panic_with_error!(&env, ContractError::VestingNotBonded);
+    })
+}
+
+/// Unbonds the stake of a schedule from its stake contract, paying the
+/// recipient the rewards earned so far.
+fn unbond_from_stake(env: &Env, recipient: &Address, index: u64) {
+    let stake_contract = get_bond_or_panic(env, recipient, index).stake_contract;
+    settle_stake_rewards(env, &stake_contract);
+
+    let mut bond = get_bond_or_panic(env, recipient, index);
+    pay_bond_rewards(env, recipient, &mut bond);
+
+    let amount = convert_u128_to_i128(bond.amount);
+    stake_contract::Client::new(env, &stake_contract).unbond(
+        &env.current_contract_address(),
+        &amount,
+        &bond.stake_timestamp,
+    );
+
+    remove_vesting_bond(env, recipient, index);
+    let mut bonds = get_stake_bonds(env, &stake_contract);
+    let key = VestingInfoKey {
+        recipient: recipient.clone(),
+        index,
+    };
+    if let Some(position) = bonds.first_index_of(&key) {
+        bonds.remove(position);
+    }
+    save_stake_bonds(env, &stake_contract, &bonds);
+
+    env.events().publish(
+        ("Unbond vesting", "recipient: "),
+        (recipient.clone(), index),
+    );
+    env.events()
+        .publish(("Unbond vesting", "stake: "), (stake_contract, amount));
+}
+
+/// Withdraws the rewards of the stakes the vesting contract holds in
+/// `stake_contract` and credits every bonded schedule with what its own stake
+/// earned.
+fn settle_stake_rewards(env: &Env, stake_contract: &Address) {
+    let bonds = get_stake_bonds(env, stake_contract);
+    if bonds.is_empty() {
+        return;
+    }
+
+    let stake_client = stake_contract::Client::new(env, stake_contract);
+    let mut stake_rewards = stake_client.query_stake_rewards(&env.current_contract_address());
+    stake_client.withdraw_rewards(&env.current_contract_address());
+
+    for key in bonds.iter() {
+        let mut bond = get_bond_or_panic(env, &key.recipient, key.index);
+        // stakes of the same amount and age earn the same, any of them will do
+        let Some(position) = stake_rewards.iter().position(|earned| {
+            earned.stake == convert_u128_to_i128(bond.amount)
+                && earned.stake_timestamp == bond.stake_timestamp
+        }) else {
+            continue;
+        };
+        let earned = stake_rewards.get_unchecked(position as u32);
+        stake_rewards.remove(position as u32);
+
+        let mut credited = false;
+        for reward in earned.rewards.iter() {
+            if reward.reward_amount == 0 {
+                continue;
+            }
+            let unpaid = bond
+                .unpaid_rewards
+                .get(reward.reward_address.clone())
+                .unwrap_or(0);
+            bond.unpaid_rewards.set(
+                reward.reward_address,
+                unpaid + convert_u128_to_i128(reward.reward_amount),
+            );
+            credited = true;
+        }
+        if credited {
+            save_vesting_bond(env, &key.recipient, key.index, &bond);
+        }
+    }
+}
+
+/// Pays the recipient of a bond the rewards credited to it so far.
+fn pay_bond_rewards(env: &Env, recipient: &Address, bond: &mut VestingBond) {
+    for (reward_token, amount) in bond.unpaid_rewards.iter() {
+        if amount > 0 {
+            token_contract::Client::new(env, &reward_token).transfer(
+                &env.current_contract_address(),
+                recipient,
+                &amount,
+            );
+        }
+    }
+    bond.unpaid_rewards = Map::new(env);
+}
@@ -6,7 +6,6 @@ use soroban_sdk::contracterror;
 #[repr(u32)]
 pub enum ContractError {
     VestingNotFoundForAddress = 700,
-    AllowanceNotFoundForGivenPair = 701,
     MinterNotFound = 702,
     NoBalanceFoundForAddress = 703,
     NoConfigFound = 704,
@@ -22,7 +21,6 @@ pub enum ContractError {
     VestsMoreThanSent = 714,
     InvalidBurnAmount = 715,
     InvalidMintAmount = 716,
-    InvalidAllowanceAmount = 717,
     DuplicateInitialBalanceAddresses = 718,
     CurveError = 719,
     NoWhitelistFound = 720,
@@ -32,9 +30,6 @@ pub enum ContractError {
     NoEnoughtTokensToStart = 724,
     NotEnoughBalance = 725,
 
-    VestingBothPresent = 726,
-    VestingNonePresent = 727,
-
     CurveConstant = 728,
     CurveSLNotDecreasing = 729,
     AlreadyInitialized = 730,
@@ -46,6 +41,22 @@ pub enum ContractError {
     AdminChangeExpired = 735,
     SameTokenAddress = 745,
     InvalidMaxComplexity = 746,
+    VestingNotRevocable = 747,
+    VestingNotTransferable = 748,
+    VestingTransferToSelf = 749,
+    StakeNotWhitelisted = 750,
+    StakeTokenMismatch = 751,
+    VestingBondedElsewhere = 752,
+    VestingStaked = 753,
+    VestingNotBonded = 754,
+    InvalidBondAmount = 755,
+    NoAirdropFound = 756,
+    InvalidMerkleProof = 757,
+    AirdropAlreadyClaimed = 758,
+    NoAmendmentFound = 759,
+    AmendmentOutdated = 760,
+    AirdropEnded = 761,
+    AirdropNotEnded = 762,
 }
 
 impl From<CurveError> for ContractError {
@@ -4,8 +4,8 @@ use phoenix::ttl::{
     PERSISTENT_TARGET_TTL,
 };
 use soroban_sdk::{
-    contracttype, log, panic_with_error, symbol_short, Address, ConversionError, Env, String,
-    Symbol, TryFromVal, Val,
+    contracttype, log, panic_with_error, symbol_short, vec, Address, BytesN, ConversionError, Env,
+    Map, String, Symbol, TryFromVal, Val, Vec,
 };
 
 use crate::error::ContractError;
@@ -32,6 +32,7 @@ pub enum DataKey {
     VestingTokenInfo = 5,
     MaxVestingComplexity = 6,
     IsInitialized = 7, //TODO: deprecated, remove in future upgrade
+    MinterEmission = 8,
 }
 
 #[contracttype]
@@ -55,6 +56,11 @@ pub struct VestingTokenInfo {
 pub struct VestingSchedule {
     pub recipient: Address,
     pub curve: Curve,
+    // whether the admin can revoke the unvested part of the schedule,
+    // irrevocable when `None`
+    pub revocable: Option<bool>,
+    // token to vest, the default vesting token when `None`
+    pub token: Option<Address>,
 }
 
 #[contracttype]
@@ -80,6 +86,17 @@ pub struct MinterInfo {
     pub mint_capacity: u128,
 }
 
+// Caps the cumulative amount the minter can mint over time, on top of its
+// `mint_capacity`. `curve` returns the total allowed to be minted by a given
+// timestamp, so it has to be monotonically increasing.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct MinterEmission {
+    pub curve: Curve,
+    // total minted since the emission schedule was set
+    pub minted: u128,
+}
+
 #[cfg(feature = "minter")]
 impl MinterInfo {
     #[cfg(not(tarpaulin_include))]
@@ -149,9 +166,29 @@ pub struct VestingInfoResponse {
     pub recipient: Address,
     pub schedule: Curve,
     pub index: u64,
+    pub revocable: bool,
+    pub transferable: bool,
+    pub token: Address,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ClaimedVesting {
+    pub index: u64,
+    pub amount: i128,
+    pub token: Address,
+}
+
+// Per schedule flags, kept apart from `VestingInfo` so that schedules saved by
+// older versions of the contract can still be read
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum VestingFlagKey {
+    Revocable(VestingInfoKey),
+    Transferable(VestingInfoKey),
 }
 
-pub fn save_vesting(env: &Env, address: &Address, vesting_info: VestingInfo) {
+pub fn save_vesting(env: &Env, address: &Address, vesting_info: VestingInfo) -> u64 {
     let counter_key = VestingCounterKey {
         recipient: address.clone(),
     };
@@ -173,6 +210,87 @@ pub fn save_vesting(env: &Env, address: &Address, vesting_info: VestingInfo) {
     env.storage()
         .persistent()
         .set(&counter_key, &(next_index + 1));
+
+    if get_recipient_position(env, address).is_none() {
+        add_recipient(env, address);
+    }
+
+    next_index
+}
+
+// Every address that ever received a vesting schedule, in order of the first
+// schedule. Used to iterate over all schedules for reporting.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum RecipientsDataKey {
+    Count,
+    Recipient(u64),
+    Position(Address),
+}
+
+fn add_recipient(env: &Env, address: &Address) {
+    let position = get_recipients_count(env);
+    let keys = [
+        RecipientsDataKey::Recipient(position),
+        RecipientsDataKey::Position(address.clone()),
+        RecipientsDataKey::Count,
+    ];
+    env.storage().persistent().set(&keys[0], address);
+    env.storage().persistent().set(&keys[1], &position);
+    env.storage().persistent().set(&keys[2], &(position + 1));
+    for key in keys.iter() {
+        env.storage().persistent().extend_ttl(
+            key,
+            PERSISTENT_RENEWAL_THRESHOLD,
+            PERSISTENT_TARGET_TTL,
+        );
+    }
+}
+
+pub fn get_recipients_count(env: &Env) -> u64 {
+    let count = env
+        .storage()
+        .persistent()
+        .get(&RecipientsDataKey::Count)
+        .unwrap_or(0);
+    if count > 0 {
+        env.storage().persistent().extend_ttl(
+            &RecipientsDataKey::Count,
+            PERSISTENT_RENEWAL_THRESHOLD,
+            PERSISTENT_TARGET_TTL,
+        );
+    }
+
+    count
+}
+
+pub fn get_recipient(env: &Env, position: u64) -> Address {
+    let key = RecipientsDataKey::Recipient(position);
+    let recipient = env.storage().persistent().get(&key).unwrap_or_else(|| {
+        log!(&env, "Vesting: Get recipient: No recipient at {}", position);
+        panic_with_error!(&env, ContractError::VestingNotFoundForAddress);
+    });
+    env.storage().persistent().extend_ttl(
+        &key,
+        PERSISTENT_RENEWAL_THRESHOLD,
+        PERSISTENT_TARGET_TTL,
+    );
+
+    recipient
+}
+
+pub fn get_recipient_position(env: &Env, address: &Address) -> Option<u64> {
+    let key = RecipientsDataKey::Position(address.clone());
+    let position = env.storage().persistent().get(&key);
+    if position.is_some() {
+        env.storage().persistent().extend_ttl(
+            &key,
+            PERSISTENT_RENEWAL_THRESHOLD,
+            PERSISTENT_TARGET_TTL,
+        );
+    }
+
+    position
 }
 
 pub fn update_vesting(env: &Env, address: &Address, index: u64, vesting_info: &VestingInfo) {
@@ -206,6 +324,439 @@ pub fn get_vesting(env: &Env, recipient: &Address, index: u64) -> VestingInfo {
     vesting_info
 }
 
+pub fn get_vesting_count(env: &Env, recipient: &Address) -> u64 {
+    let counter_key = VestingCounterKey {
+        recipient: recipient.clone(),
+    };
+    env.storage().persistent().get(&counter_key).unwrap_or(0)
+}
+
+// Unlike `get_vesting` this doesn't fail for schedules that were removed
+pub fn find_vesting(env: &Env, recipient: &Address, index: u64) -> Option<VestingInfo> {
+    let vesting_key = VestingInfoKey {
+        recipient: recipient.clone(),
+        index,
+    };
+    let vesting_info = env.storage().persistent().get(&vesting_key);
+    if vesting_info.is_some() {
+        env.storage().persistent().extend_ttl(
+            &vesting_key,
+            PERSISTENT_RENEWAL_THRESHOLD,
+            PERSISTENT_TARGET_TTL,
+        );
+    }
+
+    vesting_info
+}
+
+pub fn remove_vesting(env: &Env, recipient: &Address, index: u64) {
+    let vesting_key = VestingInfoKey {
+        recipient: recipient.clone(),
+        index,
+    };
+    env.storage().persistent().remove(&vesting_key);
+    env.storage()
+        .persistent()
+        .remove(&VestingFlagKey::Revocable(vesting_key.clone()));
+    env.storage()
+        .persistent()
+        .remove(&VestingFlagKey::Transferable(vesting_key.clone()));
+    env.storage()
+        .persistent()
+        .remove(&ScheduleTokenKey::Token(vesting_key));
+}
+
+fn save_flag(env: &Env, key: &VestingFlagKey, value: bool) {
+    env.storage().persistent().set(key, &value);
+    env.storage()
+        .persistent()
+        .extend_ttl(key, PERSISTENT_RENEWAL_THRESHOLD, PERSISTENT_TARGET_TTL);
+}
+
+fn get_flag(env: &Env, key: &VestingFlagKey) -> bool {
+    let value = env.storage().persistent().get(key);
+    if value.is_some() {
+        env.storage().persistent().extend_ttl(
+            key,
+            PERSISTENT_RENEWAL_THRESHOLD,
+            PERSISTENT_TARGET_TTL,
+        );
+    }
+
+    value.unwrap_or(false)
+}
+
+pub fn save_revocable(env: &Env, recipient: &Address, index: u64, revocable: bool) {
+    save_flag(
+        env,
+        &VestingFlagKey::Revocable(VestingInfoKey {
+            recipient: recipient.clone(),
+            index,
+        }),
+        revocable,
+    );
+}
+
+pub fn is_revocable(env: &Env, recipient: &Address, index: u64) -> bool {
+    get_flag(
+        env,
+        &VestingFlagKey::Revocable(VestingInfoKey {
+            recipient: recipient.clone(),
+            index,
+        }),
+    )
+}
+
+pub fn save_transferable(env: &Env, recipient: &Address, index: u64, transferable: bool) {
+    save_flag(
+        env,
+        &VestingFlagKey::Transferable(VestingInfoKey {
+            recipient: recipient.clone(),
+            index,
+        }),
+        transferable,
+    );
+}
+
+pub fn is_transferable(env: &Env, recipient: &Address, index: u64) -> bool {
+    get_flag(
+        env,
+        &VestingFlagKey::Transferable(VestingInfoKey {
+            recipient: recipient.clone(),
+            index,
+        }),
+    )
+}
+
+// A new curve the admin proposed for a schedule, waiting for the recipient to
+// accept it. Any top up is escrowed from the admin when proposing.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct VestingAmendment {
+    pub curve: Curve,
+    // balance of the schedule when the amendment was proposed
+    pub balance: u128,
+    pub token: Address,
+    pub top_up: u128,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum AmendmentDataKey {
+    Amendment(VestingInfoKey),
+}
+
+pub fn save_vesting_amendment(
+    env: &Env,
+    recipient: &Address,
+    index: u64,
+    amendment: &VestingAmendment,
+) {
+    let key = AmendmentDataKey::Amendment(VestingInfoKey {
+        recipient: recipient.clone(),
+        index,
+    });
+    env.storage().persistent().set(&key, amendment);
+    env.storage().persistent().extend_ttl(
+        &key,
+        PERSISTENT_RENEWAL_THRESHOLD,
+        PERSISTENT_TARGET_TTL,
+    );
+}
+
+pub fn get_vesting_amendment(
+    env: &Env,
+    recipient: &Address,
+    index: u64,
+) -> Option<VestingAmendment> {
+    let key = AmendmentDataKey::Amendment(VestingInfoKey {
+        recipient: recipient.clone(),
+        index,
+    });
+    let amendment = env.storage().persistent().get(&key);
+    if amendment.is_some() {
+        env.storage().persistent().extend_ttl(
+            &key,
+            PERSISTENT_RENEWAL_THRESHOLD,
+            PERSISTENT_TARGET_TTL,
+        );
+    }
+
+    amendment
+}
+
+pub fn remove_vesting_amendment(env: &Env, recipient: &Address, index: u64) {
+    env.storage()
+        .persistent()
+        .remove(&AmendmentDataKey::Amendment(VestingInfoKey {
+            recipient: recipient.clone(),
+            index,
+        }));
+}
+
+// Token vested by a schedule, saved when it is created. Schedules created before
+// tokens were tracked per schedule have no entry and vest the default token
+// from `VestingTokenInfo`.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum ScheduleTokenKey {
+    Token(VestingInfoKey),
+}
+
+pub fn save_vesting_token(env: &Env, recipient: &Address, index: u64, token: &Address) {
+    let key = ScheduleTokenKey::Token(VestingInfoKey {
+        recipient: recipient.clone(),
+        index,
+    });
+    env.storage().persistent().set(&key, token);
+    env.storage().persistent().extend_ttl(
+        &key,
+        PERSISTENT_RENEWAL_THRESHOLD,
+        PERSISTENT_TARGET_TTL,
+    );
+}
+
+pub fn find_vesting_token(env: &Env, recipient: &Address, index: u64) -> Option<Address> {
+    let key = ScheduleTokenKey::Token(VestingInfoKey {
+        recipient: recipient.clone(),
+        index,
+    });
+    let token = env.storage().persistent().get(&key);
+    if token.is_some() {
+        env.storage().persistent().extend_ttl(
+            &key,
+            PERSISTENT_RENEWAL_THRESHOLD,
+            PERSISTENT_TARGET_TTL,
+        );
+    }
+
+    token
+}
+
+/// Returns the token vested by the schedule
+pub fn get_vesting_token(env: &Env, recipient: &Address, index: u64) -> Address {
+    // schedules created before they kept their own token have to be migrated
+    find_vesting_token(env, recipient, index).unwrap_or_else(|| {
+        log!(
+            &env,
+            "Vesting: Get vesting token: Vesting schedule has no token and must be migrated first"
+        );
+        panic_with_error!(env, ContractError::NoTokenInfoFound);
+    })
+}
+
+// Locked vesting balances bonded into whitelisted stake contracts. The stakes
+// are held by the vesting contract, each bonded schedule is a stake of its own
+// and earns the rewards of that stake.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum StakingDataKey {
+    Whitelist,
+    Bonds(Address),
+    Bond(VestingInfoKey),
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct VestingBond {
+    pub stake_contract: Address,
+    pub amount: u128,
+    // timestamp of the stake in the stake contract
+    pub stake_timestamp: u64,
+    // rewards earned by the stake and not paid out yet, per reward token
+    pub unpaid_rewards: Map<Address, i128>,
+}
+
+pub fn save_stake_whitelist(env: &Env, whitelist: &Vec<Address>) {
+    env.storage()
+        .persistent()
+        .set(&StakingDataKey::Whitelist, whitelist);
+    env.storage().persistent().extend_ttl(
+        &StakingDataKey::Whitelist,
+        PERSISTENT_RENEWAL_THRESHOLD,
+        PERSISTENT_TARGET_TTL,
+    );
+}
+
+pub fn get_stake_whitelist(env: &Env) -> Vec<Address> {
+    let whitelist = env
+        .storage()
+        .persistent()
+        .get(&StakingDataKey::Whitelist)
+        .unwrap_or(vec![env]);
+    if env.storage().persistent().has(&StakingDataKey::Whitelist) {
+        env.storage().persistent().extend_ttl(
+            &StakingDataKey::Whitelist,
+            PERSISTENT_RENEWAL_THRESHOLD,
+            PERSISTENT_TARGET_TTL,
+        );
+    }
+
+    whitelist
+}
+
+pub fn save_stake_bonds(env: &Env, stake_contract: &Address, bonds: &Vec<VestingInfoKey>) {
+    let key = StakingDataKey::Bonds(stake_contract.clone());
+    env.storage().persistent().set(&key, bonds);
+    env.storage().persistent().extend_ttl(
+        &key,
+        PERSISTENT_RENEWAL_THRESHOLD,
+        PERSISTENT_TARGET_TTL,
+    );
+}
+
+/// Returns the schedules bonded into `stake_contract`
+pub fn get_stake_bonds(env: &Env, stake_contract: &Address) -> Vec<VestingInfoKey> {
+    let key = StakingDataKey::Bonds(stake_contract.clone());
+    match env.storage().persistent().get(&key) {
+        Some(bonds) => {
+            env.storage().persistent().extend_ttl(
+                &key,
+                PERSISTENT_RENEWAL_THRESHOLD,
+                PERSISTENT_TARGET_TTL,
+            );
+            bonds
+        }
+        None => vec![env],
+    }
+}
+
+pub fn save_vesting_bond(env: &Env, recipient: &Address, index: u64, bond: &VestingBond) {
+    let key = StakingDataKey::Bond(VestingInfoKey {
+        recipient: recipient.clone(),
+        index,
+    });
+    env.storage().persistent().set(&key, bond);
+    env.storage().persistent().extend_ttl(
+        &key,
+        PERSISTENT_RENEWAL_THRESHOLD,
+        PERSISTENT_TARGET_TTL,
+    );
+}
+
+pub fn get_vesting_bond(env: &Env, recipient: &Address, index: u64) -> Option<VestingBond> {
+    let key = StakingDataKey::Bond(VestingInfoKey {
+        recipient: recipient.clone(),
+        index,
+    });
+    let bond = env.storage().persistent().get(&key);
+    if bond.is_some() {
+        env.storage().persistent().extend_ttl(
+            &key,
+            PERSISTENT_RENEWAL_THRESHOLD,
+            PERSISTENT_TARGET_TTL,
+        );
+    }
+
+    bond
+}
+
+pub fn remove_vesting_bond(env: &Env, recipient: &Address, index: u64) {
+    env.storage()
+        .persistent()
+        .remove(&StakingDataKey::Bond(VestingInfoKey {
+            recipient: recipient.clone(),
+            index,
+        }));
+}
+
+// Vesting schedules committed as a merkle root. Each leaf is the sha256 of the
+// XDR encoded `(recipient, curve, amount)` tuple and the schedule is only
+// created once the recipient claims it with a proof. Airdrops are keyed by the
+// id handed out at creation.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum AirdropDataKey {
+    NextId,
+    Airdrop(u64),
+    Claimed(u64, Address),
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct MerkleAirdrop {
+    pub merkle_root: BytesN<32>,
+    pub total_amount: u128,
+    // sum of the schedules created from the airdrop so far
+    pub claimed_amount: u128,
+    // claims are rejected from then on and the admin can close the airdrop
+    pub end_time: u64,
+    // token the airdrop was funded with
+    pub token: Address,
+}
+
+pub fn next_airdrop_id(env: &Env) -> u64 {
+    let id = env
+        .storage()
+        .persistent()
+        .get(&AirdropDataKey::NextId)
+        .unwrap_or(0u64);
+    env.storage()
+        .persistent()
+        .set(&AirdropDataKey::NextId, &(id + 1));
+    env.storage().persistent().extend_ttl(
+        &AirdropDataKey::NextId,
+        PERSISTENT_RENEWAL_THRESHOLD,
+        PERSISTENT_TARGET_TTL,
+    );
+
+    id
+}
+
+pub fn save_airdrop(env: &Env, airdrop_id: u64, airdrop: &MerkleAirdrop) {
+    let key = AirdropDataKey::Airdrop(airdrop_id);
+    env.storage().persistent().set(&key, airdrop);
+    env.storage().persistent().extend_ttl(
+        &key,
+        PERSISTENT_RENEWAL_THRESHOLD,
+        PERSISTENT_TARGET_TTL,
+    );
+}
+
+pub fn remove_airdrop(env: &Env, airdrop_id: u64) {
+    env.storage()
+        .persistent()
+        .remove(&AirdropDataKey::Airdrop(airdrop_id));
+}
+
+pub fn get_airdrop(env: &Env, airdrop_id: u64) -> Option<MerkleAirdrop> {
+    let key = AirdropDataKey::Airdrop(airdrop_id);
+    let airdrop = env.storage().persistent().get(&key);
+    if airdrop.is_some() {
+        env.storage().persistent().extend_ttl(
+            &key,
+            PERSISTENT_RENEWAL_THRESHOLD,
+            PERSISTENT_TARGET_TTL,
+        );
+    }
+
+    airdrop
+}
+
+pub fn save_airdrop_claimed(env: &Env, airdrop_id: u64, recipient: &Address) {
+    let key = AirdropDataKey::Claimed(airdrop_id, recipient.clone());
+    env.storage().persistent().set(&key, &true);
+    env.storage().persistent().extend_ttl(
+        &key,
+        PERSISTENT_RENEWAL_THRESHOLD,
+        PERSISTENT_TARGET_TTL,
+    );
+}
+
+pub fn is_airdrop_claimed(env: &Env, airdrop_id: u64, recipient: &Address) -> bool {
+    let key = AirdropDataKey::Claimed(airdrop_id, recipient.clone());
+    let claimed = env.storage().persistent().get(&key).unwrap_or(false);
+    if claimed {
+        env.storage().persistent().extend_ttl(
+            &key,
+            PERSISTENT_RENEWAL_THRESHOLD,
+            PERSISTENT_TARGET_TTL,
+        );
+    }
+
+    claimed
+}
+
 #[cfg(feature = "minter")]
 pub fn save_minter(env: &Env, minter: &MinterInfo) {
     env.storage().instance().set(&DataKey::Minter, minter);
@@ -226,6 +777,26 @@ pub fn get_minter(env: &Env) -> Option<MinterInfo> {
     minter_info
 }
 
+#[cfg(feature = "minter")]
+pub fn save_minter_emission(env: &Env, emission: &MinterEmission) {
+    env.storage()
+        .instance()
+        .set(&DataKey::MinterEmission, emission);
+    env.storage()
+        .instance()
+        .extend_ttl(INSTANCE_RENEWAL_THRESHOLD, INSTANCE_TARGET_TTL);
+}
+
+#[cfg(feature = "minter")]
+pub fn get_minter_emission(env: &Env) -> Option<MinterEmission> {
+    let emission = env.storage().instance().get(&DataKey::MinterEmission);
+    env.storage()
+        .instance()
+        .extend_ttl(INSTANCE_RENEWAL_THRESHOLD, INSTANCE_TARGET_TTL);
+
+    emission
+}
+
 pub fn save_token_info(env: &Env, token_info: &VestingTokenInfo) {
     env.storage()
         .instance()
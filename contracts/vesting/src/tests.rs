@@ -1,6 +1,14 @@
 mod admin_change;
+mod amendment;
 mod claim;
+mod claim_all;
 mod instantiate;
+mod merkle_airdrop;
 #[cfg(feature = "minter")]
 mod minter;
+mod multi_token;
+mod reporting;
+mod revoke;
 mod setup;
+mod stake_vesting;
+mod transfer_vesting;
@@ -0,0 +1,245 @@
+use crate::{
+    contract::{Vesting, VestingClient},
+    storage::{MinterInfo, VestingAmendment, VestingSchedule, VestingTokenInfo},
+    token_contract,
+};
+use curve::{Curve, PiecewiseLinear, SaturatingLinear, Step};
+
+use soroban_sdk::{
+    testutils::{Address as _, Ledger},
+    vec, Address, Env, String,
+};
+
+use super::setup::deploy_token_contract;
+
+fn linear_curve(min_x: u64, amount: u128, max_x: u64) -> Curve {
+    Curve::SaturatingLinear(SaturatingLinear {
+        min_x,
+        min_y: amount,
+        max_x,
+        max_y: 0,
+    })
+}
+
+/// Creates a 1_000 token schedule for `vester` vesting linearly over 100
+/// seconds and leaves the admin with 1_000 tokens for top ups.
+fn setup_vesting<'a>(
+    env: &Env,
+    admin: &Address,
+    vester: &Address,
+) -> (VestingClient<'a>, token_contract::Client<'a>) {
+    let token_client = deploy_token_contract(env, admin);
+    token_client.mint(admin, &2_000);
+
+    let vesting_token = VestingTokenInfo {
+        name: String::from_str(env, "Phoenix"),
+        symbol: String::from_str(env, "PHO"),
+        decimals: 6,
+        address: token_client.address.clone(),
+    };
+
+    let vesting_client = VestingClient::new(
+        env,
+        &env.register(Vesting, (admin, vesting_token, &3u32, None::<MinterInfo>)),
+    );
+    vesting_client.create_vesting_schedules(&vec![
+        env,
+        VestingSchedule {
+            recipient: vester.clone(),
+            curve: linear_curve(0, 1_000, 100),
+            revocable: None,
+            token: None,
+        },
+    ]);
+
+    (vesting_client, token_client)
+}
+
+#[test]
+fn amendment_extends_schedule_with_top_up() {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.cost_estimate().budget().reset_unlimited();
+
+    let admin = Address::generate(&env);
+    let vester = Address::generate(&env);
+    let (vesting_client, token_client) = setup_vesting(&env, &admin, &vester);
+
+    env.ledger().with_mut(|li| li.timestamp = 50);
+    vesting_client.claim(&vester, &0);
+    assert_eq!(token_client.balance(&vester), 500);
+
+    // the remaining 500 plus 300 more now vest until 250
+    let new_curve = linear_curve(50, 800, 250);
+    vesting_client.propose_vesting_amendment(&vester, &0, &new_curve);
+    assert_eq!(token_client.balance(&admin), 700);
+    assert_eq!(
+        vesting_client.query_vesting_amendment(&vester, &0),
+        Some(VestingAmendment {
+            curve: new_curve.clone(),
+            balance: 500,
+            token: token_client.address.clone(),
+            top_up: 300,
+        })
+    );
+
+    // nothing changes until the recipient accepts
+    assert_eq!(vesting_client.query_vesting_info(&vester, &0).balance, 500);
+
+    vesting_client.accept_vesting_amendment(&vester, &0);
+    let vesting_info = vesting_client.query_vesting_info(&vester, &0);
+    assert_eq!(vesting_info.balance, 800);
+    assert_eq!(vesting_info.schedule, new_curve);
+    assert_eq!(vesting_client.query_vesting_amendment(&vester, &0), None);
+
+    env.ledger().with_mut(|li| li.timestamp = 150);
+    assert_eq!(vesting_client.query_available_to_claim(&vester, &0), 400);
+    vesting_client.claim(&vester, &0);
+    assert_eq!(token_client.balance(&vester), 900);
+}
+
+#[test]
+fn amendment_refunds_surplus_to_admin() {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.cost_estimate().budget().reset_unlimited();
+
+    let admin = Address::generate(&env);
+    let vester = Address::generate(&env);
+    let (vesting_client, token_client) = setup_vesting(&env, &admin, &vester);
+
+    env.ledger().with_mut(|li| li.timestamp = 50);
+    vesting_client.claim(&vester, &0);
+
+    vesting_client.propose_vesting_amendment(&vester, &0, &linear_curve(50, 300, 60));
+    assert_eq!(token_client.balance(&admin), 1_000);
+
+    vesting_client.accept_vesting_amendment(&vester, &0);
+    assert_eq!(token_client.balance(&admin), 1_200);
+    assert_eq!(vesting_client.query_vesting_info(&vester, &0).balance, 300);
+    assert_eq!(vesting_client.query_vesting_contract_balance(&None), 300);
+
+    env.ledger().with_mut(|li| li.timestamp = 60);
+    vesting_client.claim(&vester, &0);
+    assert_eq!(token_client.balance(&vester), 800);
+}
+
+#[test]
+fn new_proposal_and_cancel_return_the_top_up() {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.cost_estimate().budget().reset_unlimited();
+
+    let admin = Address::generate(&env);
+    let vester = Address::generate(&env);
+    let (vesting_client, token_client) = setup_vesting(&env, &admin, &vester);
+
+    vesting_client.propose_vesting_amendment(&vester, &0, &linear_curve(0, 1_500, 100));
+    assert_eq!(token_client.balance(&admin), 500);
+
+    vesting_client.propose_vesting_amendment(&vester, &0, &linear_curve(0, 1_200, 100));
+    assert_eq!(token_client.balance(&admin), 800);
+
+    vesting_client.cancel_vesting_amendment(&vester, &0);
+    assert_eq!(token_client.balance(&admin), 1_000);
+    assert_eq!(vesting_client.query_vesting_amendment(&vester, &0), None);
+    assert_eq!(vesting_client.query_vesting_contract_balance(&None), 1_000);
+}
+
+#[test]
+fn transfer_cancels_pending_amendment() {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.cost_estimate().budget().reset_unlimited();
+
+    let admin = Address::generate(&env);
+    let vester = Address::generate(&env);
+    let new_owner = Address::generate(&env);
+    let (vesting_client, token_client) = setup_vesting(&env, &admin, &vester);
+
+    vesting_client.propose_vesting_amendment(&vester, &0, &linear_curve(0, 1_500, 100));
+    assert_eq!(token_client.balance(&admin), 500);
+
+    vesting_client.set_vesting_transferable(&vester, &0, &true);
+    let new_index = vesting_client.transfer_vesting(&vester, &0, &new_owner);
+
+    // the top up goes back to the admin and the new owner keeps the old curve
+    assert_eq!(token_client.balance(&admin), 1_000);
+    assert_eq!(vesting_client.query_vesting_amendment(&vester, &0), None);
+    assert_eq!(
+        vesting_client.query_vesting_amendment(&new_owner, &new_index),
+        None
+    );
+    assert_eq!(
+        vesting_client
+            .query_vesting_info(&new_owner, &new_index)
+            .schedule,
+        linear_curve(0, 1_000, 100)
+    );
+    assert_eq!(vesting_client.query_vesting_contract_balance(&None), 1_000);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #759)")]
+fn accept_without_amendment() {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.cost_estimate().budget().reset_unlimited();
+
+    let admin = Address::generate(&env);
+    let vester = Address::generate(&env);
+    let (vesting_client, _) = setup_vesting(&env, &admin, &vester);
+
+    vesting_client.accept_vesting_amendment(&vester, &0);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #760)")]
+fn accept_after_claiming_since_the_proposal() {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.cost_estimate().budget().reset_unlimited();
+
+    let admin = Address::generate(&env);
+    let vester = Address::generate(&env);
+    let (vesting_client, _) = setup_vesting(&env, &admin, &vester);
+
+    vesting_client.propose_vesting_amendment(&vester, &0, &linear_curve(0, 1_000, 200));
+
+    env.ledger().with_mut(|li| li.timestamp = 50);
+    vesting_client.claim(&vester, &0);
+
+    vesting_client.accept_vesting_amendment(&vester, &0);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #707)")]
+fn amendment_respects_max_complexity() {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.cost_estimate().budget().reset_unlimited();
+
+    let admin = Address::generate(&env);
+    let vester = Address::generate(&env);
+    let (vesting_client, _) = setup_vesting(&env, &admin, &vester);
+
+    let too_complex = Curve::PiecewiseLinear(PiecewiseLinear {
+        steps: vec![
+            &env,
+            Step {
+                time: 10,
+                value: 1_000,
+            },
+            Step {
+                time: 20,
+                value: 600,
+            },
+            Step {
+                time: 30,
+                value: 200,
+            },
+            Step { time: 40, value: 0 },
+        ],
+    });
+    vesting_client.propose_vesting_amendment(&vester, &0, &too_complex);
+}
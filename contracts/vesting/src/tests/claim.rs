@@ -40,6 +40,8 @@ fn claim_tokens_when_fully_vested() {
                 max_x: 60,
                 max_y: 0,
             }),
+            revocable: None,
+            token: None,
         },
         VestingSchedule {
             recipient: Address::generate(&env),
@@ -49,6 +51,8 @@ fn claim_tokens_when_fully_vested() {
                 max_x: 60,
                 max_y: 0,
             }),
+            revocable: None,
+            token: None,
         },
     ];
 
@@ -68,7 +72,7 @@ fn claim_tokens_when_fully_vested() {
     assert_eq!(token_client.balance(&vesting_client.address), 320);
 
     // vester1 has 0 tokens before claiming the vested amount
-    assert_eq!(vesting_client.query_balance(&vester1), 0);
+    assert_eq!(vesting_client.query_balance(&vester1, &None), 0);
 
     // we move time to the end of the vesting period
     env.ledger().with_mut(|li| li.timestamp = 60);
@@ -77,10 +81,13 @@ fn claim_tokens_when_fully_vested() {
     vesting_client.claim(&vester1, &0);
 
     // vester1 has 120 tokens after claiming the vested amount
-    assert_eq!(vesting_client.query_balance(&vester1), 120);
+    assert_eq!(vesting_client.query_balance(&vester1, &None), 120);
 
     // there must be 200 vesting tokens left in the contract - remaining for the 2nd vester
-    assert_eq!(vesting_client.query_balance(&vesting_client.address), 200);
+    assert_eq!(
+        vesting_client.query_balance(&vesting_client.address, &None),
+        200
+    );
 }
 
 #[test]
@@ -112,6 +119,8 @@ fn transfer_tokens_when_half_vested() {
                 max_x: 60,
                 max_y: 0,
             }),
+            revocable: None,
+            token: None,
         },
     ];
 
@@ -131,7 +140,7 @@ fn transfer_tokens_when_half_vested() {
     assert_eq!(token_client.balance(&vesting_client.address), 120);
 
     // vester1 has 0 tokens before claiming the vested amount
-    assert_eq!(vesting_client.query_balance(&vester1), 0);
+    assert_eq!(vesting_client.query_balance(&vester1, &None), 0);
 
     // we move time to the middle of the vesting period
     env.ledger().with_mut(|li| li.timestamp = 30);
@@ -140,10 +149,13 @@ fn transfer_tokens_when_half_vested() {
     vesting_client.claim(&vester1, &0);
 
     // vester1 has 60 tokens after claiming the vested amount
-    assert_eq!(vesting_client.query_balance(&vester1), 60);
+    assert_eq!(vesting_client.query_balance(&vester1, &None), 60);
 
     // there must be 60 vesting tokens left in the contract
-    assert_eq!(vesting_client.query_balance(&vesting_client.address), 60);
+    assert_eq!(
+        vesting_client.query_balance(&vesting_client.address, &None),
+        60
+    );
 }
 
 #[test]
@@ -175,6 +187,8 @@ fn claim_tokens_once_then_claim_again() {
                 max_x: 60,
                 max_y: 0,
             }),
+            revocable: None,
+            token: None,
         },
     ];
 
@@ -191,7 +205,7 @@ fn claim_tokens_once_then_claim_again() {
     assert_eq!(token_client.balance(&vesting_client.address), 120);
 
     // vester1 has 0 tokens before claiming the vested amount
-    assert_eq!(vesting_client.query_balance(&vester1), 0);
+    assert_eq!(vesting_client.query_balance(&vester1, &None), 0);
 
     // we move time to the middle of the vesting period
     env.ledger().with_mut(|li| li.timestamp = 30);
@@ -200,10 +214,13 @@ fn claim_tokens_once_then_claim_again() {
     vesting_client.claim(&vester1, &0);
 
     // vester1 has 60 tokens after claiming the vested amount
-    assert_eq!(vesting_client.query_balance(&vester1), 60);
+    assert_eq!(vesting_client.query_balance(&vester1, &None), 60);
 
     // there must be 60 vesting tokens left in the contract
-    assert_eq!(vesting_client.query_balance(&vesting_client.address), 60);
+    assert_eq!(
+        vesting_client.query_balance(&vesting_client.address, &None),
+        60
+    );
 
     // we move time to the end of the vesting period
     env.ledger().with_mut(|li| li.timestamp = 60);
@@ -212,10 +229,13 @@ fn claim_tokens_once_then_claim_again() {
     vesting_client.claim(&vester1, &0);
 
     // vester1 has 120 tokens after claiming the vested amount
-    assert_eq!(vesting_client.query_balance(&vester1), 120);
+    assert_eq!(vesting_client.query_balance(&vester1, &None), 120);
 
     // there must be 0 vesting tokens left in the contract
-    assert_eq!(vesting_client.query_balance(&vesting_client.address), 0);
+    assert_eq!(
+        vesting_client.query_balance(&vesting_client.address, &None),
+        0
+    );
 }
 
 #[test]
@@ -247,6 +267,8 @@ fn user_can_claim_tokens_way_after_the_testing_period() {
                 max_x: 60,
                 max_y: 0,
             }),
+            revocable: None,
+            token: None,
         },
     ];
 
@@ -266,7 +288,7 @@ fn user_can_claim_tokens_way_after_the_testing_period() {
     assert_eq!(token_client.balance(&vesting_client.address), 120);
 
     // vester1 has 0 tokens before claiming the vested amount
-    assert_eq!(vesting_client.query_balance(&vester1), 0);
+    assert_eq!(vesting_client.query_balance(&vester1, &None), 0);
 
     // we move time ahead in time
     env.ledger().with_mut(|li| li.timestamp = 61);
@@ -275,10 +297,13 @@ fn user_can_claim_tokens_way_after_the_testing_period() {
     vesting_client.claim(&vester1, &0);
 
     // vester1 has 120 tokens after claiming the vested amount
-    assert_eq!(vesting_client.query_balance(&vester1), 120);
+    assert_eq!(vesting_client.query_balance(&vester1, &None), 120);
 
     // there must be 0 vesting tokens left in the contract
-    assert_eq!(vesting_client.query_balance(&vesting_client.address), 0);
+    assert_eq!(
+        vesting_client.query_balance(&vesting_client.address, &None),
+        0
+    );
 }
 
 #[test]
@@ -312,6 +337,8 @@ fn transfer_vesting_token_before_vesting_period_starts_should_fail() {
                 max_x: 60,
                 max_y: 0,
             }),
+            revocable: None,
+            token: None,
         },
     ];
 
@@ -360,6 +387,8 @@ fn claim_after_all_tokens_have_been_claimed() {
                 max_x: 60,
                 max_y: 0,
             }),
+            revocable: None,
+            token: None,
         },
     ];
 
@@ -374,7 +403,7 @@ fn claim_after_all_tokens_have_been_claimed() {
 
     // we claim tokens once
     vesting_client.claim(&vester1, &0);
-    assert_eq!(vesting_client.query_balance(&vester1), 1_000);
+    assert_eq!(vesting_client.query_balance(&vester1, &None), 1_000);
     // and second one fails
     vesting_client.claim(&vester1, &0);
 }
@@ -410,6 +439,8 @@ fn transfer_works_with_multiple_users_and_distributions() {
                 max_x: 1_000,
                 max_y: 0,
             }),
+            revocable: None,
+            token: None,
         },
         VestingSchedule {
             recipient: vester2.clone(),
@@ -419,6 +450,8 @@ fn transfer_works_with_multiple_users_and_distributions() {
                 max_x: 500,
                 max_y: 0,
             }),
+            revocable: None,
+            token: None,
         },
         VestingSchedule {
             recipient: vester3.clone(),
@@ -428,6 +461,8 @@ fn transfer_works_with_multiple_users_and_distributions() {
                 max_x: 750,
                 max_y: 0,
             }),
+            revocable: None,
+            token: None,
         },
         VestingSchedule {
             recipient: vester4.clone(),
@@ -437,6 +472,8 @@ fn transfer_works_with_multiple_users_and_distributions() {
                 max_x: 1_500,
                 max_y: 0,
             }),
+            revocable: None,
+            token: None,
         },
     ];
 
@@ -453,47 +490,50 @@ fn transfer_works_with_multiple_users_and_distributions() {
     env.ledger().with_mut(|li| li.timestamp = 500);
 
     // vester1 can withdraw 150 tokens out of 300 tokens
-    assert_eq!(vesting_client.query_balance(&vester1), 0);
+    assert_eq!(vesting_client.query_balance(&vester1, &None), 0);
     vesting_client.claim(&vester1, &0);
-    assert_eq!(vesting_client.query_balance(&vester1), 150);
+    assert_eq!(vesting_client.query_balance(&vester1, &None), 150);
 
     // vester2 can withdraw all tokens
-    assert_eq!(vesting_client.query_balance(&vester2), 0);
+    assert_eq!(vesting_client.query_balance(&vester2, &None), 0);
     vesting_client.claim(&vester2, &0);
-    assert_eq!(vesting_client.query_balance(&vester2), 200);
+    assert_eq!(vesting_client.query_balance(&vester2, &None), 200);
 
     // vester3 can withdraw 150 tokens out of 250 tokens
-    assert_eq!(vesting_client.query_balance(&vester3), 0);
+    assert_eq!(vesting_client.query_balance(&vester3, &None), 0);
     vesting_client.claim(&vester3, &0);
-    assert_eq!(vesting_client.query_balance(&vester3), 150);
+    assert_eq!(vesting_client.query_balance(&vester3, &None), 150);
 
     // vester4 can withdraw 50 tokens out of 250 tokens
-    assert_eq!(vesting_client.query_balance(&vester4), 0);
+    assert_eq!(vesting_client.query_balance(&vester4, &None), 0);
     vesting_client.claim(&vester4, &0);
-    assert_eq!(vesting_client.query_balance(&vester4), 50);
+    assert_eq!(vesting_client.query_balance(&vester4, &None), 50);
 
     // users have withdrawn a total of 550 tokens
     // total remaining in the contract is 450 tokens
-    assert_eq!(vesting_client.query_balance(&vesting_client.address), 450);
+    assert_eq!(
+        vesting_client.query_balance(&vesting_client.address, &None),
+        450
+    );
 
     // we now move the timestamp to 1_000
     env.ledger().with_mut(|li| li.timestamp = 1_000);
 
     // vester1 can withdraw the remaining 150 tokens
-    assert_eq!(vesting_client.query_balance(&vester1), 150);
+    assert_eq!(vesting_client.query_balance(&vester1, &None), 150);
     vesting_client.claim(&vester1, &0);
-    assert_eq!(vesting_client.query_balance(&vester1), 300);
+    assert_eq!(vesting_client.query_balance(&vester1, &None), 300);
 
     // vester2 has nothing to withdraw
     // vester3 can withdraw the remaining 100 tokens
-    assert_eq!(vesting_client.query_balance(&vester3), 150);
+    assert_eq!(vesting_client.query_balance(&vester3, &None), 150);
     vesting_client.claim(&vester3, &0);
-    assert_eq!(vesting_client.query_balance(&vester3), 250);
+    assert_eq!(vesting_client.query_balance(&vester3, &None), 250);
 
     // vester4 can withdraw 100 - maximum for the period
-    assert_eq!(vesting_client.query_balance(&vester4), 50);
+    assert_eq!(vesting_client.query_balance(&vester4, &None), 50);
     vesting_client.claim(&vester4, &0);
-    assert_eq!(vesting_client.query_balance(&vester4), 150);
+    assert_eq!(vesting_client.query_balance(&vester4, &None), 150);
 
     // in the 2nd round users have withdrawn 350 tokens
     // total remaining in the contract is 100 tokens
@@ -505,11 +545,14 @@ fn transfer_works_with_multiple_users_and_distributions() {
     // vester2 has nothing to withdraw
     // vester3 has nothing to withdraw
     // vester4 can withdraw the remaining 100 tokens
-    assert_eq!(vesting_client.query_balance(&vester4), 150);
+    assert_eq!(vesting_client.query_balance(&vester4, &None), 150);
     vesting_client.claim(&vester4, &0);
-    assert_eq!(vesting_client.query_balance(&vester4), 250);
+    assert_eq!(vesting_client.query_balance(&vester4, &None), 250);
 
-    assert_eq!(vesting_client.query_balance(&vesting_client.address), 0);
+    assert_eq!(
+        vesting_client.query_balance(&vesting_client.address, &None),
+        0
+    );
 }
 
 #[test]
@@ -541,6 +584,8 @@ fn claim_works() {
                 max_x: 60,
                 max_y: 0,
             }),
+            revocable: None,
+            token: None,
         },
     ];
 
@@ -560,7 +605,7 @@ fn claim_works() {
     assert_eq!(token_client.balance(&vesting_client.address), 120);
 
     // vester1 has 0 tokens before claiming the vested amount
-    assert_eq!(vesting_client.query_balance(&vester1), 0);
+    assert_eq!(vesting_client.query_balance(&vester1, &None), 0);
     // vester1 has 0 tokens available for claiming before the vesting period starts
     assert_eq!(vesting_client.query_available_to_claim(&vester1, &0), 0);
 
@@ -571,12 +616,15 @@ fn claim_works() {
     vesting_client.claim(&vester1, &0);
 
     // vester1 has 60 tokens after claiming the vested amount
-    assert_eq!(vesting_client.query_balance(&vester1), 60);
+    assert_eq!(vesting_client.query_balance(&vester1, &None), 60);
     // vester1 has 0 tokens available for claiming after claiming the vested amount
     assert_eq!(vesting_client.query_available_to_claim(&vester1, &0), 0);
 
     // there must be 60 vesting tokens left in the contract - remaining for the 2nd vester
-    assert_eq!(vesting_client.query_balance(&vesting_client.address), 60);
+    assert_eq!(
+        vesting_client.query_balance(&vesting_client.address, &None),
+        60
+    );
 
     // we move time to the end of the vesting period
     env.ledger().with_mut(|li| li.timestamp = 60);
@@ -585,12 +633,15 @@ fn claim_works() {
     vesting_client.claim(&vester1, &0);
 
     // vester1 has 120 tokens after claiming the vested amount
-    assert_eq!(vesting_client.query_balance(&vester1), 120);
+    assert_eq!(vesting_client.query_balance(&vester1, &None), 120);
     // vester1 has 0 tokens available for claiming after claiming the vested amount
     assert_eq!(vesting_client.query_available_to_claim(&vester1, &0), 0);
 
     // there must be 0 vesting tokens left in the contract
-    assert_eq!(vesting_client.query_balance(&vesting_client.address), 0);
+    assert_eq!(
+        vesting_client.query_balance(&vesting_client.address, &None),
+        0
+    );
 }
 
 #[test]
@@ -627,13 +678,15 @@ fn claim_tokens_from_two_distributions() {
                 max_x: 100,
                 max_y: 0,
             }),
+            revocable: None,
+            token: None,
         },
     ];
     vesting_client.create_vesting_schedules(&vesting_schedules);
     assert_eq!(token_client.balance(&vesting_client.address), 1_500);
 
     // vester1 has 0 tokens before claiming the vested amount
-    assert_eq!(vesting_client.query_balance(&vester1), 0);
+    assert_eq!(vesting_client.query_balance(&vester1, &None), 0);
 
     // we move time to the half of the vesting period
     env.ledger().with_mut(|li| li.timestamp = 50);
@@ -641,7 +694,7 @@ fn claim_tokens_from_two_distributions() {
     // user collects the vested tokens and transfers them to himself
     vesting_client.claim(&vester1, &0);
 
-    assert_eq!(vesting_client.query_balance(&vester1), 750);
+    assert_eq!(vesting_client.query_balance(&vester1, &None), 750);
     assert_eq!(token_client.balance(&vesting_client.address), 750);
 
     // create a vesting schedule which starts in the middle of the previous one
@@ -666,6 +719,8 @@ fn claim_tokens_from_two_distributions() {
                     },
                 ],
             }),
+            revocable: None,
+            token: None,
         },
     ];
     vesting_client.create_vesting_schedules(&vesting_schedules);
@@ -684,6 +739,9 @@ fn claim_tokens_from_two_distributions() {
                     max_y: 0,
                 }),
                 index: 0,
+                revocable: false,
+                transferable: false,
+                token: token_client.address.clone(),
             },
             VestingInfoResponse {
                 recipient: vester1.clone(),
@@ -706,6 +764,9 @@ fn claim_tokens_from_two_distributions() {
                     ],
                 }),
                 index: 1,
+                revocable: false,
+                transferable: false,
+                token: token_client.address.clone(),
             }
         ]
     );
@@ -713,19 +774,111 @@ fn claim_tokens_from_two_distributions() {
     env.ledger().with_mut(|li| li.timestamp = 100);
 
     vesting_client.claim(&vester1, &0);
-    assert_eq!(vesting_client.query_balance(&vester1), 1_500);
+    assert_eq!(vesting_client.query_balance(&vester1, &None), 1_500);
     assert_eq!(token_client.balance(&vesting_client.address), 500);
 
     vesting_client.claim(&vester1, &1);
-    assert_eq!(vesting_client.query_balance(&vester1), 1_750);
+    assert_eq!(vesting_client.query_balance(&vester1, &None), 1_750);
     assert_eq!(token_client.balance(&vesting_client.address), 250);
 
     env.ledger().with_mut(|li| li.timestamp = 150);
     vesting_client.claim(&vester1, &1);
-    assert_eq!(vesting_client.query_balance(&vester1), 2_000);
+    assert_eq!(vesting_client.query_balance(&vester1, &None), 2_000);
     assert_eq!(token_client.balance(&vesting_client.address), 0);
 }
 
+#[test]
+fn claim_tokens_with_cliff() {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.cost_estimate().budget().reset_unlimited();
+
+    let admin = Address::generate(&env);
+    let vester1 = Address::generate(&env);
+    let token_client = deploy_token_contract(&env, &admin);
+
+    token_client.mint(&admin, &1_000);
+
+    let vesting_token = VestingTokenInfo {
+        name: String::from_str(&env, "Phoenix"),
+        symbol: String::from_str(&env, "PHO"),
+        decimals: 6,
+        address: token_client.address.clone(),
+    };
+
+    // nothing until day 365, then 25% at once and the rest linearly for 2 years
+    let vesting_schedules = vec![
+        &env,
+        VestingSchedule {
+            recipient: vester1.clone(),
+            curve: Curve::cliff(1_000, (365, 750), (1_095, 0)),
+            revocable: None,
+            token: None,
+        },
+    ];
+
+    // a cliff counts as 3 points of complexity
+    let vesting_client = VestingClient::new(
+        &env,
+        &env.register(Vesting, (&admin, vesting_token, &4u32, None::<MinterInfo>)),
+    );
+    vesting_client.create_vesting_schedules(&vesting_schedules);
+
+    env.ledger().with_mut(|li| li.timestamp = 364);
+    assert_eq!(vesting_client.query_available_to_claim(&vester1, &0), 0);
+
+    env.ledger().with_mut(|li| li.timestamp = 365);
+    vesting_client.claim(&vester1, &0);
+    assert_eq!(vesting_client.query_balance(&vester1, &None), 250);
+
+    env.ledger().with_mut(|li| li.timestamp = 730);
+    vesting_client.claim(&vester1, &0);
+    assert_eq!(vesting_client.query_balance(&vester1, &None), 625);
+
+    env.ledger().with_mut(|li| li.timestamp = 1_095);
+    vesting_client.claim(&vester1, &0);
+    assert_eq!(vesting_client.query_balance(&vester1, &None), 1_000);
+    assert_eq!(token_client.balance(&vesting_client.address), 0);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #713)")]
+fn create_vesting_with_cliff_not_fully_vesting_fails() {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.cost_estimate().budget().reset_unlimited();
+
+    let admin = Address::generate(&env);
+    let vester1 = Address::generate(&env);
+    let token_client = deploy_token_contract(&env, &admin);
+
+    token_client.mint(&admin, &1_000);
+
+    let vesting_token = VestingTokenInfo {
+        name: String::from_str(&env, "Phoenix"),
+        symbol: String::from_str(&env, "PHO"),
+        decimals: 6,
+        address: token_client.address.clone(),
+    };
+
+    // 100 tokens would stay locked forever
+    let vesting_schedules = vec![
+        &env,
+        VestingSchedule {
+            recipient: vester1,
+            curve: Curve::cliff(1_000, (365, 750), (1_095, 100)),
+            revocable: None,
+            token: None,
+        },
+    ];
+
+    let vesting_client = VestingClient::new(
+        &env,
+        &env.register(Vesting, (&admin, vesting_token, &4u32, None::<MinterInfo>)),
+    );
+    vesting_client.create_vesting_schedules(&vesting_schedules);
+}
+
 #[test]
 fn first_mainnet_simulation() {
     let env = Env::default();
@@ -755,6 +908,8 @@ fn first_mainnet_simulation() {
                 max_x: 1716820800,
                 max_y: 0,
             }),
+            revocable: None,
+            token: None,
         },
     ];
 
@@ -773,7 +928,7 @@ fn first_mainnet_simulation() {
     assert_eq!(token_client.balance(&vesting_client.address), 100_000_000);
 
     // vester1 has 0 tokens before claiming the vested amount
-    assert_eq!(vesting_client.query_balance(&vester1), 0);
+    assert_eq!(vesting_client.query_balance(&vester1, &None), 0);
 
     // we move time 20 minutes into the future, 1/3 of the schedule
     env.ledger().with_mut(|li| li.timestamp = 1716818400);
@@ -794,13 +949,16 @@ fn first_mainnet_simulation() {
                 max_y: 0,
             }),
             index: 0,
+            revocable: false,
+            transferable: false,
+            token: token_client.address.clone(),
         },
     );
 
-    assert_eq!(vesting_client.query_balance(&vester1), 33_333_333);
+    assert_eq!(vesting_client.query_balance(&vester1, &None), 33_333_333);
     assert_eq!(token_client.balance(&vesting_client.address), 66_666_667);
     assert_eq!(
-        vesting_client.query_balance(&vesting_client.address),
+        vesting_client.query_balance(&vesting_client.address, &None),
         66_666_667
     );
 
@@ -810,7 +968,10 @@ fn first_mainnet_simulation() {
     // user collects the remaining vested tokens and transfers them to himself
     vesting_client.claim(&vester1, &0);
 
-    assert_eq!(vesting_client.query_balance(&vester1), 100_000_000);
+    assert_eq!(vesting_client.query_balance(&vester1, &None), 100_000_000);
 
-    assert_eq!(vesting_client.query_balance(&vesting_client.address), 0);
+    assert_eq!(
+        vesting_client.query_balance(&vesting_client.address, &None),
+        0
+    );
 }
@@ -0,0 +1,168 @@
+use crate::{
+    contract::{Vesting, VestingClient},
+    storage::{ClaimedVesting, MinterInfo, VestingSchedule, VestingTokenInfo},
+    token_contract,
+};
+use curve::{Curve, SaturatingLinear};
+
+use soroban_sdk::{
+    testutils::{Address as _, Ledger},
+    vec, Address, Env, String,
+};
+
+use super::setup::deploy_token_contract;
+
+/// Creates three schedules for `vester`: 1_000 vesting over [0, 100],
+/// 2_000 vesting over [100, 200] and a revocable 500 vesting over [0, 100].
+fn setup_vesting<'a>(
+    env: &Env,
+    admin: &Address,
+    vester: &Address,
+) -> (VestingClient<'a>, token_contract::Client<'a>) {
+    let token_client = deploy_token_contract(env, admin);
+    token_client.mint(admin, &3_500);
+
+    let vesting_token = VestingTokenInfo {
+        name: String::from_str(env, "Phoenix"),
+        symbol: String::from_str(env, "PHO"),
+        decimals: 6,
+        address: token_client.address.clone(),
+    };
+
+    let vesting_client = VestingClient::new(
+        env,
+        &env.register(Vesting, (admin, vesting_token, &10u32, None::<MinterInfo>)),
+    );
+
+    // duplicated recipients are not allowed within a single batch
+    for (min_x, min_y, max_x, revocable) in [
+        (0, 1_000, 100, false),
+        (100, 2_000, 200, false),
+        (0, 500, 100, true),
+    ] {
+        vesting_client.create_vesting_schedules(&vec![
+            env,
+            VestingSchedule {
+                recipient: vester.clone(),
+                curve: Curve::SaturatingLinear(SaturatingLinear {
+                    min_x,
+                    min_y,
+                    max_x,
+                    max_y: 0,
+                }),
+                revocable: Some(revocable),
+                token: None,
+            },
+        ]);
+    }
+
+    (vesting_client, token_client)
+}
+
+#[test]
+fn claim_all_skips_schedules_without_claimable_tokens() {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.cost_estimate().budget().reset_unlimited();
+
+    let admin = Address::generate(&env);
+    let vester = Address::generate(&env);
+    let (vesting_client, token_client) = setup_vesting(&env, &admin, &vester);
+
+    env.ledger().with_mut(|li| li.timestamp = 50);
+    assert_eq!(
+        vesting_client.claim_all(&vester),
+        vec![
+            &env,
+            ClaimedVesting {
+                index: 0,
+                amount: 500,
+                token: token_client.address.clone(),
+            },
+            ClaimedVesting {
+                index: 2,
+                amount: 250,
+                token: token_client.address.clone(),
+            },
+        ]
+    );
+    assert_eq!(token_client.balance(&vester), 750);
+    assert_eq!(vesting_client.query_vesting_info(&vester, &0).balance, 500);
+    assert_eq!(
+        vesting_client.query_vesting_info(&vester, &1).balance,
+        2_000
+    );
+    assert_eq!(vesting_client.query_vesting_info(&vester, &2).balance, 250);
+
+    env.ledger().with_mut(|li| li.timestamp = 150);
+    assert_eq!(
+        vesting_client.claim_all(&vester),
+        vec![
+            &env,
+            ClaimedVesting {
+                index: 0,
+                amount: 500,
+                token: token_client.address.clone(),
+            },
+            ClaimedVesting {
+                index: 1,
+                amount: 1_000,
+                token: token_client.address.clone(),
+            },
+            ClaimedVesting {
+                index: 2,
+                amount: 250,
+                token: token_client.address.clone(),
+            },
+        ]
+    );
+    assert_eq!(token_client.balance(&vester), 2_500);
+    assert_eq!(token_client.balance(&vesting_client.address), 1_000);
+}
+
+#[test]
+fn claim_all_skips_revoked_schedules() {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.cost_estimate().budget().reset_unlimited();
+
+    let admin = Address::generate(&env);
+    let vester = Address::generate(&env);
+    let (vesting_client, token_client) = setup_vesting(&env, &admin, &vester);
+
+    vesting_client.revoke_vesting(&vester, &2, &admin);
+
+    env.ledger().with_mut(|li| li.timestamp = 200);
+    assert_eq!(
+        vesting_client.claim_all(&vester),
+        vec![
+            &env,
+            ClaimedVesting {
+                index: 0,
+                amount: 1_000,
+                token: token_client.address.clone(),
+            },
+            ClaimedVesting {
+                index: 1,
+                amount: 2_000,
+                token: token_client.address.clone(),
+            },
+        ]
+    );
+    assert_eq!(token_client.balance(&vester), 3_000);
+    assert_eq!(token_client.balance(&vesting_client.address), 0);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #713)")]
+fn claim_all_fails_when_nothing_to_claim() {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.cost_estimate().budget().reset_unlimited();
+
+    let admin = Address::generate(&env);
+    let vester = Address::generate(&env);
+    let (vesting_client, _) = setup_vesting(&env, &admin, &vester);
+
+    vesting_client.claim_all(&vester);
+}
@@ -36,6 +36,8 @@ fn instantiate_contract_successfully() {
                 max_x: 60,
                 max_y: 0,
             }),
+            revocable: None,
+            token: None,
         },
         VestingSchedule {
             recipient: vester2,
@@ -45,6 +47,8 @@ fn instantiate_contract_successfully() {
                 max_x: 120,
                 max_y: 0,
             }),
+            revocable: None,
+            token: None,
         },
     ];
 
@@ -74,6 +78,9 @@ fn instantiate_contract_successfully() {
                     max_y: 0,
                 }),
                 index: 0,
+                revocable: false,
+                transferable: false,
+                token: token_client.address.clone(),
             }
         ]
     );
@@ -140,6 +147,8 @@ fn create_schedule_panics_when_admin_has_no_tokens_to_fund() {
                 max_x: 60,
                 max_y: 0,
             }),
+            revocable: None,
+            token: None,
         },
     ];
 
@@ -0,0 +1,406 @@
+use crate::{
+    contract::{Vesting, VestingClient},
+    storage::{MerkleAirdrop, MinterInfo, VestingTokenInfo},
+    token_contract,
+    utils::airdrop_leaf,
+};
+use curve::{Curve, SaturatingLinear};
+
+use soroban_sdk::{
+    testutils::{Address as _, Ledger},
+    vec, Address, Bytes, BytesN, Env, String, Vec,
+};
+
+use super::setup::deploy_token_contract;
+
+fn hash_pair(env: &Env, a: &BytesN<32>, b: &BytesN<32>) -> BytesN<32> {
+    let (first, second) = if a <= b { (a, b) } else { (b, a) };
+    let mut data = Bytes::new(env);
+    data.append(&first.clone().into());
+    data.append(&second.clone().into());
+    env.crypto().sha256(&data).to_bytes()
+}
+
+fn linear_curve(amount: u128) -> Curve {
+    Curve::SaturatingLinear(SaturatingLinear {
+        min_x: 0,
+        min_y: amount,
+        max_x: 100,
+        max_y: 0,
+    })
+}
+
+/// Builds a four leaf tree where `recipients[i]` vests `amounts[i]` linearly
+/// over 100 seconds. Returns the root and the proof of every leaf.
+fn build_tree(
+    env: &Env,
+    recipients: &[Address; 4],
+    amounts: [u128; 4],
+) -> (BytesN<32>, [Vec<BytesN<32>>; 4]) {
+    let leaves: [BytesN<32>; 4] = core::array::from_fn(|i| {
+        airdrop_leaf(env, &recipients[i], &linear_curve(amounts[i]), amounts[i])
+    });
+    let left = hash_pair(env, &leaves[0], &leaves[1]);
+    let right = hash_pair(env, &leaves[2], &leaves[3]);
+    let root = hash_pair(env, &left, &right);
+
+    let proofs = [
+        vec![env, leaves[1].clone(), right.clone()],
+        vec![env, leaves[0].clone(), right],
+        vec![env, leaves[3].clone(), left.clone()],
+        vec![env, leaves[2].clone(), left],
+    ];
+
+    (root, proofs)
+}
+
+fn setup_vesting<'a>(
+    env: &Env,
+    admin: &Address,
+) -> (VestingClient<'a>, token_contract::Client<'a>) {
+    let token_client = deploy_token_contract(env, admin);
+    token_client.mint(admin, &10_000);
+
+    let vesting_token = VestingTokenInfo {
+        name: String::from_str(env, "Phoenix"),
+        symbol: String::from_str(env, "PHO"),
+        decimals: 6,
+        address: token_client.address.clone(),
+    };
+
+    let vesting_client = VestingClient::new(
+        env,
+        &env.register(Vesting, (admin, vesting_token, &10u32, None::<MinterInfo>)),
+    );
+
+    (vesting_client, token_client)
+}
+
+#[test]
+fn claim_with_proof_creates_schedule_and_pays_vested_part() {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.cost_estimate().budget().reset_unlimited();
+
+    let admin = Address::generate(&env);
+    let recipients: [Address; 4] = core::array::from_fn(|_| Address::generate(&env));
+    let amounts = [1_000, 2_000, 500, 1_500];
+    let (root, proofs) = build_tree(&env, &recipients, amounts);
+
+    let (vesting_client, token_client) = setup_vesting(&env, &admin);
+    vesting_client.create_airdrop(&root, &5_000, &1_000);
+    assert_eq!(token_client.balance(&admin), 5_000);
+    assert_eq!(vesting_client.query_vesting_contract_balance(&None), 5_000);
+
+    // nothing has vested yet, the schedule is only created
+    let index = vesting_client.claim_with_proof(
+        &recipients[0],
+        &0,
+        &linear_curve(1_000),
+        &1_000,
+        &proofs[0],
+    );
+    assert_eq!(index, 0);
+    assert_eq!(token_client.balance(&recipients[0]), 0);
+    assert_eq!(
+        vesting_client
+            .query_vesting_info(&recipients[0], &0)
+            .balance,
+        1_000
+    );
+    assert!(vesting_client.query_airdrop_claimed(&0, &recipients[0]));
+    assert!(!vesting_client.query_airdrop_claimed(&0, &recipients[1]));
+
+    env.ledger().with_mut(|li| li.timestamp = 50);
+    vesting_client.claim_with_proof(&recipients[1], &0, &linear_curve(2_000), &2_000, &proofs[1]);
+    assert_eq!(token_client.balance(&recipients[1]), 1_000);
+    assert_eq!(
+        vesting_client
+            .query_vesting_info(&recipients[1], &0)
+            .balance,
+        1_000
+    );
+
+    // later claims go through the regular flow
+    vesting_client.claim(&recipients[0], &0);
+    assert_eq!(token_client.balance(&recipients[0]), 500);
+
+    assert_eq!(
+        vesting_client.query_airdrop(&0),
+        Some(MerkleAirdrop {
+            merkle_root: root,
+            total_amount: 5_000,
+            claimed_amount: 3_000,
+            end_time: 1_000,
+            token: token_client.address.clone(),
+        })
+    );
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #757)")]
+fn claim_with_proof_rejects_wrong_amount() {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.cost_estimate().budget().reset_unlimited();
+
+    let admin = Address::generate(&env);
+    let recipients: [Address; 4] = core::array::from_fn(|_| Address::generate(&env));
+    let (root, proofs) = build_tree(&env, &recipients, [1_000, 2_000, 500, 1_500]);
+
+    let (vesting_client, _) = setup_vesting(&env, &admin);
+    vesting_client.create_airdrop(&root, &5_000, &1_000);
+
+    vesting_client.claim_with_proof(&recipients[0], &0, &linear_curve(2_000), &2_000, &proofs[0]);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #757)")]
+fn claim_with_proof_rejects_proof_of_another_recipient() {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.cost_estimate().budget().reset_unlimited();
+
+    let admin = Address::generate(&env);
+    let recipients: [Address; 4] = core::array::from_fn(|_| Address::generate(&env));
+    let (root, proofs) = build_tree(&env, &recipients, [1_000, 1_000, 1_000, 1_000]);
+
+    let (vesting_client, _) = setup_vesting(&env, &admin);
+    vesting_client.create_airdrop(&root, &4_000, &1_000);
+
+    vesting_client.claim_with_proof(
+        &Address::generate(&env),
+        &0,
+        &linear_curve(1_000),
+        &1_000,
+        &proofs[0],
+    );
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #758)")]
+fn claim_with_proof_only_once() {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.cost_estimate().budget().reset_unlimited();
+
+    let admin = Address::generate(&env);
+    let recipients: [Address; 4] = core::array::from_fn(|_| Address::generate(&env));
+    let (root, proofs) = build_tree(&env, &recipients, [1_000, 2_000, 500, 1_500]);
+
+    let (vesting_client, _) = setup_vesting(&env, &admin);
+    vesting_client.create_airdrop(&root, &5_000, &1_000);
+
+    vesting_client.claim_with_proof(&recipients[2], &0, &linear_curve(500), &500, &proofs[2]);
+    vesting_client.claim_with_proof(&recipients[2], &0, &linear_curve(500), &500, &proofs[2]);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #756)")]
+fn claim_with_proof_without_airdrop() {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.cost_estimate().budget().reset_unlimited();
+
+    let admin = Address::generate(&env);
+    let recipients: [Address; 4] = core::array::from_fn(|_| Address::generate(&env));
+    let (_, proofs) = build_tree(&env, &recipients, [1_000, 2_000, 500, 1_500]);
+
+    let (vesting_client, _) = setup_vesting(&env, &admin);
+
+    vesting_client.claim_with_proof(&recipients[0], &0, &linear_curve(1_000), &1_000, &proofs[0]);
+}
+
+#[test]
+fn airdrops_are_keyed_by_id() {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.cost_estimate().budget().reset_unlimited();
+
+    let admin = Address::generate(&env);
+    let recipients: [Address; 4] = core::array::from_fn(|_| Address::generate(&env));
+    let (root, proofs) = build_tree(&env, &recipients, [1_000, 2_000, 500, 1_500]);
+
+    let (vesting_client, token_client) = setup_vesting(&env, &admin);
+    assert_eq!(vesting_client.create_airdrop(&root, &5_000, &1_000), 0);
+    assert_eq!(vesting_client.create_airdrop(&root, &5_000, &2_000), 1);
+    assert_eq!(token_client.balance(&admin), 0);
+
+    // the same leaf can be claimed once per airdrop
+    vesting_client.claim_with_proof(&recipients[2], &0, &linear_curve(500), &500, &proofs[2]);
+    let index =
+        vesting_client.claim_with_proof(&recipients[2], &1, &linear_curve(500), &500, &proofs[2]);
+    assert_eq!(index, 1);
+    assert!(vesting_client.query_airdrop_claimed(&1, &recipients[2]));
+    assert!(!vesting_client.query_airdrop_claimed(&1, &recipients[0]));
+    assert_eq!(
+        vesting_client.query_airdrop(&1).unwrap().claimed_amount,
+        500
+    );
+    assert_eq!(vesting_client.query_airdrop(&2), None);
+}
+
+#[test]
+fn close_airdrop_returns_unclaimed_funds_to_admin() {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.cost_estimate().budget().reset_unlimited();
+
+    let admin = Address::generate(&env);
+    let recipients: [Address; 4] = core::array::from_fn(|_| Address::generate(&env));
+    let (root, proofs) = build_tree(&env, &recipients, [1_000, 2_000, 500, 1_500]);
+
+    let (vesting_client, token_client) = setup_vesting(&env, &admin);
+    let airdrop_id = vesting_client.create_airdrop(&root, &5_000, &1_000);
+    vesting_client.claim_with_proof(
+        &recipients[1],
+        &airdrop_id,
+        &linear_curve(2_000),
+        &2_000,
+        &proofs[1],
+    );
+
+    env.ledger().with_mut(|li| li.timestamp = 1_000);
+    assert_eq!(vesting_client.close_airdrop(&airdrop_id), 3_000);
+    assert_eq!(token_client.balance(&admin), 8_000);
+    assert_eq!(vesting_client.query_airdrop(&airdrop_id), None);
+
+    // the claimed schedule keeps vesting
+    vesting_client.claim(&recipients[1], &0);
+    assert_eq!(token_client.balance(&recipients[1]), 2_000);
+    assert_eq!(vesting_client.query_vesting_contract_balance(&None), 0);
+}
+
+#[test]
+fn airdrop_keeps_its_token_after_the_vesting_token_changes() {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.cost_estimate().budget().reset_unlimited();
+
+    let admin = Address::generate(&env);
+    let recipients: [Address; 4] = core::array::from_fn(|_| Address::generate(&env));
+    let (root, proofs) = build_tree(&env, &recipients, [1_000, 2_000, 500, 1_500]);
+
+    let (vesting_client, token_client) = setup_vesting(&env, &admin);
+    let airdrop_id = vesting_client.create_airdrop(&root, &5_000, &1_000);
+
+    let new_token = deploy_token_contract(&env, &admin);
+    vesting_client.update_vesting_token(&new_token.address);
+
+    env.ledger().with_mut(|li| li.timestamp = 50);
+    let index = vesting_client.claim_with_proof(
+        &recipients[1],
+        &airdrop_id,
+        &linear_curve(2_000),
+        &2_000,
+        &proofs[1],
+    );
+    assert_eq!(token_client.balance(&recipients[1]), 1_000);
+    assert_eq!(
+        vesting_client
+            .query_vesting_info(&recipients[1], &index)
+            .token,
+        token_client.address
+    );
+
+    env.ledger().with_mut(|li| li.timestamp = 1_000);
+    assert_eq!(vesting_client.close_airdrop(&airdrop_id), 3_000);
+    assert_eq!(token_client.balance(&admin), 8_000);
+    assert_eq!(new_token.balance(&admin), 0);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #762)")]
+fn close_airdrop_before_end_time() {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.cost_estimate().budget().reset_unlimited();
+
+    let admin = Address::generate(&env);
+    let recipients: [Address; 4] = core::array::from_fn(|_| Address::generate(&env));
+    let (root, _) = build_tree(&env, &recipients, [1_000, 2_000, 500, 1_500]);
+
+    let (vesting_client, _) = setup_vesting(&env, &admin);
+    let airdrop_id = vesting_client.create_airdrop(&root, &5_000, &1_000);
+
+    env.ledger().with_mut(|li| li.timestamp = 999);
+    vesting_client.close_airdrop(&airdrop_id);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #756)")]
+fn close_airdrop_twice() {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.cost_estimate().budget().reset_unlimited();
+
+    let admin = Address::generate(&env);
+    let recipients: [Address; 4] = core::array::from_fn(|_| Address::generate(&env));
+    let (root, _) = build_tree(&env, &recipients, [1_000, 2_000, 500, 1_500]);
+
+    let (vesting_client, _) = setup_vesting(&env, &admin);
+    let airdrop_id = vesting_client.create_airdrop(&root, &5_000, &1_000);
+
+    env.ledger().with_mut(|li| li.timestamp = 1_000);
+    vesting_client.close_airdrop(&airdrop_id);
+    vesting_client.close_airdrop(&airdrop_id);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #761)")]
+fn claim_with_proof_after_end_time() {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.cost_estimate().budget().reset_unlimited();
+
+    let admin = Address::generate(&env);
+    let recipients: [Address; 4] = core::array::from_fn(|_| Address::generate(&env));
+    let (root, proofs) = build_tree(&env, &recipients, [1_000, 2_000, 500, 1_500]);
+
+    let (vesting_client, _) = setup_vesting(&env, &admin);
+    let airdrop_id = vesting_client.create_airdrop(&root, &5_000, &1_000);
+
+    env.ledger().with_mut(|li| li.timestamp = 1_000);
+    vesting_client.claim_with_proof(
+        &recipients[0],
+        &airdrop_id,
+        &linear_curve(1_000),
+        &1_000,
+        &proofs[0],
+    );
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #761)")]
+fn create_airdrop_ending_in_the_past() {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.cost_estimate().budget().reset_unlimited();
+
+    let admin = Address::generate(&env);
+    let recipients: [Address; 4] = core::array::from_fn(|_| Address::generate(&env));
+    let (root, _) = build_tree(&env, &recipients, [1_000, 2_000, 500, 1_500]);
+
+    let (vesting_client, _) = setup_vesting(&env, &admin);
+    env.ledger().with_mut(|li| li.timestamp = 1_000);
+    vesting_client.create_airdrop(&root, &5_000, &1_000);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #708)")]
+fn claim_with_proof_over_funding() {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.cost_estimate().budget().reset_unlimited();
+
+    let admin = Address::generate(&env);
+    let recipients: [Address; 4] = core::array::from_fn(|_| Address::generate(&env));
+    let (root, proofs) = build_tree(&env, &recipients, [1_000, 2_000, 500, 1_500]);
+
+    let (vesting_client, _) = setup_vesting(&env, &admin);
+    // the tree commits to 5_000 tokens, but only 2_500 are funded
+    vesting_client.create_airdrop(&root, &2_500, &1_000);
+
+    vesting_client.claim_with_proof(&recipients[0], &0, &linear_curve(1_000), &1_000, &proofs[0]);
+    vesting_client.claim_with_proof(&recipients[1], &0, &linear_curve(2_000), &2_000, &proofs[1]);
+}
@@ -5,9 +5,9 @@ use soroban_sdk::{
 
 use crate::{
     contract::{Vesting, VestingClient},
-    storage::{MinterInfo, VestingSchedule, VestingTokenInfo},
+    storage::{MinterEmission, MinterInfo, VestingSchedule, VestingTokenInfo},
 };
-use curve::{Curve, SaturatingLinear};
+use curve::{Curve, PiecewiseLinear, SaturatingLinear, Step};
 
 use super::setup::deploy_token_contract;
 
@@ -103,6 +103,8 @@ fn burn_works() {
                 max_x: 60,
                 max_y: 0,
             }),
+            revocable: None,
+            token: None,
         },
     ];
 
@@ -118,10 +120,10 @@ fn burn_works() {
     vesting_client.create_vesting_schedules(&vesting_schedules);
 
     env.ledger().with_mut(|li| li.timestamp = 100);
-    assert_eq!(vesting_client.query_vesting_contract_balance(), 120);
+    assert_eq!(vesting_client.query_vesting_contract_balance(&None), 120);
 
     vesting_client.claim(&vester1, &0);
-    assert_eq!(vesting_client.query_vesting_contract_balance(), 0);
+    assert_eq!(vesting_client.query_vesting_contract_balance(&None), 0);
     assert_eq!(token.balance(&vester1), 120);
 
     vesting_client.burn(&vester1, &120);
@@ -188,6 +190,8 @@ fn mint_works() {
                 max_x: 60,
                 max_y: 0,
             }),
+            revocable: None,
+            token: None,
         },
     ];
 
@@ -202,7 +206,7 @@ fn mint_works() {
     vesting_client.create_vesting_schedules(&vesting_schedules);
 
     // we start with 120 tokens minted to the contract
-    assert_eq!(vesting_client.query_vesting_contract_balance(), 120);
+    assert_eq!(vesting_client.query_vesting_contract_balance(&None), 120);
     // amdin should have none
     assert_eq!(token.balance(&admin), 0);
 
@@ -213,16 +217,16 @@ fn mint_works() {
     env.ledger().with_mut(|li| li.timestamp = 100);
     vesting_client.claim(&vester1, &0);
     assert_eq!(token.balance(&vester1), 120);
-    assert_eq!(vesting_client.query_vesting_contract_balance(), 0);
+    assert_eq!(vesting_client.query_vesting_contract_balance(&None), 0);
 
     // minter decides to mint new 250 tokens
     vesting_client.mint(&minter, &250);
-    assert_eq!(vesting_client.query_vesting_contract_balance(), 250);
+    assert_eq!(vesting_client.query_vesting_contract_balance(&None), 250);
     assert_eq!(vesting_client.query_minter().mint_capacity, 250);
 
     // we mint 250 more tokens
     vesting_client.mint(&minter, &250);
-    assert_eq!(vesting_client.query_vesting_contract_balance(), 500);
+    assert_eq!(vesting_client.query_vesting_contract_balance(&None), 500);
     assert_eq!(vesting_client.query_minter().mint_capacity, 0);
 }
 
@@ -511,3 +515,173 @@ fn burning_more_than_balance() {
     // vester1 tries to burn 121 tokens
     vesting_client.burn(&Address::generate(&env), &121);
 }
+
+/// Cumulative emissions starting at 100 that halve every 100 seconds: 1_000
+/// tokens in the first period, 500 in the second and 250 in the third.
+fn halving_emission_curve(env: &Env) -> Curve {
+    Curve::PiecewiseLinear(PiecewiseLinear {
+        steps: vec![
+            env,
+            Step {
+                time: 100,
+                value: 0,
+            },
+            Step {
+                time: 200,
+                value: 1_000,
+            },
+            Step {
+                time: 300,
+                value: 1_500,
+            },
+            Step {
+                time: 400,
+                value: 1_750,
+            },
+        ],
+    })
+}
+
+fn setup_minter<'a>(env: &Env, admin: &Address, minter: &Address) -> VestingClient<'a> {
+    let token = deploy_token_contract(env, admin);
+
+    let vesting_token = VestingTokenInfo {
+        name: String::from_str(env, "Token"),
+        symbol: String::from_str(env, "TOK"),
+        decimals: 6,
+        address: token.address.clone(),
+    };
+
+    let minter_info = MinterInfo {
+        address: minter.clone(),
+        mint_capacity: 10_000,
+    };
+
+    VestingClient::new(
+        env,
+        &env.register(Vesting, (admin, vesting_token, &10u32, minter_info)),
+    )
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #710)")]
+fn update_vesting_token_with_minter_fails() {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.cost_estimate().budget().reset_unlimited();
+
+    let admin = Address::generate(&env);
+    let minter = Address::generate(&env);
+    let vesting_client = setup_minter(&env, &admin, &minter);
+
+    let new_token = deploy_token_contract(&env, &admin);
+    vesting_client.update_vesting_token(&new_token.address);
+}
+
+#[test]
+fn mint_follows_emission_curve() {
+    let env = Env::default();
+    env.mock_all_auths_allowing_non_root_auth();
+    env.cost_estimate().budget().reset_unlimited();
+
+    let admin = Address::generate(&env);
+    let minter = Address::generate(&env);
+    let vesting_client = setup_minter(&env, &admin, &minter);
+
+    // without an emission curve only the capacity applies
+    assert_eq!(vesting_client.query_mintable_amount(), 10_000);
+    assert_eq!(vesting_client.query_minter_emission(), None);
+
+    vesting_client.set_minter_emission_curve(&admin, &halving_emission_curve(&env));
+    assert_eq!(vesting_client.query_mintable_amount(), 0);
+
+    env.ledger().with_mut(|li| li.timestamp = 150);
+    assert_eq!(vesting_client.query_mintable_amount(), 500);
+    vesting_client.mint(&minter, &400);
+    assert_eq!(vesting_client.query_mintable_amount(), 100);
+    assert_eq!(vesting_client.query_minter().mint_capacity, 9_600);
+
+    env.ledger().with_mut(|li| li.timestamp = 250);
+    assert_eq!(vesting_client.query_mintable_amount(), 850);
+    vesting_client.mint(&minter, &850);
+    assert_eq!(vesting_client.query_mintable_amount(), 0);
+
+    // emissions stop at the end of the curve
+    env.ledger().with_mut(|li| li.timestamp = 1_000);
+    assert_eq!(vesting_client.query_mintable_amount(), 500);
+    assert_eq!(
+        vesting_client.query_minter_emission(),
+        Some(MinterEmission {
+            curve: halving_emission_curve(&env),
+            minted: 1_250,
+        })
+    );
+    assert_eq!(vesting_client.query_vesting_contract_balance(&None), 1_250);
+}
+
+#[test]
+#[should_panic(expected = "Vesting: Mint: Minting would exceed the emission schedule")]
+fn mint_over_emission_curve_fails() {
+    let env = Env::default();
+    env.mock_all_auths_allowing_non_root_auth();
+    env.cost_estimate().budget().reset_unlimited();
+
+    let admin = Address::generate(&env);
+    let minter = Address::generate(&env);
+    let vesting_client = setup_minter(&env, &admin, &minter);
+    vesting_client.set_minter_emission_curve(&admin, &halving_emission_curve(&env));
+
+    env.ledger().with_mut(|li| li.timestamp = 150);
+    vesting_client.mint(&minter, &501);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #730)")]
+fn emission_curve_can_only_be_set_once() {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.cost_estimate().budget().reset_unlimited();
+
+    let admin = Address::generate(&env);
+    let vesting_client = setup_minter(&env, &admin, &Address::generate(&env));
+
+    vesting_client.set_minter_emission_curve(&admin, &halving_emission_curve(&env));
+    vesting_client.set_minter_emission_curve(&admin, &Curve::Constant(1_000_000));
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #719)")]
+fn emission_curve_must_be_increasing() {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.cost_estimate().budget().reset_unlimited();
+
+    let admin = Address::generate(&env);
+    let vesting_client = setup_minter(&env, &admin, &Address::generate(&env));
+
+    vesting_client.set_minter_emission_curve(
+        &admin,
+        &Curve::SaturatingLinear(SaturatingLinear {
+            min_x: 0,
+            min_y: 1_000,
+            max_x: 100,
+            max_y: 0,
+        }),
+    );
+}
+
+#[test]
+#[should_panic(
+    expected = "Vesting: Set Minter Emission Curve: Only contract's admin can set the emission curve"
+)]
+fn emission_curve_set_by_non_admin_fails() {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.cost_estimate().budget().reset_unlimited();
+
+    let admin = Address::generate(&env);
+    let minter = Address::generate(&env);
+    let vesting_client = setup_minter(&env, &admin, &minter);
+
+    vesting_client.set_minter_emission_curve(&minter, &halving_emission_curve(&env));
+}
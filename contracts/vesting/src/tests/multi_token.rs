@@ -0,0 +1,295 @@
+use crate::{
+    contract::{Vesting, VestingClient},
+    storage::{
+        ClaimedVesting, MinterInfo, ScheduleTokenKey, VestingInfoKey, VestingSchedule,
+        VestingTokenInfo,
+    },
+    token_contract,
+};
+use curve::{Curve, SaturatingLinear};
+
+use soroban_sdk::{
+    testutils::{Address as _, Ledger},
+    vec, Address, Env, String,
+};
+
+use super::setup::deploy_token_contract;
+
+fn linear_curve(amount: u128) -> Curve {
+    Curve::SaturatingLinear(SaturatingLinear {
+        min_x: 0,
+        min_y: amount,
+        max_x: 100,
+        max_y: 0,
+    })
+}
+
+fn schedule(recipient: &Address, amount: u128, token: Option<Address>) -> VestingSchedule {
+    VestingSchedule {
+        recipient: recipient.clone(),
+        curve: linear_curve(amount),
+        revocable: Some(true),
+        token,
+    }
+}
+
+/// Deploys a vesting contract with PHO as the default token and funds the
+/// admin with 2_000 PHO and 2_000 of a partner token.
+fn setup_vesting<'a>(
+    env: &Env,
+    admin: &Address,
+) -> (
+    VestingClient<'a>,
+    token_contract::Client<'a>,
+    token_contract::Client<'a>,
+) {
+    let pho = deploy_token_contract(env, admin);
+    pho.mint(admin, &2_000);
+    let partner = deploy_token_contract(env, admin);
+    partner.mint(admin, &2_000);
+
+    let vesting_token = VestingTokenInfo {
+        name: String::from_str(env, "Phoenix"),
+        symbol: String::from_str(env, "PHO"),
+        decimals: 6,
+        address: pho.address.clone(),
+    };
+
+    let vesting_client = VestingClient::new(
+        env,
+        &env.register(Vesting, (admin, vesting_token, &10u32, None::<MinterInfo>)),
+    );
+
+    (vesting_client, pho, partner)
+}
+
+#[test]
+fn schedules_vest_their_own_token() {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.cost_estimate().budget().reset_unlimited();
+
+    let admin = Address::generate(&env);
+    let vester = Address::generate(&env);
+    let other = Address::generate(&env);
+    let (vesting_client, pho, partner) = setup_vesting(&env, &admin);
+
+    vesting_client.create_vesting_schedules(&vec![
+        &env,
+        schedule(&vester, 1_000, None),
+        schedule(&other, 500, Some(partner.address.clone())),
+    ]);
+    vesting_client.create_vesting_schedules(&vec![
+        &env,
+        schedule(&vester, 300, Some(partner.address.clone())),
+    ]);
+
+    assert_eq!(pho.balance(&admin), 1_000);
+    assert_eq!(partner.balance(&admin), 1_200);
+    assert_eq!(vesting_client.query_vesting_contract_balance(&None), 1_000);
+    assert_eq!(
+        vesting_client.query_vesting_contract_balance(&Some(partner.address.clone())),
+        800
+    );
+    assert_eq!(
+        vesting_client.query_vesting_info(&vester, &0).token,
+        pho.address
+    );
+    assert_eq!(
+        vesting_client.query_vesting_info(&vester, &1).token,
+        partner.address
+    );
+
+    env.ledger().with_mut(|li| li.timestamp = 50);
+    vesting_client.claim(&other, &0);
+    assert_eq!(partner.balance(&other), 250);
+    assert_eq!(pho.balance(&other), 0);
+
+    assert_eq!(
+        vesting_client.claim_all(&vester),
+        vec![
+            &env,
+            ClaimedVesting {
+                index: 0,
+                amount: 500,
+                token: pho.address.clone(),
+            },
+            ClaimedVesting {
+                index: 1,
+                amount: 150,
+                token: partner.address.clone(),
+            },
+        ]
+    );
+    assert_eq!(pho.balance(&vester), 500);
+    assert_eq!(partner.balance(&vester), 150);
+}
+
+#[test]
+fn default_token_schedules_keep_their_token_after_update() {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.cost_estimate().budget().reset_unlimited();
+
+    let admin = Address::generate(&env);
+    let vester = Address::generate(&env);
+    let (vesting_client, pho, partner) = setup_vesting(&env, &admin);
+
+    vesting_client.create_vesting_schedules(&vec![&env, schedule(&vester, 1_000, None)]);
+    vesting_client.update_vesting_token(&partner.address);
+
+    assert_eq!(
+        vesting_client.query_vesting_info(&vester, &0).token,
+        pho.address
+    );
+    env.ledger().with_mut(|li| li.timestamp = 50);
+    vesting_client.claim(&vester, &0);
+    assert_eq!(pho.balance(&vester), 500);
+    assert_eq!(partner.balance(&vester), 0);
+}
+
+/// Drops the token of a schedule, the way schedules were stored before they
+/// kept their own token.
+fn make_legacy(env: &Env, vesting_client: &VestingClient, recipient: &Address, index: u64) {
+    env.as_contract(&vesting_client.address, || {
+        env.storage()
+            .persistent()
+            .remove(&ScheduleTokenKey::Token(VestingInfoKey {
+                recipient: recipient.clone(),
+                index,
+            }));
+    });
+}
+
+#[test]
+fn migrated_schedules_keep_their_token_after_update() {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.cost_estimate().budget().reset_unlimited();
+
+    let admin = Address::generate(&env);
+    let vester = Address::generate(&env);
+    let (vesting_client, pho, partner) = setup_vesting(&env, &admin);
+
+    vesting_client.create_vesting_schedules(&vec![&env, schedule(&vester, 1_000, None)]);
+    make_legacy(&env, &vesting_client, &vester, 0);
+
+    let key = VestingInfoKey {
+        recipient: vester.clone(),
+        index: 0,
+    };
+    vesting_client.migrate_vesting_tokens(&vec![&env, key]);
+    vesting_client.update_vesting_token(&partner.address);
+
+    assert_eq!(
+        vesting_client.query_vesting_info(&vester, &0).token,
+        pho.address
+    );
+    env.ledger().with_mut(|li| li.timestamp = 50);
+    vesting_client.claim(&vester, &0);
+    assert_eq!(pho.balance(&vester), 500);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #721)")]
+fn claim_of_schedule_without_token_fails() {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.cost_estimate().budget().reset_unlimited();
+
+    let admin = Address::generate(&env);
+    let vester = Address::generate(&env);
+    let (vesting_client, _, _) = setup_vesting(&env, &admin);
+
+    vesting_client.create_vesting_schedules(&vec![&env, schedule(&vester, 1_000, None)]);
+    make_legacy(&env, &vesting_client, &vester, 0);
+
+    env.ledger().with_mut(|li| li.timestamp = 50);
+    vesting_client.claim(&vester, &0);
+}
+
+#[test]
+fn revoke_and_transfer_keep_the_schedule_token() {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.cost_estimate().budget().reset_unlimited();
+
+    let admin = Address::generate(&env);
+    let vester = Address::generate(&env);
+    let custody = Address::generate(&env);
+    let (vesting_client, pho, partner) = setup_vesting(&env, &admin);
+
+    vesting_client.create_vesting_schedules(&vec![
+        &env,
+        schedule(&vester, 1_000, Some(partner.address.clone())),
+    ]);
+
+    vesting_client.set_vesting_transferable(&vester, &0, &true);
+    let new_index = vesting_client.transfer_vesting(&vester, &0, &custody);
+    assert_eq!(
+        vesting_client
+            .query_vesting_info(&custody, &new_index)
+            .token,
+        partner.address
+    );
+
+    env.ledger().with_mut(|li| li.timestamp = 40);
+    vesting_client.revoke_vesting(&custody, &new_index, &admin);
+    assert_eq!(partner.balance(&custody), 400);
+    assert_eq!(partner.balance(&admin), 1_600);
+    assert_eq!(pho.balance(&admin), 2_000);
+}
+
+#[test]
+fn reporting_queries_are_per_token() {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.cost_estimate().budget().reset_unlimited();
+
+    let admin = Address::generate(&env);
+    let (vesting_client, _, partner) = setup_vesting(&env, &admin);
+
+    vesting_client.create_vesting_schedules(&vec![
+        &env,
+        schedule(&Address::generate(&env), 1_000, None),
+        schedule(&Address::generate(&env), 600, Some(partner.address.clone())),
+    ]);
+
+    let partner_token = Some(partner.address.clone());
+    assert_eq!(vesting_client.query_total_locked(&None, &None, &10), 1_000);
+    assert_eq!(
+        vesting_client.query_total_locked(&partner_token, &None, &10),
+        600
+    );
+    assert_eq!(
+        vesting_client.query_total_claimable_at(&50, &partner_token, &None, &10),
+        300
+    );
+    assert_eq!(
+        vesting_client
+            .query_unlock_calendar(&partner_token, &None, &10)
+            .value(25),
+        450
+    );
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #724)")]
+fn create_fails_without_enough_partner_tokens() {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.cost_estimate().budget().reset_unlimited();
+
+    let admin = Address::generate(&env);
+    let (vesting_client, _, partner) = setup_vesting(&env, &admin);
+
+    vesting_client.create_vesting_schedules(&vec![
+        &env,
+        schedule(&Address::generate(&env), 1_000, None),
+        schedule(
+            &Address::generate(&env),
+            2_500,
+            Some(partner.address.clone()),
+        ),
+    ]);
+}
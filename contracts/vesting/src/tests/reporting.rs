@@ -0,0 +1,231 @@
+use crate::{
+    contract::{Vesting, VestingClient},
+    storage::{MinterInfo, VestingSchedule, VestingTokenInfo},
+};
+use curve::{Curve, SaturatingLinear};
+
+use soroban_sdk::{
+    testutils::{Address as _, Ledger},
+    vec, Address, Env, String,
+};
+
+use super::setup::deploy_token_contract;
+
+fn linear_curve(min_x: u64, amount: u128, max_x: u64) -> Curve {
+    Curve::SaturatingLinear(SaturatingLinear {
+        min_x,
+        min_y: amount,
+        max_x,
+        max_y: 0,
+    })
+}
+
+fn schedule(recipient: &Address, curve: Curve) -> VestingSchedule {
+    VestingSchedule {
+        recipient: recipient.clone(),
+        curve,
+        revocable: None,
+        token: None,
+    }
+}
+
+/// `first` gets two schedules, `second` and `third` one each.
+fn setup_vesting<'a>(
+    env: &Env,
+    first: &Address,
+    second: &Address,
+    third: &Address,
+) -> VestingClient<'a> {
+    let admin = Address::generate(env);
+    let token_client = deploy_token_contract(env, &admin);
+    token_client.mint(&admin, &4_000);
+
+    let vesting_token = VestingTokenInfo {
+        name: String::from_str(env, "Phoenix"),
+        symbol: String::from_str(env, "PHO"),
+        decimals: 6,
+        address: token_client.address.clone(),
+    };
+
+    let vesting_client = VestingClient::new(
+        env,
+        &env.register(Vesting, (&admin, vesting_token, &10u32, None::<MinterInfo>)),
+    );
+    vesting_client.create_vesting_schedules(&vec![
+        env,
+        schedule(first, linear_curve(0, 1_000, 100)),
+        schedule(second, linear_curve(50, 2_000, 150)),
+        schedule(third, linear_curve(0, 600, 200)),
+    ]);
+    vesting_client
+        .create_vesting_schedules(&vec![env, schedule(first, linear_curve(100, 400, 200))]);
+
+    vesting_client
+}
+
+#[test]
+fn totals_over_all_schedules() {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.cost_estimate().budget().reset_unlimited();
+
+    let first = Address::generate(&env);
+    let vesting_client = setup_vesting(
+        &env,
+        &first,
+        &Address::generate(&env),
+        &Address::generate(&env),
+    );
+
+    assert_eq!(vesting_client.query_total_locked(&None, &None, &10), 4_000);
+    assert_eq!(
+        vesting_client.query_total_claimable_at(&0, &None, &None, &10),
+        0
+    );
+
+    env.ledger().with_mut(|li| li.timestamp = 50);
+    vesting_client.claim(&first, &0);
+
+    assert_eq!(vesting_client.query_total_locked(&None, &None, &10), 3_350);
+    assert_eq!(
+        vesting_client.query_total_claimable_at(&50, &None, &None, &10),
+        150
+    );
+    assert_eq!(
+        vesting_client.query_total_claimable_at(&100, &None, &None, &10),
+        1_800
+    );
+    // everything left once all curves ended
+    assert_eq!(
+        vesting_client.query_total_claimable_at(&1_000, &None, &None, &10),
+        3_500
+    );
+}
+
+#[test]
+fn totals_are_paginated_by_recipient() {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.cost_estimate().budget().reset_unlimited();
+
+    let first = Address::generate(&env);
+    let second = Address::generate(&env);
+    let vesting_client = setup_vesting(&env, &first, &second, &Address::generate(&env));
+
+    // both schedules of `first` land on the same page
+    assert_eq!(vesting_client.query_total_locked(&None, &None, &2), 3_400);
+    assert_eq!(
+        vesting_client.query_total_locked(&None, &Some(second.clone()), &2),
+        600
+    );
+    assert_eq!(
+        vesting_client.query_total_claimable_at(&1_000, &None, &Some(first.clone()), &1),
+        2_000
+    );
+    assert_eq!(
+        vesting_client
+            .query_unlock_calendar(&None, &Some(first), &1)
+            .value(100),
+        1_000
+    );
+}
+
+#[test]
+fn unlock_calendar_combines_all_curves() {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.cost_estimate().budget().reset_unlimited();
+
+    let first = Address::generate(&env);
+    let vesting_client = setup_vesting(
+        &env,
+        &first,
+        &Address::generate(&env),
+        &Address::generate(&env),
+    );
+
+    env.ledger().with_mut(|li| li.timestamp = 50);
+    vesting_client.claim(&first, &0);
+
+    let calendar = vesting_client.query_unlock_calendar(&None, &None, &10);
+    assert_eq!(
+        calendar.value(50),
+        vesting_client.query_total_locked(&None, &None, &10) as u128
+    );
+    assert_eq!(calendar.value(100), 1_700);
+    assert_eq!(calendar.value(150), 350);
+    assert_eq!(calendar.value(200), 0);
+}
+
+#[test]
+fn unlock_calendar_without_schedules() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let token_client = deploy_token_contract(&env, &admin);
+    let vesting_token = VestingTokenInfo {
+        name: String::from_str(&env, "Phoenix"),
+        symbol: String::from_str(&env, "PHO"),
+        decimals: 6,
+        address: token_client.address.clone(),
+    };
+    let vesting_client = VestingClient::new(
+        &env,
+        &env.register(Vesting, (&admin, vesting_token, &10u32, None::<MinterInfo>)),
+    );
+
+    assert_eq!(
+        vesting_client.query_unlock_calendar(&None, &None, &10),
+        Curve::Constant(0)
+    );
+    assert_eq!(vesting_client.query_total_locked(&None, &None, &10), 0);
+    assert_eq!(vesting_client.query_recipients(&None, &10), vec![&env]);
+}
+
+#[test]
+fn recipients_are_paginated() {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.cost_estimate().budget().reset_unlimited();
+
+    let first = Address::generate(&env);
+    let second = Address::generate(&env);
+    let third = Address::generate(&env);
+    let vesting_client = setup_vesting(&env, &first, &second, &third);
+
+    // recipients with several schedules are only listed once
+    assert_eq!(
+        vesting_client.query_recipients(&None, &500),
+        vec![&env, first.clone(), second.clone(), third.clone()]
+    );
+    assert_eq!(
+        vesting_client.query_recipients(&None, &2),
+        vec![&env, first, second.clone()]
+    );
+    assert_eq!(
+        vesting_client.query_recipients(&Some(second), &2),
+        vec![&env, third.clone()]
+    );
+    assert_eq!(
+        vesting_client.query_recipients(&Some(third), &2),
+        vec![&env]
+    );
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #700)")]
+fn recipients_start_after_unknown_address() {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.cost_estimate().budget().reset_unlimited();
+
+    let vesting_client = setup_vesting(
+        &env,
+        &Address::generate(&env),
+        &Address::generate(&env),
+        &Address::generate(&env),
+    );
+
+    vesting_client.query_recipients(&Some(Address::generate(&env)), &10);
+}
@@ -0,0 +1,142 @@
+use crate::{
+    contract::{Vesting, VestingClient},
+    storage::{MinterInfo, VestingSchedule, VestingTokenInfo},
+    token_contract,
+};
+use curve::{Curve, SaturatingLinear};
+
+use soroban_sdk::{
+    testutils::{Address as _, Ledger},
+    vec, Address, Env, String,
+};
+
+use super::setup::deploy_token_contract;
+
+/// Creates a revocable 1_000 token schedule vesting linearly over 100 seconds
+/// for `vester` and an irrevocable one of the same shape for `other`.
+fn setup_vesting<'a>(
+    env: &Env,
+    admin: &Address,
+    vester: &Address,
+    other: &Address,
+) -> (VestingClient<'a>, token_contract::Client<'a>) {
+    let token_client = deploy_token_contract(env, admin);
+    token_client.mint(admin, &2_000);
+
+    let vesting_token = VestingTokenInfo {
+        name: String::from_str(env, "Phoenix"),
+        symbol: String::from_str(env, "PHO"),
+        decimals: 6,
+        address: token_client.address.clone(),
+    };
+
+    let curve = Curve::SaturatingLinear(SaturatingLinear {
+        min_x: 0,
+        min_y: 1_000,
+        max_x: 100,
+        max_y: 0,
+    });
+    let vesting_schedules = vec![
+        env,
+        VestingSchedule {
+            recipient: vester.clone(),
+            curve: curve.clone(),
+            revocable: Some(true),
+            token: None,
+        },
+        VestingSchedule {
+            recipient: other.clone(),
+            curve,
+            revocable: None,
+            token: None,
+        },
+    ];
+
+    let vesting_client = VestingClient::new(
+        env,
+        &env.register(Vesting, (admin, vesting_token, &10u32, None::<MinterInfo>)),
+    );
+    vesting_client.create_vesting_schedules(&vesting_schedules);
+
+    (vesting_client, token_client)
+}
+
+#[test]
+fn revoke_pays_claimable_to_recipient_and_unvested_to_beneficiary() {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.cost_estimate().budget().reset_unlimited();
+
+    let admin = Address::generate(&env);
+    let vester = Address::generate(&env);
+    let beneficiary = Address::generate(&env);
+    let (vesting_client, token_client) =
+        setup_vesting(&env, &admin, &vester, &Address::generate(&env));
+
+    assert!(vesting_client.query_vesting_info(&vester, &0).revocable);
+
+    env.ledger().with_mut(|li| li.timestamp = 30);
+    vesting_client.claim(&vester, &0);
+    assert_eq!(token_client.balance(&vester), 300);
+
+    env.ledger().with_mut(|li| li.timestamp = 40);
+    vesting_client.revoke_vesting(&vester, &0, &beneficiary);
+
+    assert_eq!(token_client.balance(&vester), 400);
+    assert_eq!(token_client.balance(&beneficiary), 600);
+    // only the irrevocable schedule is left in the contract
+    assert_eq!(token_client.balance(&vesting_client.address), 1_000);
+    assert_eq!(vesting_client.query_all_vesting_info(&vester), vec![&env]);
+}
+
+#[test]
+fn revoke_after_fully_vested_pays_everything_to_recipient() {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.cost_estimate().budget().reset_unlimited();
+
+    let admin = Address::generate(&env);
+    let vester = Address::generate(&env);
+    let beneficiary = Address::generate(&env);
+    let (vesting_client, token_client) =
+        setup_vesting(&env, &admin, &vester, &Address::generate(&env));
+
+    env.ledger().with_mut(|li| li.timestamp = 100);
+    vesting_client.revoke_vesting(&vester, &0, &beneficiary);
+
+    assert_eq!(token_client.balance(&vester), 1_000);
+    assert_eq!(token_client.balance(&beneficiary), 0);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #700)")]
+fn claim_after_revoke_fails() {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.cost_estimate().budget().reset_unlimited();
+
+    let admin = Address::generate(&env);
+    let vester = Address::generate(&env);
+    let (vesting_client, _) = setup_vesting(&env, &admin, &vester, &Address::generate(&env));
+
+    env.ledger().with_mut(|li| li.timestamp = 50);
+    vesting_client.revoke_vesting(&vester, &0, &admin);
+
+    env.ledger().with_mut(|li| li.timestamp = 100);
+    vesting_client.claim(&vester, &0);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #747)")]
+fn revoke_irrevocable_schedule_fails() {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.cost_estimate().budget().reset_unlimited();
+
+    let admin = Address::generate(&env);
+    let other = Address::generate(&env);
+    let (vesting_client, _) = setup_vesting(&env, &admin, &Address::generate(&env), &other);
+
+    assert!(!vesting_client.query_vesting_info(&other, &0).revocable);
+    vesting_client.revoke_vesting(&other, &0, &admin);
+}
@@ -3,7 +3,7 @@ use soroban_sdk::{
     Address, BytesN, Env,
 };
 
-use crate::{contract::VestingClient, storage::Config, token_contract};
+use crate::{contract::VestingClient, stake_contract, storage::Config, token_contract};
 
 pub mod old_vesting {
     soroban_sdk::contractimport!(file = "../../.wasm_binaries_mainnet/live_vesting.wasm");
@@ -24,12 +24,26 @@ pub fn deploy_token_contract<'a>(env: &Env, admin: &Address) -> token_contract::
     )
 }
 
+pub fn deploy_stake_contract<'a>(
+    env: &Env,
+    admin: &Address,
+    lp_token: &Address,
+) -> stake_contract::Client<'a> {
+    stake_contract::Client::new(
+        env,
+        &env.register(
+            stake_contract::WASM,
+            (admin, lp_token, &100i128, &100i128, admin, admin, &10u32),
+        ),
+    )
+}
+
 #[test]
 #[allow(deprecated)]
 fn upgrade_vesting_contract() {
     use soroban_sdk::{vec, String};
 
-    use crate::tests::setup::deploy_token_contract;
+    use crate::{storage::VestingInfoKey, tests::setup::deploy_token_contract};
 
     let env = Env::default();
     env.mock_all_auths();
@@ -93,6 +107,14 @@ fn upgrade_vesting_contract() {
     let latest_vesting = VestingClient::new(&env, &vesting_addr);
 
     latest_vesting.migrate_config(&false);
+    // the schedule was created before schedules kept their own token
+    latest_vesting.migrate_vesting_tokens(&vec![
+        &env,
+        VestingInfoKey {
+            recipient: vester1.clone(),
+            index: 0,
+        },
+    ]);
 
     let actual_config = latest_vesting.query_config();
     assert_eq!(
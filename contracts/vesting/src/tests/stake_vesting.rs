@@ -0,0 +1,341 @@
+use crate::{
+    contract::{Vesting, VestingClient},
+    stake_contract,
+    storage::{MinterInfo, VestingSchedule, VestingTokenInfo},
+    token_contract,
+};
+use curve::{Curve, SaturatingLinear};
+
+use soroban_sdk::{
+    testutils::{Address as _, Ledger},
+    vec, Address, Env, String,
+};
+
+use super::setup::{deploy_stake_contract, deploy_token_contract};
+
+const ONE_DAY: u64 = 86_400;
+const SIXTY_DAYS: u64 = 60 * ONE_DAY;
+
+struct StakeVestingSetup<'a> {
+    admin: Address,
+    vester1: Address,
+    vester2: Address,
+    vesting: VestingClient<'a>,
+    token: token_contract::Client<'a>,
+    reward_token: token_contract::Client<'a>,
+    stake: stake_contract::Client<'a>,
+}
+
+/// 1_000 tokens for `vester1` and 3_000 for `vester2`, both vesting linearly
+/// over 100 days, and a whitelisted stake contract for the vesting token.
+fn setup<'a>(env: &Env) -> StakeVestingSetup<'a> {
+    env.mock_all_auths();
+    env.cost_estimate().budget().reset_unlimited();
+
+    let admin = Address::generate(env);
+    let vester1 = Address::generate(env);
+    let vester2 = Address::generate(env);
+    let token = deploy_token_contract(env, &admin);
+    let reward_token = deploy_token_contract(env, &admin);
+    token.mint(&admin, &4_000);
+    reward_token.mint(&admin, &1_000_000);
+
+    let vesting_token = VestingTokenInfo {
+        name: String::from_str(env, "Phoenix"),
+        symbol: String::from_str(env, "PHO"),
+        decimals: 6,
+        address: token.address.clone(),
+    };
+    let vesting = VestingClient::new(
+        env,
+        &env.register(Vesting, (&admin, vesting_token, &10u32, None::<MinterInfo>)),
+    );
+
+    let schedule = |recipient: &Address, amount: u128| VestingSchedule {
+        recipient: recipient.clone(),
+        curve: Curve::SaturatingLinear(SaturatingLinear {
+            min_x: 0,
+            min_y: amount,
+            max_x: 100 * ONE_DAY,
+            max_y: 0,
+        }),
+        revocable: None,
+        token: None,
+    };
+    vesting.create_vesting_schedules(&vec![
+        env,
+        schedule(&vester1, 1_000),
+        schedule(&vester2, 3_000),
+    ]);
+
+    let stake = deploy_stake_contract(env, &admin, &token.address);
+    stake.create_distribution_flow(&admin, &reward_token.address);
+    vesting.update_stake_whitelist(&vec![env, stake.address.clone()], &vec![env]);
+
+    StakeVestingSetup {
+        admin,
+        vester1,
+        vester2,
+        vesting,
+        token,
+        reward_token,
+        stake,
+    }
+}
+
+#[test]
+fn bonded_vesting_rewards_are_split_between_recipients() {
+    let env = Env::default();
+    let StakeVestingSetup {
+        admin,
+        vester1,
+        vester2,
+        vesting,
+        token,
+        reward_token,
+        stake,
+    } = setup(&env);
+
+    vesting.bond_vesting(&vester1, &0, &stake.address, &1_000);
+    vesting.bond_vesting(&vester2, &0, &stake.address, &3_000);
+
+    assert_eq!(token.balance(&stake.address), 4_000);
+    assert_eq!(token.balance(&vesting.address), 0);
+    assert_eq!(stake.query_staked(&vesting.address).total_stake, 4_000);
+    assert_eq!(
+        vesting.query_vesting_bond(&vester1, &0).unwrap().amount,
+        1_000
+    );
+    assert_eq!(vesting.query_available_to_claim(&vester1, &0), 0);
+
+    env.ledger().with_mut(|li| li.timestamp = SIXTY_DAYS);
+    stake.distribute_rewards(&admin, &4_000, &reward_token.address);
+    env.ledger().with_mut(|li| li.timestamp += ONE_DAY);
+
+    vesting.withdraw_vesting_rewards(&vester1, &0);
+    assert_eq!(reward_token.balance(&vester1), 1_000);
+    // vester2's share waits in the vesting contract until withdrawn
+    assert_eq!(reward_token.balance(&vesting.address), 3_000);
+
+    env.ledger().with_mut(|li| li.timestamp += ONE_DAY);
+    stake.distribute_rewards(&admin, &4_000, &reward_token.address);
+    env.ledger().with_mut(|li| li.timestamp += ONE_DAY);
+
+    // unbonding pays the pending rewards as well
+    vesting.unbond_vesting(&vester2, &0);
+    assert_eq!(reward_token.balance(&vester2), 6_000);
+    assert_eq!(vesting.query_vesting_bond(&vester2, &0), None);
+
+    vesting.withdraw_vesting_rewards(&vester1, &0);
+    assert_eq!(reward_token.balance(&vester1), 2_000);
+    assert_eq!(reward_token.balance(&vesting.address), 0);
+}
+
+#[test]
+fn bonded_vesting_earns_the_rewards_of_its_own_stake() {
+    let env = Env::default();
+    let StakeVestingSetup {
+        admin,
+        vester1,
+        vester2,
+        vesting,
+        reward_token,
+        stake,
+        ..
+    } = setup(&env);
+
+    vesting.bond_vesting(&vester1, &0, &stake.address, &1_000);
+    env.ledger().with_mut(|li| li.timestamp = 30 * ONE_DAY);
+    vesting.bond_vesting(&vester2, &0, &stake.address, &1_000);
+    assert_eq!(
+        vesting
+            .query_vesting_bond(&vester2, &0)
+            .unwrap()
+            .stake_timestamp,
+        30 * ONE_DAY
+    );
+
+    env.ledger().with_mut(|li| li.timestamp = SIXTY_DAYS);
+    stake.distribute_rewards(&admin, &2_000, &reward_token.address);
+    env.ledger().with_mut(|li| li.timestamp += ONE_DAY);
+
+    // the stake of vester2 is 30 days old and earns half of its share
+    vesting.withdraw_vesting_rewards(&vester2, &0);
+    assert_eq!(reward_token.balance(&vester2), 500);
+
+    // vester1 unbonds its own stake, not the newest one
+    vesting.unbond_vesting(&vester1, &0);
+    assert_eq!(reward_token.balance(&vester1), 1_000);
+    assert_eq!(
+        stake.query_staked(&vesting.address).stakes,
+        vec![
+            &env,
+            stake_contract::Stake {
+                stake: 1_000,
+                stake_timestamp: 30 * ONE_DAY,
+            }
+        ]
+    );
+    assert_eq!(
+        vesting.query_vesting_bond(&vester2, &0).unwrap().amount,
+        1_000
+    );
+}
+
+#[test]
+fn claim_after_unbonding() {
+    let env = Env::default();
+    let StakeVestingSetup {
+        vester1,
+        vesting,
+        token,
+        stake,
+        ..
+    } = setup(&env);
+
+    vesting.bond_vesting(&vester1, &0, &stake.address, &1_000);
+
+    env.ledger().with_mut(|li| li.timestamp = 50 * ONE_DAY);
+    vesting.unbond_vesting(&vester1, &0);
+    assert_eq!(token.balance(&vesting.address), 4_000);
+    assert_eq!(vesting.query_available_to_claim(&vester1, &0), 500);
+
+    vesting.claim(&vester1, &0);
+    assert_eq!(token.balance(&vester1), 500);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #753)")]
+fn claim_while_bonded_fails() {
+    let env = Env::default();
+    let StakeVestingSetup {
+        vester1,
+        vesting,
+        stake,
+        ..
+    } = setup(&env);
+
+    vesting.bond_vesting(&vester1, &0, &stake.address, &500);
+
+    env.ledger().with_mut(|li| li.timestamp = 50 * ONE_DAY);
+    vesting.claim(&vester1, &0);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #750)")]
+fn bond_into_not_whitelisted_stake_fails() {
+    let env = Env::default();
+    let StakeVestingSetup {
+        admin,
+        vester1,
+        vesting,
+        token,
+        ..
+    } = setup(&env);
+
+    let other_stake = deploy_stake_contract(&env, &admin, &token.address);
+    vesting.bond_vesting(&vester1, &0, &other_stake.address, &500);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #751)")]
+fn bond_into_stake_of_other_token_fails() {
+    let env = Env::default();
+    let StakeVestingSetup {
+        admin,
+        vester1,
+        vesting,
+        reward_token,
+        ..
+    } = setup(&env);
+
+    let other_stake = deploy_stake_contract(&env, &admin, &reward_token.address);
+    vesting.update_stake_whitelist(&vec![&env, other_stake.address.clone()], &vec![&env]);
+    vesting.bond_vesting(&vester1, &0, &other_stake.address, &500);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #755)")]
+fn bond_more_than_vesting_balance_fails() {
+    let env = Env::default();
+    let StakeVestingSetup {
+        vester1,
+        vesting,
+        stake,
+        ..
+    } = setup(&env);
+
+    vesting.bond_vesting(&vester1, &0, &stake.address, &1_001);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #753)")]
+fn bond_already_bonded_vesting_fails() {
+    let env = Env::default();
+    let StakeVestingSetup {
+        vester1,
+        vesting,
+        stake,
+        ..
+    } = setup(&env);
+
+    vesting.bond_vesting(&vester1, &0, &stake.address, &600);
+    vesting.bond_vesting(&vester1, &0, &stake.address, &400);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #754)")]
+fn unbond_without_bond_fails() {
+    let env = Env::default();
+    let StakeVestingSetup {
+        vester1, vesting, ..
+    } = setup(&env);
+
+    vesting.unbond_vesting(&vester1, &0);
+}
+
+#[test]
+fn revoke_bonded_vesting_unbonds_it_first() {
+    let env = Env::default();
+    let StakeVestingSetup {
+        admin,
+        vester1,
+        vesting,
+        token,
+        reward_token,
+        stake,
+        ..
+    } = setup(&env);
+
+    token.mint(&admin, &1_000);
+    vesting.create_vesting_schedules(&vec![
+        &env,
+        VestingSchedule {
+            recipient: vester1.clone(),
+            curve: Curve::SaturatingLinear(SaturatingLinear {
+                min_x: 0,
+                min_y: 1_000,
+                max_x: 100 * ONE_DAY,
+                max_y: 0,
+            }),
+            revocable: Some(true),
+            token: None,
+        },
+    ]);
+    // bonding doesn't shield the schedule from being revoked
+    vesting.bond_vesting(&vester1, &1, &stake.address, &1_000);
+
+    env.ledger().with_mut(|li| li.timestamp = SIXTY_DAYS);
+    stake.distribute_rewards(&admin, &1_000, &reward_token.address);
+    env.ledger().with_mut(|li| li.timestamp += ONE_DAY);
+
+    let beneficiary = Address::generate(&env);
+    vesting.revoke_vesting(&vester1, &1, &beneficiary);
+
+    assert_eq!(vesting.query_vesting_bond(&vester1, &1), None);
+    assert_eq!(stake.query_staked(&vesting.address).total_stake, 0);
+    // 61 of the 100 days have vested
+    assert_eq!(token.balance(&vester1), 610);
+    assert_eq!(token.balance(&beneficiary), 390);
+    assert_eq!(reward_token.balance(&vester1), 1_000);
+}
@@ -0,0 +1,157 @@
+use crate::{
+    contract::{Vesting, VestingClient},
+    storage::{MinterInfo, VestingInfoResponse, VestingSchedule, VestingTokenInfo},
+    token_contract,
+};
+use curve::{Curve, SaturatingLinear};
+
+use soroban_sdk::{
+    testutils::{Address as _, Ledger},
+    vec, Address, Env, String,
+};
+
+use super::setup::deploy_token_contract;
+
+fn curve() -> Curve {
+    Curve::SaturatingLinear(SaturatingLinear {
+        min_x: 0,
+        min_y: 1_000,
+        max_x: 100,
+        max_y: 0,
+    })
+}
+
+/// Creates a revocable 1_000 token schedule for `vester`, vesting over [0, 100].
+fn setup_vesting<'a>(
+    env: &Env,
+    admin: &Address,
+    vester: &Address,
+) -> (VestingClient<'a>, token_contract::Client<'a>) {
+    let token_client = deploy_token_contract(env, admin);
+    token_client.mint(admin, &1_000);
+
+    let vesting_token = VestingTokenInfo {
+        name: String::from_str(env, "Phoenix"),
+        symbol: String::from_str(env, "PHO"),
+        decimals: 6,
+        address: token_client.address.clone(),
+    };
+
+    let vesting_client = VestingClient::new(
+        env,
+        &env.register(Vesting, (admin, vesting_token, &10u32, None::<MinterInfo>)),
+    );
+    vesting_client.create_vesting_schedules(&vec![
+        env,
+        VestingSchedule {
+            recipient: vester.clone(),
+            curve: curve(),
+            revocable: Some(true),
+            token: None,
+        },
+    ]);
+
+    (vesting_client, token_client)
+}
+
+#[test]
+fn transfer_vesting_moves_remaining_balance_and_curve() {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.cost_estimate().budget().reset_unlimited();
+
+    let admin = Address::generate(&env);
+    let vester = Address::generate(&env);
+    let custody = Address::generate(&env);
+    let (vesting_client, token_client) = setup_vesting(&env, &admin, &vester);
+
+    env.ledger().with_mut(|li| li.timestamp = 20);
+    vesting_client.claim(&vester, &0);
+    assert_eq!(token_client.balance(&vester), 200);
+
+    vesting_client.set_vesting_transferable(&vester, &0, &true);
+    assert!(vesting_client.query_vesting_info(&vester, &0).transferable);
+
+    env.ledger().with_mut(|li| li.timestamp = 50);
+    let new_index = vesting_client.transfer_vesting(&vester, &0, &custody);
+
+    assert_eq!(new_index, 0);
+    assert_eq!(vesting_client.query_all_vesting_info(&vester), vec![&env]);
+    assert_eq!(
+        vesting_client.query_vesting_info(&custody, &new_index),
+        VestingInfoResponse {
+            balance: 800,
+            recipient: custody.clone(),
+            schedule: curve(),
+            index: 0,
+            revocable: true,
+            transferable: true,
+            token: token_client.address.clone(),
+        }
+    );
+
+    // the part vested before the transfer is claimable by the new owner
+    vesting_client.claim(&custody, &new_index);
+    assert_eq!(token_client.balance(&custody), 300);
+    assert_eq!(token_client.balance(&vester), 200);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #748)")]
+fn transfer_vesting_not_allowed_by_default() {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.cost_estimate().budget().reset_unlimited();
+
+    let admin = Address::generate(&env);
+    let vester = Address::generate(&env);
+    let (vesting_client, _) = setup_vesting(&env, &admin, &vester);
+
+    vesting_client.transfer_vesting(&vester, &0, &Address::generate(&env));
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #748)")]
+fn transfer_vesting_fails_after_admin_disallows_it() {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.cost_estimate().budget().reset_unlimited();
+
+    let admin = Address::generate(&env);
+    let vester = Address::generate(&env);
+    let (vesting_client, _) = setup_vesting(&env, &admin, &vester);
+
+    vesting_client.set_vesting_transferable(&vester, &0, &true);
+    vesting_client.set_vesting_transferable(&vester, &0, &false);
+
+    vesting_client.transfer_vesting(&vester, &0, &Address::generate(&env));
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #749)")]
+fn transfer_vesting_to_self() {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.cost_estimate().budget().reset_unlimited();
+
+    let admin = Address::generate(&env);
+    let vester = Address::generate(&env);
+    let (vesting_client, _) = setup_vesting(&env, &admin, &vester);
+
+    vesting_client.set_vesting_transferable(&vester, &0, &true);
+    vesting_client.transfer_vesting(&vester, &0, &vester);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #700)")]
+fn set_transferable_for_missing_schedule() {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.cost_estimate().budget().reset_unlimited();
+
+    let admin = Address::generate(&env);
+    let vester = Address::generate(&env);
+    let (vesting_client, _) = setup_vesting(&env, &admin, &vester);
+
+    vesting_client.set_vesting_transferable(&vester, &1, &true);
+}
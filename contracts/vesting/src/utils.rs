@@ -1,5 +1,5 @@
 use curve::Curve;
-use soroban_sdk::{log, panic_with_error, Address, Env, Vec};
+use soroban_sdk::{log, panic_with_error, xdr::ToXdr, Address, Bytes, BytesN, Env, Vec};
 
 use crate::{error::ContractError, storage::VestingSchedule};
 
@@ -52,12 +52,56 @@ pub fn validate_vesting_schedule(env: &Env, schedule: &Curve) -> Result<u128, Co
             // Return the amount to be distributed (value of the first step)
             Ok(pl.first_value().unwrap())
         }
+        Curve::Cliff(cliff) => {
+            if cliff.end_y != 0 {
+                log!(
+                    &env,
+                    "Vesting: Transfer Vesting: Cannot transfer when non-fully vested"
+                );
+                panic_with_error!(&env, ContractError::NeverFullyVested)
+            }
+
+            // nothing is released before the cliff
+            Ok(cliff.start_y)
+        }
     }
 }
 
+/// Leaf of the airdrop merkle tree: sha256 of the XDR encoded
+/// `(recipient, curve, amount)` tuple
+pub fn airdrop_leaf(env: &Env, recipient: &Address, curve: &Curve, amount: u128) -> BytesN<32> {
+    env.crypto()
+        .sha256(&(recipient.clone(), curve.clone(), amount).to_xdr(env))
+        .to_bytes()
+}
+
+/// Walks the proof from the leaf up to the root. Every pair is hashed in
+/// sorted order, so the proof doesn't need to carry the sibling's side.
+pub fn verify_merkle_proof(
+    env: &Env,
+    leaf: BytesN<32>,
+    proof: &Vec<BytesN<32>>,
+    root: &BytesN<32>,
+) -> bool {
+    let mut hash = leaf;
+    for sibling in proof.iter() {
+        let (first, second) = if hash <= sibling {
+            (hash, sibling)
+        } else {
+            (sibling, hash)
+        };
+        let mut data = Bytes::new(env);
+        data.append(&first.into());
+        data.append(&second.into());
+        hash = env.crypto().sha256(&data).to_bytes();
+    }
+
+    hash == *root
+}
+
 #[cfg(test)]
 mod test {
-    use curve::{PiecewiseLinear, SaturatingLinear, Step};
+    use curve::{Cliff, PiecewiseLinear, SaturatingLinear, Step};
     use soroban_sdk::testutils::Address as _;
     use soroban_sdk::vec;
 
@@ -75,14 +119,20 @@ mod test {
             VestingSchedule {
                 recipient: address1.clone(),
                 curve: Curve::Constant(1),
+                revocable: None,
+                token: None,
             },
             VestingSchedule {
                 recipient: address2.clone(),
                 curve: Curve::Constant(1),
+                revocable: None,
+                token: None,
             },
             VestingSchedule {
                 recipient: address3.clone(),
                 curve: Curve::Constant(1),
+                revocable: None,
+                token: None,
             },
         ];
 
@@ -100,14 +150,20 @@ mod test {
             VestingSchedule {
                 recipient: duplicate_address.clone(),
                 curve: Curve::Constant(1),
+                revocable: None,
+                token: None,
             },
             VestingSchedule {
                 recipient: Address::generate(&env),
                 curve: Curve::Constant(1),
+                revocable: None,
+                token: None,
             },
             VestingSchedule {
                 recipient: duplicate_address,
                 curve: Curve::Constant(1),
+                revocable: None,
+                token: None,
             },
         ];
 
@@ -147,6 +203,35 @@ mod test {
         assert_eq!(validate_vesting_schedule(&env, &curve), Ok(150));
     }
 
+    #[test]
+    fn validate_cliff_vesting() {
+        let env = Env::default();
+        let curve = Curve::Cliff(Cliff {
+            start_y: 1_000,
+            cliff_x: 365,
+            cliff_y: 750,
+            end_x: 1_095,
+            end_y: 0,
+        });
+
+        assert_eq!(validate_vesting_schedule(&env, &curve), Ok(1_000));
+    }
+
+    #[test]
+    #[should_panic(expected = "Error(Contract, #713)")]
+    fn cliff_schedule_fails_when_not_fully_vested() {
+        let env = Env::default();
+        let curve = Curve::Cliff(Cliff {
+            start_y: 1_000,
+            cliff_x: 365,
+            cliff_y: 750,
+            end_x: 1_095,
+            end_y: 10,
+        });
+
+        validate_vesting_schedule(&env, &curve).unwrap();
+    }
+
     #[test]
     #[should_panic(expected = "Error(Contract, #713)")]
     fn saturating_linear_schedule_fails_when_not_fully_vested() {
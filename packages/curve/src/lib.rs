@@ -49,6 +49,10 @@ pub enum Curve {
 
     /// Curve with different slopes
     PiecewiseLinear(PiecewiseLinear),
+
+    /// Constant curve that jumps at a given time and
+    /// then changes linearly to a saturated value.
+    Cliff(Cliff),
 }
 
 impl Curve {
@@ -66,6 +70,21 @@ impl Curve {
     pub fn constant(y: u128) -> Self {
         Curve::Constant(y)
     }
+
+    /// Ctor for cliff curve
+    pub fn cliff(
+        start_y: u128,
+        (cliff_x, cliff_y): (u64, u128),
+        (end_x, end_y): (u64, u128),
+    ) -> Self {
+        Curve::Cliff(Cliff {
+            start_y,
+            cliff_x,
+            cliff_y,
+            end_x,
+            end_y,
+        })
+    }
 }
 
 impl Curve {
@@ -75,6 +94,7 @@ impl Curve {
             Curve::Constant(y) => *y,
             Curve::SaturatingLinear(s) => s.value(x),
             Curve::PiecewiseLinear(p) => p.value(x),
+            Curve::Cliff(c) => c.value(x),
         }
     }
 
@@ -84,6 +104,7 @@ impl Curve {
             Curve::Constant(_) => 1,
             Curve::SaturatingLinear(_) => 2,
             Curve::PiecewiseLinear(pl) => pl.steps.len(),
+            Curve::Cliff(_) => 3,
         }
     }
 
@@ -94,6 +115,7 @@ impl Curve {
             Curve::Constant(_) => Ok(()),
             Curve::SaturatingLinear(s) => s.validate(),
             Curve::PiecewiseLinear(p) => p.validate(),
+            Curve::Cliff(c) => c.validate(),
         }
     }
 
@@ -103,6 +125,7 @@ impl Curve {
             Curve::Constant(_) => Ok(()),
             Curve::SaturatingLinear(s) => s.validate_monotonic_increasing(),
             Curve::PiecewiseLinear(p) => p.validate_monotonic_increasing(),
+            Curve::Cliff(c) => c.validate_monotonic_increasing(),
         }
     }
 
@@ -112,6 +135,7 @@ impl Curve {
             Curve::Constant(_) => Ok(()),
             Curve::SaturatingLinear(s) => s.validate_monotonic_decreasing(),
             Curve::PiecewiseLinear(p) => p.validate_monotonic_decreasing(),
+            Curve::Cliff(c) => c.validate_monotonic_decreasing(),
         }
     }
 
@@ -130,6 +154,7 @@ impl Curve {
             Curve::Constant(y) => (*y, *y),
             Curve::SaturatingLinear(sat) => sat.range(),
             Curve::PiecewiseLinear(p) => p.range(),
+            Curve::Cliff(c) => c.range(),
         }
     }
 
@@ -155,6 +180,13 @@ impl Curve {
                 }
                 Curve::PiecewiseLinear(PiecewiseLinear { steps: new_steps })
             }
+            Curve::Cliff(c) => Curve::Cliff(Cliff {
+                start_y: c.start_y + const_y,
+                cliff_x: c.cliff_x,
+                cliff_y: c.cliff_y + const_y,
+                end_x: c.end_x,
+                end_y: c.end_y + const_y,
+            }),
         }
     }
 
@@ -181,6 +213,16 @@ impl Curve {
             (Curve::PiecewiseLinear(pl1), Curve::PiecewiseLinear(pl2)) => {
                 Curve::PiecewiseLinear(pl1.combine(env, pl2))
             }
+            // a cliff is converted to piecewise linear as well, see `from_cliff`
+            (Curve::Cliff(c), curve) | (curve, Curve::Cliff(c)) => {
+                let pl = match curve {
+                    Curve::Cliff(other) => from_cliff(env, other),
+                    Curve::SaturatingLinear(sl) => from_saturating_linear(env, sl),
+                    Curve::PiecewiseLinear(pl) => pl.clone(),
+                    Curve::Constant(_) => unreachable!(),
+                };
+                Curve::PiecewiseLinear(from_cliff(env, c).combine(env, &pl))
+            }
         }
     }
 
@@ -189,6 +231,7 @@ impl Curve {
             Curve::Constant { .. } => None,
             Curve::SaturatingLinear(sl) => sl.end(),
             Curve::PiecewiseLinear(pl) => pl.end(),
+            Curve::Cliff(c) => c.end(),
         }
     }
 }
@@ -267,6 +310,74 @@ impl SaturatingLinear {
     }
 }
 
+/// Cliff
+///
+/// start_y for all x < cliff_x, a jump to cliff_y at cliff_x, linear in between
+/// cliff_x and end_x, and end_y for all x >= end_x
+#[contracttype]
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Cliff {
+    /// value before the cliff
+    pub start_y: u128,
+    /// time of the cliff
+    pub cliff_x: u64,
+    /// value right at the cliff
+    pub cliff_y: u128,
+    /// time when curve has fully saturated
+    pub end_x: u64,
+    /// value at saturated time
+    pub end_y: u128,
+}
+
+impl Cliff {
+    /// provides y = f(x) evaluation
+    pub fn value(&self, x: u64) -> u128 {
+        match (x < self.cliff_x, x > self.end_x) {
+            (true, _) => self.start_y,
+            (_, true) => self.end_y,
+            _ => interpolate((self.cliff_x, self.cliff_y), (self.end_x, self.end_y), x),
+        }
+    }
+
+    /// general sanity checks on input values to ensure this is valid.
+    /// these checks should be included by the other validate_* functions
+    pub fn validate(&self) -> Result<(), CurveError> {
+        if self.end_x <= self.cliff_x {
+            return Err(CurveError::PointsOutOfOrder);
+        }
+        Ok(())
+    }
+
+    /// returns an error if there is ever x2 > x1 such that value(x2) < value(x1)
+    pub fn validate_monotonic_increasing(&self) -> Result<(), CurveError> {
+        self.validate()?;
+        if self.cliff_y < self.start_y || self.end_y < self.cliff_y {
+            return Err(CurveError::MonotonicDecreasing);
+        }
+        Ok(())
+    }
+
+    /// returns an error if there is ever x2 > x1 such that value(x1) < value(x2)
+    pub fn validate_monotonic_decreasing(&self) -> Result<(), CurveError> {
+        self.validate()?;
+        if self.cliff_y > self.start_y || self.end_y > self.cliff_y {
+            return Err(CurveError::MonotonicIncreasing);
+        }
+        Ok(())
+    }
+
+    /// return (min, max) that can ever be returned from value. These could potentially be 0 and u64::MAX
+    pub fn range(&self) -> (u128, u128) {
+        let min = self.start_y.min(self.cliff_y).min(self.end_y);
+        let max = self.start_y.max(self.cliff_y).max(self.end_y);
+        (min, max)
+    }
+
+    fn end(&self) -> Option<u64> {
+        Some(self.end_x)
+    }
+}
+
 // this requires min_x < x < max_x to have been previously validated
 fn interpolate((min_x, min_y): (u64, u128), (max_x, max_y): (u64, u128), x: u64) -> u128 {
     if max_y > min_y {
@@ -478,6 +589,28 @@ pub fn from_saturating_linear(env: &Env, sl: &SaturatingLinear) -> PiecewiseLine
     }
 }
 
+/// Time is discrete, so a jump at `cliff_x` is exactly represented by a step
+/// one second before it that still holds the start value.
+pub fn from_cliff(env: &Env, cliff: &Cliff) -> PiecewiseLinear {
+    let mut steps = vec![env];
+    if cliff.cliff_x > 0 {
+        steps.push_back(Step {
+            time: cliff.cliff_x - 1,
+            value: cliff.start_y,
+        });
+    }
+    steps.push_back(Step {
+        time: cliff.cliff_x,
+        value: cliff.cliff_y,
+    });
+    steps.push_back(Step {
+        time: cliff.end_x,
+        value: cliff.end_y,
+    });
+
+    PiecewiseLinear { steps }
+}
+
 enum Shape {
     // If there is only one point, or all have same value
     Constant,
@@ -940,6 +1073,115 @@ mod tests {
         test_combine(&sl, &sl, [0, 10, 20, 50, 100, 110, 120], 2);
     }
 
+    #[test]
+    fn test_cliff_value() {
+        // 1_000 locked, 250 unlock at the cliff, the rest linearly afterwards
+        let curve = Curve::cliff(1_000, (365, 750), (1_095, 0));
+        curve.validate_monotonic_decreasing().unwrap();
+        assert_eq!(
+            curve.validate_monotonic_increasing().unwrap_err(),
+            CurveError::MonotonicDecreasing
+        );
+
+        assert_eq!(curve.value(0), 1_000);
+        assert_eq!(curve.value(364), 1_000);
+        assert_eq!(curve.value(365), 750);
+        assert_eq!(curve.value(730), 375);
+        assert_eq!(curve.value(1_095), 0);
+        assert_eq!(curve.value(2_000), 0);
+        assert_eq!(curve.range(), (0, 1_000));
+        assert_eq!(curve.end(), Some(1_095));
+        assert_eq!(curve.size(), 3);
+    }
+
+    #[test]
+    fn test_cliff_validation() {
+        let increasing = Curve::cliff(0, (10, 50), (20, 100));
+        increasing.validate_monotonic_increasing().unwrap();
+        assert_eq!(
+            increasing.validate_monotonic_decreasing().unwrap_err(),
+            CurveError::MonotonicIncreasing
+        );
+
+        // linear part goes back up after the cliff
+        let not_monotonic = Curve::cliff(100, (10, 50), (20, 80));
+        assert_eq!(
+            not_monotonic.validate_monotonic_decreasing().unwrap_err(),
+            CurveError::MonotonicIncreasing
+        );
+        assert_eq!(
+            not_monotonic.validate_monotonic_increasing().unwrap_err(),
+            CurveError::MonotonicDecreasing
+        );
+
+        let out_of_order = Curve::cliff(100, (20, 50), (20, 0));
+        assert_eq!(
+            out_of_order.validate().unwrap_err(),
+            CurveError::PointsOutOfOrder
+        );
+    }
+
+    #[test]
+    fn test_cliff_to_piecewise() {
+        let env = Env::default();
+        let cliff = Cliff {
+            start_y: 1_000,
+            cliff_x: 100,
+            cliff_y: 600,
+            end_x: 200,
+            end_y: 0,
+        };
+
+        let converted = from_cliff(&env, &cliff);
+        assert_eq!(converted.steps.len(), 3);
+        for x in [0, 98, 99, 100, 101, 150, 199, 200, 300] {
+            assert_eq!(converted.value(x), cliff.value(x));
+        }
+
+        // nothing can happen before a cliff at time 0
+        let cliff = Cliff {
+            cliff_x: 0,
+            ..cliff
+        };
+        let converted = from_cliff(&env, &cliff);
+        assert_eq!(converted.steps.len(), 2);
+        for x in [0, 1, 100, 200] {
+            assert_eq!(converted.value(x), cliff.value(x));
+        }
+    }
+
+    #[test]
+    fn test_combine_cliff() {
+        let env = Env::default();
+        let c = Curve::Constant(10);
+        let cliff = Curve::cliff(1_000, (100, 600), (200, 0));
+        let sl = Curve::SaturatingLinear(SaturatingLinear {
+            min_x: 50,
+            min_y: 500,
+            max_x: 150,
+            max_y: 0,
+        });
+        let pl = Curve::PiecewiseLinear(PiecewiseLinear {
+            steps: vec![
+                &env,
+                Step {
+                    time: 99,
+                    value: 300,
+                },
+                Step {
+                    time: 300,
+                    value: 0,
+                },
+            ],
+        });
+
+        test_combine(&cliff, &c, [0, 99, 100, 150, 200, 250], 3);
+        test_combine(&cliff, &sl, [0, 50, 99, 100, 125, 150, 200, 250], 5);
+        // the step before the cliff is deduplicated
+        test_combine(&cliff, &pl, [0, 98, 99, 100, 200, 250, 300], 4);
+        test_combine(&cliff, &cliff, [0, 99, 100, 150, 200, 250], 3);
+    }
+
     #[test]
     fn test_complexity_validation() {
         let env = Env::default();